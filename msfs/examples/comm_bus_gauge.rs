@@ -20,7 +20,7 @@ impl ToggleGauge {
 
     fn send(&self, v: i32) {
         let payload = v.to_le_bytes();
-        let _ = commbus_call(
+        let _ = try_commbus_call(
             EVT_TOGGLE,
             &payload,
             BroadcastFlags::JS | BroadcastFlags::WASM,
@@ -21,7 +21,7 @@ impl CommbusStateSystem {
         let mut l_for_cb =
             LVar::new("L:INFINITY_DEMO_ENABLED", "Bool").expect("LVar create failed");
 
-        let sub = Subscription::subscribe(EVT_CMD, move |bytes| {
+        let sub = Subscription::try_subscribe(EVT_CMD, move |bytes| {
             let cmd = bytes.get(0).copied().unwrap_or(0);
             let cur = l_for_cb.get().unwrap_or(0.0);
 
@@ -53,7 +53,7 @@ impl CommbusStateSystem {
         let enabled_u8 = (self.l_enabled.get().unwrap_or(0.0) >= 0.5) as u8;
         let payload = [enabled_u8];
 
-        let _ = commbus_call(EVT_STATE, &payload, BroadcastFlags::ALL);
+        let _ = try_commbus_call(EVT_STATE, &payload, BroadcastFlags::ALL);
     }
 }
 
@@ -18,7 +18,7 @@ impl NetworkFetchSystem {
         let mut l_for_cb = LVar::new("L:INFINITY_FETCH_LAST_OK", "Bool")
             .expect("Failed to create LVar for callback");
 
-        let sub = Subscription::subscribe(EVT_FETCH, move |_bytes| {
+        let sub = Subscription::try_subscribe(EVT_FETCH, move |_bytes| {
             let params = HttpParams {
                 headers: vec![
                     "Accept: application/json".to_string(),
@@ -26,6 +26,7 @@ impl NetworkFetchSystem {
                 ],
                 post_field: None,
                 body: vec![],
+                timeout: None,
             };
 
             let _ = http_request(
@@ -36,7 +37,7 @@ impl NetworkFetchSystem {
                     let ok = (resp.error_code == 0) as i32;
                     let _ = l_for_cb.set(ok as f64);
 
-                    let _ = commbus_call(
+                    let _ = try_commbus_call(
                         EVT_CONFIG,
                         &resp.data,
                         BroadcastFlags::JS | BroadcastFlags::WASM,
@@ -28,6 +28,7 @@ impl TelemetryGauge {
             ],
             post_field: None,
             body: blob,
+            timeout: None,
         };
 
         // We currently cannot pass var ownership, so we create a new one for the callback to use. This has no effect on the gauge since it's just a handle to an LVar with a known name.
@@ -41,7 +42,7 @@ impl TelemetryGauge {
             move |resp| {
                 let _ = l_for_cb.set(0.0);
 
-                let _ = commbus_call(
+                let _ = try_commbus_call(
                     EVT_POST_RESULT,
                     &resp.data,
                     BroadcastFlags::JS | BroadcastFlags::WASM,
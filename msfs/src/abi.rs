@@ -1,12 +1,45 @@
+use crate::context::Context;
 use crate::sys::*;
+use crate::types::{GaugeDraw, GaugeInstall, SystemInstall};
 
 pub struct Fs2024;
 
+/// Raw types the exported entry points receive, and how to turn them into the
+/// types `System` / `Gauge` see.
+///
+/// [`Fs2024`] is the simulator's own ABI. A native test harness can implement this
+/// with its own context handle and install/draw structs and export against it via
+/// `export_gauge_abi!` / `export_system_abi!`, driving the same gauge code.
 pub trait Abi {
-    type Context;
+    type Context: Copy;
     type SystemInstall;
     type GaugeInstall;
     type GaugeDraw;
+
+    /// # Safety
+    /// `raw` must be whatever the host passed to the exported entry point.
+    unsafe fn context(raw: Self::Context) -> Context;
+
+    /// # Safety
+    /// `raw` must be valid for reads and writes for the duration of the call.
+    unsafe fn with_system_install<R>(
+        raw: *mut Self::SystemInstall,
+        f: impl FnOnce(&mut SystemInstall) -> R,
+    ) -> R;
+
+    /// # Safety
+    /// `raw` must be valid for reads and writes for the duration of the call.
+    unsafe fn with_gauge_install<R>(
+        raw: *mut Self::GaugeInstall,
+        f: impl FnOnce(&mut GaugeInstall) -> R,
+    ) -> R;
+
+    /// # Safety
+    /// `raw` must be valid for reads and writes for the duration of the call.
+    unsafe fn with_gauge_draw<R>(
+        raw: *mut Self::GaugeDraw,
+        f: impl FnOnce(&mut GaugeDraw) -> R,
+    ) -> R;
 }
 
 impl Abi for Fs2024 {
@@ -14,4 +47,33 @@ impl Abi for Fs2024 {
     type SystemInstall = sSystemInstallData;
     type GaugeInstall = sGaugeInstallData;
     type GaugeDraw = sGaugeDrawData;
+
+    #[inline(always)]
+    unsafe fn context(raw: FsContext) -> Context {
+        unsafe { Context::from_raw(raw) }
+    }
+
+    #[inline(always)]
+    unsafe fn with_system_install<R>(
+        raw: *mut sSystemInstallData,
+        f: impl FnOnce(&mut SystemInstall) -> R,
+    ) -> R {
+        f(unsafe { &mut *raw })
+    }
+
+    #[inline(always)]
+    unsafe fn with_gauge_install<R>(
+        raw: *mut sGaugeInstallData,
+        f: impl FnOnce(&mut GaugeInstall) -> R,
+    ) -> R {
+        f(unsafe { &mut *raw })
+    }
+
+    #[inline(always)]
+    unsafe fn with_gauge_draw<R>(
+        raw: *mut sGaugeDrawData,
+        f: impl FnOnce(&mut GaugeDraw) -> R,
+    ) -> R {
+        f(unsafe { &mut *raw })
+    }
 }
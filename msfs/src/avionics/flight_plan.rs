@@ -0,0 +1,179 @@
+//! Keeping an aircraft's own FMS in sync with the sim's flight plan.
+//!
+//! The stock GPS and FMS units fly the plan the sim has active: the one filed
+//! in the world map, or one loaded through SimConnect. [`FlightPlanSync`]
+//! reports when a plan is activated (with the path of its `.PLN` file) or
+//! cleared, and [`load`](FlightPlanSync::load) activates a plan so the stock
+//! units pick it up. Once a plan is active, [`crossfill`] reads the waypoints
+//! the stock units are flying from [`crate::vars::gps`].
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::avionics::flight_plan::{FlightPlanEvent, FlightPlanSync, crossfill};
+//!
+//! let mut sync = FlightPlanSync::new().expect("SimConnect unavailable");
+//!
+//! // in update():
+//! for event in sync.poll() {
+//!     match event {
+//!         FlightPlanEvent::Activated { path } => {
+//!             println!("plan {path}");
+//!             for wp in crossfill()? {
+//!                 println!("  {} {:.4} {:.4}", wp.ident, wp.latitude_deg, wp.longitude_deg);
+//!             }
+//!         }
+//!         FlightPlanEvent::Deactivated => println!("plan cleared"),
+//!     }
+//! }
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use crate::simconnect::{self, Inbox, read_u32, with_client};
+use crate::sys::{
+    DWORD, SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME, SimConnect_FlightPlanLoad,
+    SimConnect_SubscribeToSystemEvent, SimConnect_UnsubscribeFromSystemEvent,
+};
+use crate::vars::{
+    VarResult,
+    gps::{self, Waypoint},
+};
+use std::ffi::CString;
+
+// `SIMCONNECT_RECV_EVENT` is packed: dwSize dwVersion dwID uGroupID uEventID
+// dwData. `SIMCONNECT_RECV_EVENT_FILENAME` adds char szFileName[MAX_PATH].
+const EVENT: usize = 16;
+const FILE_NAME: usize = 24;
+const MAX_PATH: usize = 260;
+
+/// A change of the sim's active flight plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlightPlanEvent {
+    /// A plan was filed or loaded. `path` is its `.PLN` file.
+    Activated { path: String },
+    /// The active plan was cleared.
+    Deactivated,
+}
+
+/// Flight plan activation, from the `FlightPlanActivated` and
+/// `FlightPlanDeactivated` system events on the module's shared SimConnect
+/// client.
+pub struct FlightPlanSync {
+    inbox: Inbox,
+    activated: DWORD,
+    deactivated: DWORD,
+    path: Option<String>,
+}
+
+impl FlightPlanSync {
+    /// Start listening. `None` if SimConnect is unavailable or refused the
+    /// subscriptions.
+    pub fn new() -> Option<Self> {
+        let inbox = Inbox::new()?;
+        let (activated, deactivated) = with_client(|client| {
+            let activated = client.next_id();
+            let deactivated = client.next_id();
+            unsafe {
+                if SimConnect_SubscribeToSystemEvent(
+                    client.handle(),
+                    activated,
+                    c"FlightPlanActivated".as_ptr(),
+                ) < 0
+                {
+                    return None;
+                }
+                if SimConnect_SubscribeToSystemEvent(
+                    client.handle(),
+                    deactivated,
+                    c"FlightPlanDeactivated".as_ptr(),
+                ) < 0
+                {
+                    SimConnect_UnsubscribeFromSystemEvent(client.handle(), activated);
+                    return None;
+                }
+            }
+            Some((activated, deactivated))
+        })??;
+        inbox.claim(activated);
+        inbox.claim(deactivated);
+        Some(Self {
+            inbox,
+            activated,
+            deactivated,
+            path: None,
+        })
+    }
+
+    /// Changes since the last call, oldest first.
+    pub fn poll(&mut self) -> Vec<FlightPlanEvent> {
+        let events: Vec<_> = self
+            .inbox
+            .receive()
+            .iter()
+            .filter_map(|bytes| self.parse(bytes))
+            .collect();
+        for event in &events {
+            self.path = match event {
+                FlightPlanEvent::Activated { path } => Some(path.clone()),
+                FlightPlanEvent::Deactivated => None,
+            };
+        }
+        events
+    }
+
+    /// The `.PLN` file of the active plan, if one was activated since this
+    /// was created.
+    pub fn active_path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Load and activate the plan in `path`, a `.PLN` file given without its
+    /// extension. The stock units fly it once
+    /// [`Activated`](FlightPlanEvent::Activated) arrives.
+    pub fn load(&self, path: &str) -> bool {
+        let Ok(path) = CString::new(path) else {
+            return false;
+        };
+        with_client(|client| unsafe {
+            SimConnect_FlightPlanLoad(client.handle(), path.as_ptr()) >= 0
+        })
+        .unwrap_or(false)
+    }
+
+    fn parse(&self, bytes: &[u8]) -> Option<FlightPlanEvent> {
+        let id = simconnect::recv_id(bytes)? as i32;
+        let event = read_u32(bytes, EVENT)?;
+        if id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME as i32
+            && event == self.activated as u32
+        {
+            let name = bytes.get(FILE_NAME..)?;
+            let name = &name[..name.len().min(MAX_PATH)];
+            let name = name.split(|&b| b == 0).next().unwrap_or_default();
+            Some(FlightPlanEvent::Activated {
+                path: String::from_utf8_lossy(name).into_owned(),
+            })
+        } else if id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as i32
+            && event == self.deactivated as u32
+        {
+            Some(FlightPlanEvent::Deactivated)
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for FlightPlanSync {
+    fn drop(&mut self) {
+        simconnect::try_with_client(|client| unsafe {
+            SimConnect_UnsubscribeFromSystemEvent(client.handle(), self.activated);
+            SimConnect_UnsubscribeFromSystemEvent(client.handle(), self.deactivated);
+        });
+    }
+}
+
+/// The waypoints of the plan the stock units are flying, in order, to fill
+/// an aircraft's own FMS with. The active leg is
+/// [`FlightPlan::active_index`](gps::FlightPlan::active_index).
+pub fn crossfill() -> VarResult<Vec<Waypoint>> {
+    gps::waypoints()
+}
@@ -0,0 +1,168 @@
+//! Typed access to the interfaces exposed by the stock avionics.
+//!
+//! The default G1000 (and the NXi that replaces it) and the GNS 430/530 units
+//! expose their bezel keys and knobs as `H:` events. These helpers build the
+//! event names so aircraft can drive the default units without copying magic
+//! strings around. Flight plan sync and cross-fill are in [`flight_plan`].
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::avionics::{G1000Display, G1000Key, GnsKey, GnsUnit};
+//!
+//! msfs::avionics::g1000_press(G1000Display::Mfd, G1000Key::DirectTo);
+//! msfs::avionics::gns_press(GnsUnit::Gns530, GnsKey::RightLargeKnobRight);
+//! ```
+
+pub mod flight_plan;
+
+use crate::events::fire_h_event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum G1000Display {
+    Pfd,
+    Mfd,
+}
+
+impl G1000Display {
+    #[inline]
+    fn prefix(self) -> &'static str {
+        match self {
+            G1000Display::Pfd => "AS1000_PFD",
+            G1000Display::Mfd => "AS1000_MFD",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum G1000Key {
+    /// Softkey 1..=12, left to right.
+    Softkey(u8),
+    FmsUpperInc,
+    FmsUpperDec,
+    FmsLowerInc,
+    FmsLowerDec,
+    FmsUpperPush,
+    RangeInc,
+    RangeDec,
+    DirectTo,
+    Menu,
+    Fpl,
+    Proc,
+    Clr,
+    ClrLong,
+    Ent,
+}
+
+impl G1000Key {
+    fn suffix(self) -> Option<String> {
+        let s = match self {
+            G1000Key::Softkey(n @ 1..=12) => return Some(format!("SOFTKEYS_{n}")),
+            G1000Key::Softkey(_) => return None,
+            G1000Key::FmsUpperInc => "FMS_Upper_INC",
+            G1000Key::FmsUpperDec => "FMS_Upper_DEC",
+            G1000Key::FmsLowerInc => "FMS_Lower_INC",
+            G1000Key::FmsLowerDec => "FMS_Lower_DEC",
+            G1000Key::FmsUpperPush => "FMS_Upper_PUSH",
+            G1000Key::RangeInc => "RANGE_INC",
+            G1000Key::RangeDec => "RANGE_DEC",
+            G1000Key::DirectTo => "DIRECTTO",
+            G1000Key::Menu => "MENU_Push",
+            G1000Key::Fpl => "FPL_Push",
+            G1000Key::Proc => "PROC_Push",
+            G1000Key::Clr => "CLR",
+            G1000Key::ClrLong => "CLR_Long",
+            G1000Key::Ent => "ENT_Push",
+        };
+        Some(s.to_string())
+    }
+}
+
+/// `H:` event name for a G1000 key, or `None` for an out-of-range softkey.
+pub fn g1000_event(display: G1000Display, key: G1000Key) -> Option<String> {
+    key.suffix()
+        .map(|suffix| format!("{}_{suffix}", display.prefix()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GnsUnit {
+    Gns430,
+    Gns530,
+}
+
+impl GnsUnit {
+    #[inline]
+    fn prefix(self) -> &'static str {
+        match self {
+            GnsUnit::Gns430 => "AS430",
+            GnsUnit::Gns530 => "AS530",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GnsKey {
+    DirectTo,
+    Menu,
+    Fpl,
+    Proc,
+    Msg,
+    Obs,
+    Vnav,
+    Clr,
+    ClrLong,
+    Ent,
+    RangeZoom,
+    RangeDezoom,
+    LeftLargeKnobLeft,
+    LeftLargeKnobRight,
+    LeftSmallKnobLeft,
+    LeftSmallKnobRight,
+    RightLargeKnobLeft,
+    RightLargeKnobRight,
+    RightSmallKnobLeft,
+    RightSmallKnobRight,
+    RightSmallKnobPush,
+}
+
+impl GnsKey {
+    fn suffix(self) -> &'static str {
+        match self {
+            GnsKey::DirectTo => "DRCT_Push",
+            GnsKey::Menu => "MENU_Push",
+            GnsKey::Fpl => "FPL_Push",
+            GnsKey::Proc => "PROC_Push",
+            GnsKey::Msg => "MSG_Push",
+            GnsKey::Obs => "OBS_Push",
+            GnsKey::Vnav => "VNAV_Push",
+            GnsKey::Clr => "CLR_Push",
+            GnsKey::ClrLong => "CLR_Push_Long",
+            GnsKey::Ent => "ENT_Push",
+            GnsKey::RangeZoom => "RNG_Zoom",
+            GnsKey::RangeDezoom => "RNG_Dezoom",
+            GnsKey::LeftLargeKnobLeft => "LeftLargeKnob_Left",
+            GnsKey::LeftLargeKnobRight => "LeftLargeKnob_Right",
+            GnsKey::LeftSmallKnobLeft => "LeftSmallKnob_Left",
+            GnsKey::LeftSmallKnobRight => "LeftSmallKnob_Right",
+            GnsKey::RightLargeKnobLeft => "RightLargeKnob_Left",
+            GnsKey::RightLargeKnobRight => "RightLargeKnob_Right",
+            GnsKey::RightSmallKnobLeft => "RightSmallKnob_Left",
+            GnsKey::RightSmallKnobRight => "RightSmallKnob_Right",
+            GnsKey::RightSmallKnobPush => "RightSmallKnob_Push",
+        }
+    }
+}
+
+/// `H:` event name for a GNS key or knob.
+pub fn gns_event(unit: GnsUnit, key: GnsKey) -> String {
+    format!("{}_{}", unit.prefix(), key.suffix())
+}
+
+/// Press a G1000 key. Returns `false` if the key is invalid or the event could not be sent.
+pub fn g1000_press(display: G1000Display, key: G1000Key) -> bool {
+    g1000_event(display, key).is_some_and(|name| fire_h_event(&name))
+}
+
+/// Press a GNS key or turn a knob one detent.
+pub fn gns_press(unit: GnsUnit, key: GnsKey) -> bool {
+    fire_h_event(&gns_event(unit, key))
+}
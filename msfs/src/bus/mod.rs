@@ -0,0 +1,118 @@
+//! In-process publish/subscribe between gauges and systems of one module.
+//!
+//! Everything compiled into the same WASM module shares this bus, so local
+//! messages can be plain Rust values instead of going through the comm bus
+//! and its serialization. Messages are routed by type: every live
+//! [`Receiver<T>`] gets a clone of each `T` published after it subscribed.
+//!
+//! # Examples
+//! ```no_run
+//! #[derive(Clone)]
+//! struct MasterCaution(bool);
+//!
+//! // in the warning system's init():
+//! let caution = msfs::bus::subscribe::<MasterCaution>();
+//!
+//! // anywhere else in the module:
+//! msfs::bus::publish(MasterCaution(true));
+//!
+//! // in the warning system's update():
+//! for MasterCaution(on) in caution.drain() {
+//!     println!("master caution: {on}");
+//! }
+//! ```
+//!
+//! Receivers queue until drained, so one that is kept but never read grows
+//! without bound. Dropping a receiver unsubscribes it.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::{Rc, Weak},
+};
+
+type Queue<T> = RefCell<VecDeque<T>>;
+
+thread_local! {
+    // One `Vec<Weak<Queue<T>>>` per message type.
+    static TOPICS: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+fn with_topic<T: 'static, R>(f: impl FnOnce(&mut Vec<Weak<Queue<T>>>) -> R) -> R {
+    TOPICS.with(|t| {
+        let mut topics = t.borrow_mut();
+        let topic = topics
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Vec::<Weak<Queue<T>>>::new()))
+            .downcast_mut()
+            .expect("bus topic keyed by its type");
+        f(topic)
+    })
+}
+
+/// Messages of type `T` published since [`subscribe`].
+pub struct Receiver<T> {
+    queue: Rc<Queue<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// The oldest undelivered message.
+    pub fn try_recv(&self) -> Option<T> {
+        self.queue.borrow_mut().pop_front()
+    }
+
+    /// Every undelivered message, oldest first.
+    pub fn drain(&self) -> std::vec::IntoIter<T> {
+        // Collected so handlers may publish while iterating.
+        let messages: Vec<T> = self.queue.borrow_mut().drain(..).collect();
+        messages.into_iter()
+    }
+
+    /// The newest message, dropping any older ones.
+    pub fn latest(&self) -> Option<T> {
+        let mut queue = self.queue.borrow_mut();
+        let latest = queue.pop_back();
+        queue.clear();
+        latest
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.borrow().is_empty()
+    }
+}
+
+/// Start receiving messages of type `T`.
+pub fn subscribe<T: Clone + 'static>() -> Receiver<T> {
+    let queue = Rc::new(RefCell::new(VecDeque::new()));
+    with_topic::<T, _>(|topic| topic.push(Rc::downgrade(&queue)));
+    Receiver { queue }
+}
+
+/// Send `msg` to every receiver of `T`. Returns how many got it.
+pub fn publish<T: Clone + 'static>(msg: T) -> usize {
+    let queues = with_topic::<T, _>(|topic| {
+        topic.retain(|q| q.strong_count() > 0);
+        topic.iter().filter_map(Weak::upgrade).collect::<Vec<_>>()
+    });
+    let Some((last, rest)) = queues.split_last() else {
+        return 0;
+    };
+    for queue in rest {
+        queue.borrow_mut().push_back(msg.clone());
+    }
+    last.borrow_mut().push_back(msg);
+    queues.len()
+}
+
+/// Number of live receivers of `T`.
+pub fn subscribers<T: 'static>() -> usize {
+    with_topic::<T, _>(|topic| {
+        topic.retain(|q| q.strong_count() > 0);
+        topic.len()
+    })
+}
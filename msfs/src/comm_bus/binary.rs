@@ -0,0 +1,196 @@
+//! Compact binary comm bus messages for WASM-to-WASM traffic.
+//!
+//! Payloads are a 6-byte [`Header`] (schema id and version, little-endian)
+//! followed by the [postcard](https://docs.rs/postcard) encoding of the
+//! message, which is far smaller and cheaper to produce than JSON for
+//! high-frequency telemetry. JS can't read them; use [`super::typed`] or
+//! `#[derive(CommBusMessage)]` for traffic to the panel. Needs the `postcard`
+//! feature.
+//!
+//! The header lets a receiver reject payloads from a module built against a
+//! different message layout instead of misreading them: bump
+//! [`BinaryMessage::VERSION`] whenever the struct changes.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, binary::{self, BinaryMessage}};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct AirData {
+//!     ias_kt: f32,
+//!     alt_ft: f32,
+//!     vs_fpm: f32,
+//! }
+//!
+//! impl BinaryMessage for AirData {
+//!     const SCHEMA_ID: u32 = binary::schema_id("infinity/air_data");
+//!     const VERSION: u16 = 2;
+//! }
+//!
+//! let data = AirData { ias_kt: 250.0, alt_ft: 12000.0, vs_fpm: -500.0 };
+//! binary::publish("ADC_DATA", &data, BroadcastFlags::ALL_WASM)?;
+//!
+//! let _sub = binary::subscribe("ADC_DATA", |data: AirData| {
+//!     println!("{} kt", data.ias_kt);
+//! })?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusError, CommBusResult, Subscription, try_call};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+
+/// Size of the [`Header`] in bytes.
+pub const HEADER_LEN: usize = 6;
+
+/// A message with a fixed binary identity.
+pub trait BinaryMessage: Serialize + DeserializeOwned {
+    /// Identifies the message type, e.g. [`schema_id`] of a stable name.
+    const SCHEMA_ID: u32;
+    /// Layout version; bump it when fields change.
+    const VERSION: u16 = 1;
+}
+
+/// FNV-1a of `name`, for [`BinaryMessage::SCHEMA_ID`].
+pub const fn schema_id(name: &str) -> u32 {
+    super::envelope::hash(name)
+}
+
+/// The envelope in front of every binary payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Header {
+    pub schema_id: u32,
+    pub version: u16,
+}
+
+impl Header {
+    pub fn of<T: BinaryMessage>() -> Self {
+        Self {
+            schema_id: T::SCHEMA_ID,
+            version: T::VERSION,
+        }
+    }
+
+    pub fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut out = [0; HEADER_LEN];
+        out[..4].copy_from_slice(&self.schema_id.to_le_bytes());
+        out[4..].copy_from_slice(&self.version.to_le_bytes());
+        out
+    }
+
+    /// The header at the start of `payload`, `None` if it's too short.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        Some(Self {
+            schema_id: u32::from_le_bytes(payload.get(..4)?.try_into().ok()?),
+            version: u16::from_le_bytes(payload.get(4..HEADER_LEN)?.try_into().ok()?),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum BinaryError {
+    /// The comm bus rejected the call, e.g. a NUL byte in the event name.
+    Bus(CommBusError),
+    /// Shorter than a [`Header`].
+    Truncated,
+    /// The payload is a different message.
+    WrongSchema { expected: u32, found: u32 },
+    /// The payload is this message, but another layout version.
+    WrongVersion { expected: u16, found: u16 },
+    /// postcard couldn't encode or decode the body.
+    Codec(postcard::Error),
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryError::Bus(e) => e.fmt(f),
+            BinaryError::Truncated => f.write_str("payload shorter than the binary header"),
+            BinaryError::WrongSchema { expected, found } => {
+                write!(f, "schema {found:08x}, expected {expected:08x}")
+            }
+            BinaryError::WrongVersion { expected, found } => {
+                write!(f, "message version {found}, expected {expected}")
+            }
+            BinaryError::Codec(e) => write!(f, "postcard: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BinaryError::Bus(e) => Some(e),
+            BinaryError::Codec(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<CommBusError> for BinaryError {
+    fn from(e: CommBusError) -> Self {
+        BinaryError::Bus(e)
+    }
+}
+
+impl From<postcard::Error> for BinaryError {
+    fn from(e: postcard::Error) -> Self {
+        BinaryError::Codec(e)
+    }
+}
+
+/// Header followed by the postcard body.
+pub fn encode<T: BinaryMessage>(value: &T) -> Result<Vec<u8>, BinaryError> {
+    let header = Header::of::<T>().to_bytes().to_vec();
+    Ok(postcard::to_extend(value, header)?)
+}
+
+/// Check the header against `T` and decode the body.
+pub fn decode<T: BinaryMessage>(payload: &[u8]) -> Result<T, BinaryError> {
+    let header = Header::parse(payload).ok_or(BinaryError::Truncated)?;
+    if header.schema_id != T::SCHEMA_ID {
+        return Err(BinaryError::WrongSchema {
+            expected: T::SCHEMA_ID,
+            found: header.schema_id,
+        });
+    }
+    if header.version != T::VERSION {
+        return Err(BinaryError::WrongVersion {
+            expected: T::VERSION,
+            found: header.version,
+        });
+    }
+    Ok(postcard::from_bytes(&payload[HEADER_LEN..])?)
+}
+
+/// Send `value` on `event`.
+pub fn publish<T: BinaryMessage>(
+    event: &str,
+    value: &T,
+    broadcast: BroadcastFlags,
+) -> Result<bool, BinaryError> {
+    let payload = encode(value)?;
+    Ok(try_call(event, &payload, broadcast)?)
+}
+
+/// Call `cb` with every `T` received on `event`. Payloads of other messages or
+/// versions are dropped; use [`subscribe_with_errors`] to see them.
+pub fn subscribe<T: BinaryMessage + 'static>(
+    event: &str,
+    mut cb: impl FnMut(T) + 'static,
+) -> CommBusResult<Subscription> {
+    Subscription::try_subscribe(event, move |bytes| {
+        if let Ok(value) = decode(bytes) {
+            cb(value);
+        }
+    })
+}
+
+/// Like [`subscribe`], but also passes on payloads that don't decode.
+pub fn subscribe_with_errors<T: BinaryMessage + 'static>(
+    event: &str,
+    mut cb: impl FnMut(Result<T, BinaryError>) + 'static,
+) -> CommBusResult<Subscription> {
+    Subscription::try_subscribe(event, move |bytes| cb(decode(bytes)))
+}
@@ -0,0 +1,156 @@
+//! Subscriptions that buffer messages for `update()` to pull.
+//!
+//! A [`BufferedSubscription`] runs no user callback: messages are stored in a
+//! queue of bounded size, and `update()` takes them with
+//! [`try_recv`](BufferedSubscription::try_recv) or
+//! [`drain`](BufferedSubscription::drain). When a publisher outpaces the
+//! module, the [`Overflow`] policy decides what is lost, and
+//! [`stats`](BufferedSubscription::stats) counts it.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::buffered::{BufferedSubscription, Overflow};
+//!
+//! // position reports: only the newest one matters
+//! let position = BufferedSubscription::new("TRAFFIC_POS", 1, Overflow::CoalesceLatest)?;
+//! // commands: keep up to 32, refuse more until update() catches up
+//! let commands = BufferedSubscription::new("FMS_CMD", 32, Overflow::DropNewest)?;
+//!
+//! // in update():
+//! if let Some(report) = position.try_recv() { /* ... */ }
+//! for command in commands.drain() { /* ... */ }
+//! if commands.stats().dropped > 0 {
+//!     println!("dropped {} commands", commands.stats().dropped);
+//! }
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{CommBusResult, Subscription};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+/// What a full [`BufferedSubscription`] does with a new message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Drop the oldest buffered message to make room.
+    DropOldest,
+    /// Drop the new message.
+    DropNewest,
+    /// Keep only the newest message, whatever the capacity.
+    CoalesceLatest,
+}
+
+/// Counters since the subscription was created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferStats {
+    /// Messages delivered by the sim.
+    pub received: u64,
+    /// Messages lost to the [`Overflow`] policy, including coalesced ones.
+    pub dropped: u64,
+    /// Most messages buffered at once.
+    pub high_water: usize,
+}
+
+struct Buffer {
+    queue: VecDeque<Vec<u8>>,
+    capacity: usize,
+    overflow: Overflow,
+    stats: BufferStats,
+}
+
+impl Buffer {
+    fn push(&mut self, bytes: &[u8]) {
+        self.stats.received += 1;
+        match self.overflow {
+            Overflow::CoalesceLatest => {
+                self.stats.dropped += self.queue.len() as u64;
+                self.queue.clear();
+            }
+            _ if self.queue.len() < self.capacity => {}
+            Overflow::DropOldest => {
+                self.queue.pop_front();
+                self.stats.dropped += 1;
+            }
+            Overflow::DropNewest => {
+                self.stats.dropped += 1;
+                return;
+            }
+        }
+        self.queue.push_back(bytes.to_vec());
+        self.stats.high_water = self.stats.high_water.max(self.queue.len());
+    }
+}
+
+/// Messages on one event, buffered until taken.
+pub struct BufferedSubscription {
+    buffer: Rc<RefCell<Buffer>>,
+    _sub: Subscription,
+}
+
+impl BufferedSubscription {
+    /// Buffer up to `capacity` messages on `event` (at least one).
+    pub fn new(event: &str, capacity: usize, overflow: Overflow) -> CommBusResult<Self> {
+        let buffer = Rc::new(RefCell::new(Buffer {
+            queue: VecDeque::new(),
+            capacity: capacity.max(1),
+            overflow,
+            stats: BufferStats::default(),
+        }));
+        let inbox = Rc::clone(&buffer);
+        let sub = Subscription::try_subscribe(event, move |bytes| inbox.borrow_mut().push(bytes))?;
+        Ok(Self { buffer, _sub: sub })
+    }
+
+    /// The oldest buffered message.
+    pub fn try_recv(&self) -> Option<Vec<u8>> {
+        self.buffer.borrow_mut().queue.pop_front()
+    }
+
+    /// Every buffered message, oldest first.
+    pub fn drain(&self) -> std::vec::IntoIter<Vec<u8>> {
+        let messages: Vec<_> = self.buffer.borrow_mut().queue.drain(..).collect();
+        messages.into_iter()
+    }
+
+    /// The newest message, dropping any older ones. These don't count as
+    /// dropped in [`stats`](Self::stats).
+    pub fn latest(&self) -> Option<Vec<u8>> {
+        let mut buffer = self.buffer.borrow_mut();
+        let latest = buffer.queue.pop_back();
+        buffer.queue.clear();
+        latest
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.borrow().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.borrow().queue.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer.borrow().capacity
+    }
+
+    pub fn overflow(&self) -> Overflow {
+        self.buffer.borrow().overflow
+    }
+
+    /// Change the policy for messages from now on.
+    pub fn set_overflow(&self, overflow: Overflow) {
+        self.buffer.borrow_mut().overflow = overflow;
+    }
+
+    pub fn stats(&self) -> BufferStats {
+        self.buffer.borrow().stats
+    }
+
+    /// Zero the counters, e.g. after logging them.
+    pub fn reset_stats(&self) {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.stats = BufferStats {
+            high_water: buffer.queue.len(),
+            ..BufferStats::default()
+        };
+    }
+}
@@ -0,0 +1,140 @@
+//! Typed channels over the comm bus, in the style of `std::sync::mpsc`.
+//!
+//! [`channel`] returns both ends for one event name; the [`Sender`] encodes
+//! values with the [`Json`] codec of `#[derive(CommBusMessage)]` types and the
+//! [`Receiver`] decodes and buffers them until `update()` takes them. The ends
+//! can also be made separately with [`sender`] and [`receiver`] when they live
+//! in different modules.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::channel;
+//!
+//! let (tx, rx) = channel::channel::<f64>("INFINITY_BARO_SET")?;
+//!
+//! tx.send(&29.92)?;
+//!
+//! // in update():
+//! for baro in rx.try_iter() {
+//!     println!("baro {baro}");
+//! }
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{
+    BroadcastFlags, CommBusResult, Subscription,
+    json::{self, Json},
+    try_call,
+};
+use std::{cell::RefCell, collections::VecDeque, marker::PhantomData, rc::Rc};
+
+/// Both ends of a channel on `name`. The sender reaches this module and every
+/// other WASM module ([`BroadcastFlags::ALL_WASM`]).
+pub fn channel<T: Json + 'static>(name: &str) -> CommBusResult<(Sender<T>, Receiver<T>)> {
+    Ok((sender(name), receiver(name)?))
+}
+
+/// The sending end of a channel on `name`.
+pub fn sender<T: Json>(name: &str) -> Sender<T> {
+    Sender {
+        event: Rc::from(name),
+        broadcast: BroadcastFlags::ALL_WASM,
+        _marker: PhantomData,
+    }
+}
+
+/// The receiving end of a channel on `name`.
+pub fn receiver<T: Json + 'static>(name: &str) -> CommBusResult<Receiver<T>> {
+    let inbox = Rc::new(RefCell::new(Inbox {
+        values: VecDeque::new(),
+        decode_errors: 0,
+    }));
+    let shared = Rc::clone(&inbox);
+    let sub = Subscription::try_subscribe(name, move |bytes| {
+        let mut inbox = shared.borrow_mut();
+        match json::from_slice(bytes) {
+            Ok(value) => inbox.values.push_back(value),
+            Err(_) => inbox.decode_errors += 1,
+        }
+    })?;
+    Ok(Receiver { inbox, _sub: sub })
+}
+
+/// Sends values on a channel. Cheap to clone.
+pub struct Sender<T> {
+    event: Rc<str>,
+    broadcast: BroadcastFlags,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            event: Rc::clone(&self.event),
+            broadcast: self.broadcast,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Json> Sender<T> {
+    /// Send to other targets instead, e.g. `JS` as well.
+    pub fn with_broadcast(mut self, broadcast: BroadcastFlags) -> Self {
+        self.broadcast = broadcast;
+        self
+    }
+
+    /// Encode and send `value`. `Ok(false)` if the sim refused the call.
+    pub fn send(&self, value: &T) -> CommBusResult<bool> {
+        try_call(&self.event, &json::to_vec(value), self.broadcast)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.event
+    }
+}
+
+struct Inbox<T> {
+    values: VecDeque<T>,
+    decode_errors: u64,
+}
+
+/// Receives the values sent on a channel. Unsubscribes when dropped.
+pub struct Receiver<T> {
+    inbox: Rc<RefCell<Inbox<T>>>,
+    _sub: Subscription,
+}
+
+impl<T> Receiver<T> {
+    /// The oldest value not yet taken.
+    pub fn try_recv(&self) -> Option<T> {
+        self.inbox.borrow_mut().values.pop_front()
+    }
+
+    /// Every value not yet taken, oldest first.
+    pub fn try_iter(&self) -> std::vec::IntoIter<T> {
+        let values: Vec<T> = self.inbox.borrow_mut().values.drain(..).collect();
+        values.into_iter()
+    }
+
+    /// The newest value, dropping any older ones.
+    pub fn latest(&self) -> Option<T> {
+        let mut inbox = self.inbox.borrow_mut();
+        let latest = inbox.values.pop_back();
+        inbox.values.clear();
+        latest
+    }
+
+    pub fn len(&self) -> usize {
+        self.inbox.borrow().values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inbox.borrow().values.is_empty()
+    }
+
+    /// Payloads on the channel's event that didn't decode as `T`.
+    pub fn decode_errors(&self) -> u64 {
+        self.inbox.borrow().decode_errors
+    }
+}
@@ -0,0 +1,394 @@
+//! Large payloads split into numbered chunks.
+//!
+//! [`send`] cuts a blob into chunks of at most [`DEFAULT_CHUNK_SIZE`] bytes,
+//! each sent as its own comm bus call with a 20-byte header (little-endian):
+//!
+//! ```text
+//! [sender: u32][transfer id: u32][index: u32][count: u32][crc32 of the whole blob: u32][data]
+//! ```
+//!
+//! The receiving [`Reassembler`] collects the chunks of each transfer in any
+//! order and hands back the blob once all have arrived and the checksum
+//! matches. Transfers that stop arriving are dropped after [`STALE_AFTER`].
+//!
+//! Each module numbers its transfers from 1, so transfers are told apart by
+//! the sender's [`sender_id`] together with the transfer id, and several
+//! modules can send on one event at the same time.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, chunked};
+//!
+//! // receiver:
+//! let _sub = chunked::subscribe("NAVDATA_BLOB", |blob| {
+//!     println!("nav data: {} bytes", blob.len());
+//! })?;
+//!
+//! // sender:
+//! let blob = vec![0u8; 1 << 20];
+//! chunked::send("NAVDATA_BLOB", &blob, BroadcastFlags::ALL_WASM)?;
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusResult, Subscription, sender_id, try_call};
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Size of the chunk header in bytes.
+pub const HEADER_LEN: usize = 20;
+
+/// Data bytes per chunk used by [`send`].
+pub const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+/// Most chunks a [`Reassembler`] accepts per transfer (256 MiB at the default
+/// chunk size), so a bad header can't make it allocate without bound.
+pub const MAX_CHUNKS: u32 = 1 << 16;
+
+/// How long a [`Reassembler`] keeps an incomplete transfer after its last chunk.
+pub const STALE_AFTER: Duration = Duration::from_secs(10);
+
+thread_local! {
+    static NEXT_TRANSFER: Cell<u32> = const { Cell::new(1) };
+}
+
+/// CRC-32 (IEEE) of `bytes`.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// One chunk as received.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk<'a> {
+    /// The sending module's [`sender_id`].
+    pub sender: u32,
+    pub transfer: u32,
+    pub index: u32,
+    pub count: u32,
+    pub checksum: u32,
+    pub data: &'a [u8],
+}
+
+impl<'a> Chunk<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let field = |n: usize| {
+            let at = n * 4;
+            Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+        };
+        Some(Self {
+            sender: field(0)?,
+            transfer: field(1)?,
+            index: field(2)?,
+            count: field(3)?,
+            checksum: field(4)?,
+            data: &bytes[HEADER_LEN..],
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.data.len());
+        for field in [
+            self.sender,
+            self.transfer,
+            self.index,
+            self.count,
+            self.checksum,
+        ] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        out.extend_from_slice(self.data);
+        out
+    }
+}
+
+/// The chunks for `data`, numbered under `transfer` from `sender`. An empty
+/// blob is one empty chunk.
+pub fn split(sender: u32, transfer: u32, data: &[u8], chunk_size: usize) -> Vec<Vec<u8>> {
+    let chunk_size = chunk_size.max(1);
+    let checksum = crc32(data);
+    let count = data.len().div_ceil(chunk_size).max(1);
+    (0..count)
+        .map(|index| {
+            let start = index * chunk_size;
+            let end = (start + chunk_size).min(data.len());
+            Chunk {
+                sender,
+                transfer,
+                index: index as u32,
+                count: count as u32,
+                checksum,
+                data: &data[start..end],
+            }
+            .to_bytes()
+        })
+        .collect()
+}
+
+/// Send `data` on `event` in chunks of [`DEFAULT_CHUNK_SIZE`]. `Ok(false)` if
+/// any chunk couldn't be sent; the receiver then drops the transfer.
+pub fn send(event: &str, data: &[u8], broadcast: BroadcastFlags) -> CommBusResult<bool> {
+    send_with(event, data, DEFAULT_CHUNK_SIZE, broadcast)
+}
+
+/// [`send`] with `chunk_size` data bytes per chunk.
+pub fn send_with(
+    event: &str,
+    data: &[u8],
+    chunk_size: usize,
+    broadcast: BroadcastFlags,
+) -> CommBusResult<bool> {
+    let transfer = NEXT_TRANSFER.with(|n| n.replace(n.get().wrapping_add(1)));
+    for chunk in split(sender_id(), transfer, data, chunk_size) {
+        if !try_call(event, &chunk, broadcast)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkError {
+    /// Shorter than the chunk header.
+    Malformed,
+    /// A chunk disagrees with earlier chunks of its transfer about the count
+    /// or checksum, or its index or count is out of range.
+    Inconsistent { transfer: u32 },
+    /// Every chunk arrived, but the blob doesn't match its checksum.
+    ChecksumMismatch { transfer: u32 },
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkError::Malformed => f.write_str("payload shorter than the chunk header"),
+            ChunkError::Inconsistent { transfer } => {
+                write!(f, "transfer {transfer}: chunk headers disagree")
+            }
+            ChunkError::ChecksumMismatch { transfer } => {
+                write!(f, "transfer {transfer}: checksum mismatch")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChunkError {}
+
+struct Partial {
+    count: u32,
+    checksum: u32,
+    // By index, so a forged count can't make us allocate up front.
+    chunks: BTreeMap<u32, Vec<u8>>,
+    last_seen: Instant,
+}
+
+/// Collects chunks into complete blobs.
+#[derive(Default)]
+pub struct Reassembler {
+    // By sender and transfer id.
+    partial: HashMap<(u32, u32), Partial>,
+}
+
+impl Reassembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one received payload. Returns the blob when this chunk completes
+    /// its transfer, an error when the chunk or the transfer is bad, and
+    /// `None` while waiting for more.
+    pub fn push(&mut self, bytes: &[u8]) -> Option<Result<Vec<u8>, ChunkError>> {
+        self.expire(STALE_AFTER);
+        let Some(chunk) = Chunk::parse(bytes) else {
+            return Some(Err(ChunkError::Malformed));
+        };
+        let transfer = chunk.transfer;
+        let key = (chunk.sender, transfer);
+        if chunk.count == 0 || chunk.count > MAX_CHUNKS || chunk.index >= chunk.count {
+            self.partial.remove(&key);
+            return Some(Err(ChunkError::Inconsistent { transfer }));
+        }
+
+        let partial = self.partial.entry(key).or_insert_with(|| Partial {
+            count: chunk.count,
+            checksum: chunk.checksum,
+            chunks: BTreeMap::new(),
+            last_seen: Instant::now(),
+        });
+        if partial.count != chunk.count || partial.checksum != chunk.checksum {
+            self.partial.remove(&key);
+            return Some(Err(ChunkError::Inconsistent { transfer }));
+        }
+        partial.last_seen = Instant::now();
+        partial
+            .chunks
+            .entry(chunk.index)
+            .or_insert_with(|| chunk.data.to_vec());
+        if (partial.chunks.len() as u32) < partial.count {
+            return None;
+        }
+
+        let partial = self.partial.remove(&key)?;
+        let blob: Vec<u8> = partial.chunks.into_values().flatten().collect();
+        if crc32(&blob) != partial.checksum {
+            return Some(Err(ChunkError::ChecksumMismatch { transfer }));
+        }
+        Some(Ok(blob))
+    }
+
+    /// Drop incomplete transfers that haven't had a chunk for `max_age`.
+    pub fn expire(&mut self, max_age: Duration) {
+        self.partial.retain(|_, p| p.last_seen.elapsed() < max_age);
+    }
+
+    /// Transfers still waiting for chunks.
+    pub fn pending(&self) -> usize {
+        self.partial.len()
+    }
+}
+
+/// Call `cb` with every complete, intact blob on `event`. Bad chunks and
+/// transfers are dropped; use [`subscribe_with_errors`] to see them.
+pub fn subscribe(
+    event: &str,
+    mut cb: impl FnMut(Vec<u8>) + 'static,
+) -> CommBusResult<Subscription> {
+    subscribe_with_errors(event, move |blob| {
+        if let Ok(blob) = blob {
+            cb(blob);
+        }
+    })
+}
+
+/// Like [`subscribe`], but also passes on chunks and transfers that fail.
+pub fn subscribe_with_errors(
+    event: &str,
+    mut cb: impl FnMut(Result<Vec<u8>, ChunkError>) + 'static,
+) -> CommBusResult<Subscription> {
+    let mut reassembler = Reassembler::new();
+    Subscription::try_subscribe(event, move |bytes| {
+        if let Some(result) = reassembler.push(bytes) {
+            cb(result);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn split_sizes() {
+        let data: Vec<u8> = (0..10).collect();
+        let chunks = split(1, 2, &data, 4);
+        assert_eq!(chunks.len(), 3);
+        let last = Chunk::parse(&chunks[2]).unwrap();
+        assert_eq!((last.sender, last.transfer), (1, 2));
+        assert_eq!((last.index, last.count), (2, 3));
+        assert_eq!(last.data, &[8, 9]);
+
+        let empty = split(1, 3, &[], 4);
+        assert_eq!(empty.len(), 1);
+        assert_eq!(empty[0].len(), HEADER_LEN);
+        assert_eq!(Chunk::parse(&empty[0]).unwrap().count, 1);
+    }
+
+    #[test]
+    fn reassembles_out_of_order() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut chunks = split(1, 1, &data, 100);
+        chunks.reverse();
+        let mut r = Reassembler::new();
+        assert_eq!(r.push(&chunks[0]), None);
+        assert_eq!(r.push(&chunks[0]), None, "duplicates are ignored");
+        assert_eq!(r.push(&chunks[1]), None);
+        assert_eq!(r.pending(), 1);
+        assert_eq!(r.push(&chunks[2]), Some(Ok(data)));
+        assert_eq!(r.pending(), 0);
+    }
+
+    #[test]
+    fn senders_with_the_same_transfer_id_stay_apart() {
+        let a = split(1, 1, b"from module a", 4);
+        let b = split(2, 1, b"from module b!", 4);
+        let mut r = Reassembler::new();
+        let mut done = Vec::new();
+        for (a, b) in a.iter().zip(&b) {
+            done.extend(r.push(a));
+            done.extend(r.push(b));
+        }
+        for b in &b[a.len()..] {
+            done.extend(r.push(b));
+        }
+        assert_eq!(
+            done,
+            [
+                Ok(b"from module a".to_vec()),
+                Ok(b"from module b!".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_chunks() {
+        let mut r = Reassembler::new();
+        assert_eq!(
+            r.push(&[0; HEADER_LEN - 1]),
+            Some(Err(ChunkError::Malformed))
+        );
+
+        let mut chunks = split(1, 7, b"abcdefgh", 4);
+        // Index past the count.
+        let mut bad = chunks[0].clone();
+        bad[8..12].copy_from_slice(&5u32.to_le_bytes());
+        assert_eq!(
+            r.push(&bad),
+            Some(Err(ChunkError::Inconsistent { transfer: 7 }))
+        );
+
+        // Corrupted data, intact header.
+        let last = chunks[1].len() - 1;
+        chunks[1][last] ^= 1;
+        assert_eq!(r.push(&chunks[0]), None);
+        assert_eq!(
+            r.push(&chunks[1]),
+            Some(Err(ChunkError::ChecksumMismatch { transfer: 7 }))
+        );
+    }
+
+    #[test]
+    fn duplicate_and_sparse_chunks() {
+        let mut r = Reassembler::new();
+        let chunks = split(1, 3, b"abcdefgh", 4);
+        assert_eq!(r.push(&chunks[1]), None);
+        assert_eq!(r.push(&chunks[1]), None);
+        assert_eq!(r.push(&chunks[0]), Some(Ok(b"abcdefgh".to_vec())));
+
+        // A header claiming the maximum count only holds what arrived.
+        let chunk = Chunk {
+            sender: 1,
+            transfer: 4,
+            index: MAX_CHUNKS - 1,
+            count: MAX_CHUNKS,
+            checksum: 0,
+            data: b"x",
+        };
+        assert_eq!(r.push(&chunk.to_bytes()), None);
+        assert_eq!(r.pending(), 1);
+    }
+}
@@ -0,0 +1,347 @@
+//! Mirror comm bus events into SimConnect client data areas and back, so a
+//! native SimConnect app can take part in the same traffic as WASM modules.
+//!
+//! A [`ClientDataBridge`] works on the module's shared SimConnect client.
+//! [`export`](ClientDataBridge::export) writes every message on a comm bus
+//! event to a named client data area, and [`import`](ClientDataBridge::import)
+//! re-sends on the comm bus whatever the external app writes to an area. Use a
+//! separate area for each direction.
+//!
+//! Every area is [`AREA_SIZE`] bytes: a little-endian `u32` sequence number, a
+//! little-endian `u32` payload length, then the payload. Writers start at
+//! sequence 1 and skip 0 on wrap-around; an area whose sequence is 0 is empty.
+//! An area holds one message, so a reader that falls behind sees only the
+//! latest one and can tell from the gap how many it missed.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, client_data::ClientDataBridge};
+//!
+//! let mut bridge = ClientDataBridge::new().expect("SimConnect unavailable");
+//! bridge.export("INFINITY_FMS/route", "Infinity.FmsRoute")?;
+//! bridge.import("Infinity.EfbCommand", "INFINITY_EFB/command", BroadcastFlags::ALL_WASM)?;
+//!
+//! // in update():
+//! bridge.poll();
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusResult, Subscription, try_call};
+use crate::simconnect::{self, Inbox, read_u32, with_client};
+use crate::sys::{
+    DWORD, HANDLE, SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_NEVER,
+    SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ON_SET,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA, SimConnect_AddToClientDataDefinition,
+    SimConnect_CreateClientData, SimConnect_MapClientDataNameToID, SimConnect_RequestClientData,
+    SimConnect_SetClientData,
+};
+use std::{cell::RefCell, ffi::CString, os::raw::c_void, rc::Rc};
+
+/// Size of every bridged area; SimConnect's maximum.
+pub const AREA_SIZE: usize = 8192;
+/// Sequence number and payload length.
+pub const HEADER_LEN: usize = 8;
+/// Largest payload that fits in an area.
+pub const MAX_PAYLOAD: usize = AREA_SIZE - HEADER_LEN;
+
+// `static const`s in SimConnect.h, which bindgen doesn't emit.
+const CLIENT_DATA_REQUEST_FLAG_CHANGED: DWORD = 1;
+const UNUSED: DWORD = u32::MAX as DWORD;
+
+// `SIMCONNECT_RECV_CLIENT_DATA` has the same packed header as
+// `SIMCONNECT_RECV_SIMOBJECT_DATA`, then the area.
+const DATA: usize = 40;
+
+/// The area contents for `payload` as message `seq`. `None` if the payload is
+/// longer than [`MAX_PAYLOAD`].
+pub fn encode(seq: u32, payload: &[u8]) -> Option<Vec<u8>> {
+    if payload.len() > MAX_PAYLOAD {
+        return None;
+    }
+    let mut area = vec![0u8; AREA_SIZE];
+    area[..4].copy_from_slice(&seq.to_le_bytes());
+    area[4..8].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+    area[HEADER_LEN..HEADER_LEN + payload.len()].copy_from_slice(payload);
+    Some(area)
+}
+
+/// The sequence number and payload in an area. `None` if it's empty or the
+/// length doesn't fit.
+pub fn decode(area: &[u8]) -> Option<(u32, &[u8])> {
+    let seq = read_u32(area, 0).filter(|&seq| seq != 0)?;
+    let len = read_u32(area, 4)? as usize;
+    let payload = area.get(HEADER_LEN..HEADER_LEN.checked_add(len)?)?;
+    Some((seq, payload))
+}
+
+/// Counters since the bridge was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BridgeStats {
+    /// Comm bus messages written to an area.
+    pub exported: u64,
+    /// Area writes re-sent on the comm bus.
+    pub imported: u64,
+    /// Comm bus messages too long for an area, or that SimConnect refused.
+    pub dropped: u64,
+}
+
+struct Area {
+    id: DWORD,
+    definition: DWORD,
+}
+
+struct Export {
+    event: String,
+    area: String,
+    _sub: Subscription,
+}
+
+struct Import {
+    event: String,
+    area: String,
+    id: DWORD,
+    definition: DWORD,
+    request: DWORD,
+    broadcast: BroadcastFlags,
+    last_seq: u32,
+}
+
+/// Comm bus events mirrored to and from client data areas.
+pub struct ClientDataBridge {
+    // The shared client's; it stays open for the life of the module.
+    handle: HANDLE,
+    inbox: Inbox,
+    exports: Vec<Export>,
+    imports: Vec<Import>,
+    stats: Rc<RefCell<BridgeStats>>,
+}
+
+impl ClientDataBridge {
+    /// A bridge with nothing mirrored yet. `None` if SimConnect is
+    /// unavailable.
+    pub fn new() -> Option<Self> {
+        let inbox = Inbox::new()?;
+        let handle = with_client(|client| client.handle())?;
+        Some(Self {
+            handle,
+            inbox,
+            exports: Vec::new(),
+            imports: Vec::new(),
+            stats: Rc::new(RefCell::new(BridgeStats::default())),
+        })
+    }
+
+    /// Write every message on `event` to the client data area `area`,
+    /// creating it. `Ok(false)` if SimConnect refused the area.
+    pub fn export(&mut self, event: &str, area: &str) -> CommBusResult<bool> {
+        let Some(Area { id, definition }) = self.area(area)? else {
+            return Ok(false);
+        };
+        let handle = self.handle;
+        let stats = Rc::clone(&self.stats);
+        let mut seq = 0u32;
+        let sub = Subscription::try_subscribe(event, move |bytes| {
+            let mut stats = stats.borrow_mut();
+            let next = seq.wrapping_add(1).max(1);
+            let Some(mut data) = encode(next, bytes) else {
+                stats.dropped += 1;
+                return;
+            };
+            let ok = unsafe {
+                SimConnect_SetClientData(
+                    handle,
+                    id,
+                    definition,
+                    0,
+                    0,
+                    AREA_SIZE as DWORD,
+                    data.as_mut_ptr() as *mut c_void,
+                ) >= 0
+            };
+            if ok {
+                seq = next;
+                stats.exported += 1;
+            } else {
+                stats.dropped += 1;
+            }
+        })?;
+        self.exports.push(Export {
+            event: event.to_string(),
+            area: area.to_string(),
+            _sub: sub,
+        });
+        Ok(true)
+    }
+
+    /// Send whatever is written to the client data area `area` on `event`
+    /// with `broadcast`, creating the area. Messages arrive from
+    /// [`poll`](Self::poll). `Ok(false)` if SimConnect refused the area or the
+    /// request.
+    pub fn import(
+        &mut self,
+        area: &str,
+        event: &str,
+        broadcast: BroadcastFlags,
+    ) -> CommBusResult<bool> {
+        CString::new(event)?;
+        let Some(Area { id, definition }) = self.area(area)? else {
+            return Ok(false);
+        };
+        let request = self.next_id();
+        let ok = unsafe {
+            SimConnect_RequestClientData(
+                self.handle,
+                id,
+                request,
+                definition,
+                SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_ON_SET,
+                CLIENT_DATA_REQUEST_FLAG_CHANGED,
+                0,
+                0,
+                0,
+            ) >= 0
+        };
+        if ok {
+            self.inbox.claim(request);
+            self.imports.push(Import {
+                event: event.to_string(),
+                area: area.to_string(),
+                id,
+                definition,
+                request,
+                broadcast,
+                last_seq: 0,
+            });
+        }
+        Ok(ok)
+    }
+
+    /// Stop mirroring `event` to its area, if exported.
+    pub fn unexport(&mut self, event: &str) {
+        self.exports.retain(|e| e.event != event);
+    }
+
+    /// Stop reading `area`, if imported.
+    pub fn unimport(&mut self, area: &str) {
+        let handle = self.handle;
+        let inbox = &self.inbox;
+        self.imports.retain(|i| {
+            if i.area != area {
+                return true;
+            }
+            stop(handle, i);
+            inbox.release(i.request);
+            false
+        });
+    }
+
+    /// `(event, area)` for every export.
+    pub fn exports(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.exports
+            .iter()
+            .map(|e| (e.event.as_str(), e.area.as_str()))
+    }
+
+    /// `(area, event)` for every import.
+    pub fn imports(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.imports
+            .iter()
+            .map(|i| (i.area.as_str(), i.event.as_str()))
+    }
+
+    pub fn stats(&self) -> BridgeStats {
+        *self.stats.borrow()
+    }
+
+    /// Process pending SimConnect messages and send new area contents on the
+    /// comm bus. Returns how many were sent.
+    pub fn poll(&mut self) -> usize {
+        let received: Vec<_> = self
+            .inbox
+            .receive()
+            .iter()
+            .filter_map(|bytes| self.receive(bytes))
+            .collect();
+        let mut sent = 0;
+        for (index, payload) in received {
+            let import = &self.imports[index];
+            if let Ok(true) = try_call(&import.event, &payload, import.broadcast) {
+                sent += 1;
+            }
+        }
+        self.stats.borrow_mut().imported += sent as u64;
+        sent
+    }
+
+    fn next_id(&mut self) -> DWORD {
+        with_client(|client| client.next_id()).unwrap_or_default()
+    }
+
+    /// Map, create and define `name` as one block of [`AREA_SIZE`] bytes.
+    fn area(&mut self, name: &str) -> CommBusResult<Option<Area>> {
+        let c_name = CString::new(name)?;
+        let id = self.next_id();
+        let definition = self.next_id();
+        let ok = unsafe {
+            SimConnect_MapClientDataNameToID(self.handle, c_name.as_ptr(), id) >= 0
+                // Fails harmlessly (with an exception message) if the
+                // external app already created it.
+                && SimConnect_CreateClientData(self.handle, id, AREA_SIZE as DWORD, 0) >= 0
+                && SimConnect_AddToClientDataDefinition(
+                    self.handle,
+                    definition,
+                    0,
+                    AREA_SIZE as DWORD,
+                    0.0,
+                    UNUSED,
+                ) >= 0
+        };
+        Ok(ok.then_some(Area { id, definition }))
+    }
+
+    /// The import index and payload of a new area write.
+    fn receive(&mut self, bytes: &[u8]) -> Option<(usize, Vec<u8>)> {
+        if simconnect::recv_id(bytes)? as i32
+            != SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA as i32
+        {
+            return None;
+        }
+        let request = read_u32(bytes, 12)?;
+        let index = self
+            .imports
+            .iter()
+            .position(|i| i.request as u32 == request)?;
+        let (seq, payload) = bytes.get(DATA..).and_then(decode)?;
+        let import = &mut self.imports[index];
+        if seq == import.last_seq {
+            return None;
+        }
+        import.last_seq = seq;
+        Some((index, payload.to_vec()))
+    }
+}
+
+/// Stop the requests for `import`'s area.
+fn stop(handle: HANDLE, import: &Import) {
+    unsafe {
+        SimConnect_RequestClientData(
+            handle,
+            import.id,
+            import.request,
+            import.definition,
+            SIMCONNECT_CLIENT_DATA_PERIOD_SIMCONNECT_CLIENT_DATA_PERIOD_NEVER,
+            0,
+            0,
+            0,
+            0,
+        );
+    }
+}
+
+impl Drop for ClientDataBridge {
+    fn drop(&mut self) {
+        for import in &self.imports {
+            stop(self.handle, import);
+        }
+    }
+}
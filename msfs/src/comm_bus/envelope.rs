@@ -0,0 +1,381 @@
+//! Versioned envelope for raw comm bus payloads.
+//!
+//! An envelope puts a 10-byte [`Header`] in front of the payload: the
+//! [`MAGIC`] bytes, the payload's format version and a hash of the event name,
+//! all little-endian. A receiver can then tell a payload from a module built
+//! against another format (or one sent on the wrong event, or without an
+//! envelope at all) from a valid one, instead of misreading its bytes.
+//!
+//! [`VersionHandshake`] lets the two sides agree on a version up front: each
+//! announces the range it supports and both pick the highest common one.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, envelope::{self, VersionHandshake}};
+//!
+//! // this build reads v1 and v2, and writes whatever the peer also knows
+//! let handshake = VersionHandshake::new("FMS_ROUTE", 1..=2)?;
+//! handshake.announce();
+//!
+//! let _sub = envelope::subscribe("FMS_ROUTE", 1..=2, |version, payload| {
+//!     println!("route v{version}, {} bytes", payload.len());
+//! })?;
+//!
+//! // later:
+//! if let Some(version) = handshake.agreed() {
+//!     envelope::publish("FMS_ROUTE", version, b"...", BroadcastFlags::ALL_WASM)?;
+//! }
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusResult, Subscription, sender_id, try_call};
+use std::{cell::RefCell, fmt, ops::RangeInclusive, rc::Rc};
+
+/// First bytes of every envelope.
+pub const MAGIC: [u8; 4] = *b"IRSE";
+
+/// Size of the [`Header`] in bytes.
+pub const HEADER_LEN: usize = 10;
+
+/// FNV-1a of `name`, as stored in [`Header::event_hash`].
+pub const fn hash(name: &str) -> u32 {
+    let bytes = name.as_bytes();
+    let mut hash: u32 = 0x811c_9dc5;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    hash
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Header {
+    pub version: u16,
+    pub event_hash: u32,
+}
+
+impl Header {
+    pub fn new(event: &str, version: u16) -> Self {
+        Self {
+            version,
+            event_hash: hash(event),
+        }
+    }
+
+    pub fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let mut out = [0; HEADER_LEN];
+        out[..4].copy_from_slice(&MAGIC);
+        out[4..6].copy_from_slice(&self.version.to_le_bytes());
+        out[6..].copy_from_slice(&self.event_hash.to_le_bytes());
+        out
+    }
+
+    /// The header at the start of `bytes`.
+    pub fn parse(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        match bytes.get(..4) {
+            Some(magic) if magic == MAGIC => {}
+            Some(_) => return Err(EnvelopeError::NotEnveloped),
+            None => return Err(EnvelopeError::Truncated),
+        }
+        let header = bytes.get(..HEADER_LEN).ok_or(EnvelopeError::Truncated)?;
+        Ok(Self {
+            version: u16::from_le_bytes([header[4], header[5]]),
+            event_hash: u32::from_le_bytes([header[6], header[7], header[8], header[9]]),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The payload doesn't start with [`MAGIC`].
+    NotEnveloped,
+    /// Shorter than a [`Header`].
+    Truncated,
+    /// The envelope was addressed to another event.
+    WrongEvent { expected: u32, found: u32 },
+    /// A version outside the range the receiver supports.
+    UnsupportedVersion {
+        found: u16,
+        supported: RangeInclusive<u16>,
+    },
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::NotEnveloped => f.write_str("payload has no envelope"),
+            EnvelopeError::Truncated => f.write_str("payload shorter than the envelope header"),
+            EnvelopeError::WrongEvent { expected, found } => {
+                write!(f, "envelope for event {found:08x}, expected {expected:08x}")
+            }
+            EnvelopeError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "version {found}, supported {}..={}",
+                supported.start(),
+                supported.end()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+/// `payload` in an envelope for `event` at `version`.
+pub fn wrap(event: &str, version: u16, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+    out.extend_from_slice(&Header::new(event, version).to_bytes());
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Check the envelope of a payload received on `event` against `supported`
+/// and return its version and body.
+pub fn open<'a>(
+    event: &str,
+    bytes: &'a [u8],
+    supported: RangeInclusive<u16>,
+) -> Result<(u16, &'a [u8]), EnvelopeError> {
+    let header = Header::parse(bytes)?;
+    let expected = hash(event);
+    if header.event_hash != expected {
+        return Err(EnvelopeError::WrongEvent {
+            expected,
+            found: header.event_hash,
+        });
+    }
+    if !supported.contains(&header.version) {
+        return Err(EnvelopeError::UnsupportedVersion {
+            found: header.version,
+            supported,
+        });
+    }
+    Ok((header.version, &bytes[HEADER_LEN..]))
+}
+
+/// Send `payload` in an envelope on `event`.
+pub fn publish(
+    event: &str,
+    version: u16,
+    payload: &[u8],
+    broadcast: BroadcastFlags,
+) -> CommBusResult<bool> {
+    try_call(event, &wrap(event, version, payload), broadcast)
+}
+
+/// Call `cb` with the version and body of every envelope on `event` whose
+/// version is in `supported`; other payloads are dropped. Use
+/// [`subscribe_with_errors`] to see them.
+pub fn subscribe(
+    event: &str,
+    supported: RangeInclusive<u16>,
+    mut cb: impl FnMut(u16, &[u8]) + 'static,
+) -> CommBusResult<Subscription> {
+    subscribe_with_errors(event, supported, move |opened| {
+        if let Ok((version, payload)) = opened {
+            cb(version, payload);
+        }
+    })
+}
+
+/// Like [`subscribe`], but also passes on payloads that don't open.
+pub fn subscribe_with_errors(
+    event: &str,
+    supported: RangeInclusive<u16>,
+    mut cb: impl FnMut(Result<(u16, &[u8]), EnvelopeError>) + 'static,
+) -> CommBusResult<Subscription> {
+    let name = event.to_string();
+    Subscription::try_subscribe(event, move |bytes| {
+        cb(open(&name, bytes, supported.clone()))
+    })
+}
+
+/// The highest version in both ranges.
+pub fn negotiate(ours: &RangeInclusive<u16>, theirs: &RangeInclusive<u16>) -> Option<u16> {
+    let high = *ours.end().min(theirs.end());
+    let low = *ours.start().max(theirs.start());
+    (low <= high).then_some(high)
+}
+
+/// Event a [`VersionHandshake`] for `event` talks on.
+pub fn handshake_event(event: &str) -> String {
+    format!("{event}:versions")
+}
+
+const HELLO: &str = "hello";
+const REPLY: &str = "reply";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionStatus {
+    /// The peer hasn't announced its versions (yet).
+    Unknown,
+    /// The highest version both sides support.
+    Agreed(u16),
+    /// The peer's range doesn't overlap ours.
+    Incompatible,
+}
+
+struct Shared {
+    event: String,
+    broadcast: BroadcastFlags,
+    supported: RangeInclusive<u16>,
+    peer: Option<RangeInclusive<u16>>,
+}
+
+type Message = (String, Vec<u8>, BroadcastFlags);
+
+impl Shared {
+    fn encode(&self, kind: &str) -> Vec<u8> {
+        format!(
+            "{kind} {} {} {:08x}",
+            self.supported.start(),
+            self.supported.end(),
+            sender_id()
+        )
+        .into_bytes()
+    }
+
+    /// What [`try_call`] needs to send a `kind` announcement, so it can be
+    /// sent without holding a borrow.
+    fn message(&self, kind: &str) -> Message {
+        (self.event.clone(), self.encode(kind), self.broadcast)
+    }
+
+    /// Record the peer's range unless the announcement is from `own_id`, our
+    /// own sender id. Returns the reply to send if it was a hello.
+    fn receive(&mut self, bytes: &[u8], own_id: u32) -> Option<Message> {
+        let text = String::from_utf8_lossy(bytes);
+        let mut fields = text.trim_end_matches('\0').split_whitespace();
+        let kind = fields.next()?;
+        let min = fields.next()?.parse::<u16>().ok()?;
+        let max = fields.next()?.parse::<u16>().ok()?;
+        let sender = fields.next().and_then(|s| u32::from_str_radix(s, 16).ok());
+        if sender == Some(own_id) {
+            return None;
+        }
+        self.peer = Some(min..=max);
+        (kind == HELLO).then(|| self.message(REPLY))
+    }
+}
+
+/// Agrees on an envelope version with the module or panel on the other end.
+///
+/// The announcement is plain text on [`handshake_event`], so JS can join in:
+/// `"hello <min> <max> <sender>"`, answered with `"reply <min> <max> <sender>"`.
+/// The sender is the hex [`sender_id`], so a module ignores its own
+/// announcements when they come back to it; JS peers may leave it out.
+pub struct VersionHandshake {
+    shared: Rc<RefCell<Shared>>,
+    _sub: Subscription,
+}
+
+impl VersionHandshake {
+    /// Listen for the peer's versions for `event`. Announcements go to other
+    /// WASM modules by default.
+    pub fn new(event: &str, supported: RangeInclusive<u16>) -> CommBusResult<Self> {
+        let shared = Rc::new(RefCell::new(Shared {
+            event: handshake_event(event),
+            broadcast: BroadcastFlags::WASM,
+            supported,
+            peer: None,
+        }));
+
+        let inbox = Rc::clone(&shared);
+        let sub = Subscription::try_subscribe(&handshake_event(event), move |bytes| {
+            let reply = inbox.borrow_mut().receive(bytes, sender_id());
+            // Not borrowed while sending, in case the reply loops back.
+            if let Some((event, reply, broadcast)) = reply {
+                let _ = try_call(&event, &reply, broadcast);
+            }
+        })?;
+
+        Ok(Self { shared, _sub: sub })
+    }
+
+    /// Announce to JS instead of (or as well as) other WASM modules.
+    pub fn with_broadcast(self, broadcast: BroadcastFlags) -> Self {
+        self.shared.borrow_mut().broadcast = broadcast;
+        self
+    }
+
+    /// Send our range and ask the peer to answer with theirs.
+    pub fn announce(&self) -> bool {
+        let (event, hello, broadcast) = self.shared.borrow().message(HELLO);
+        try_call(&event, &hello, broadcast).unwrap_or(false)
+    }
+
+    /// The peer's supported versions, once announced.
+    pub fn peer(&self) -> Option<RangeInclusive<u16>> {
+        self.shared.borrow().peer.clone()
+    }
+
+    pub fn status(&self) -> VersionStatus {
+        let s = self.shared.borrow();
+        match &s.peer {
+            None => VersionStatus::Unknown,
+            Some(peer) => match negotiate(&s.supported, peer) {
+                Some(version) => VersionStatus::Agreed(version),
+                None => VersionStatus::Incompatible,
+            },
+        }
+    }
+
+    /// The version to send, once agreed.
+    pub fn agreed(&self) -> Option<u16> {
+        match self.status() {
+            VersionStatus::Agreed(version) => Some(version),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWN: u32 = 0x5eed;
+
+    fn shared() -> Shared {
+        Shared {
+            event: handshake_event("FMS_ROUTE"),
+            broadcast: BroadcastFlags::WASM,
+            supported: 1..=2,
+            peer: None,
+        }
+    }
+
+    #[test]
+    fn ignores_own_announcements() {
+        let mut s = shared();
+        let own = format!("hello 1 2 {OWN:08x}");
+        assert!(s.receive(own.as_bytes(), OWN).is_none());
+        assert_eq!(s.peer, None);
+
+        let hello = String::from_utf8(s.encode(HELLO)).unwrap();
+        assert_eq!(hello, format!("hello 1 2 {:08x}", sender_id()));
+        assert!(s.receive(hello.as_bytes(), sender_id()).is_none());
+    }
+
+    #[test]
+    fn records_peers_and_answers_hellos() {
+        let mut s = shared();
+        let (event, reply, _) = s.receive(b"hello 2 3 00000001", OWN).unwrap();
+        assert_eq!(event, "FMS_ROUTE:versions");
+        assert!(String::from_utf8(reply).unwrap().starts_with("reply 1 2 "));
+        assert_eq!(s.peer, Some(2..=3));
+
+        // JS peers may leave the sender out.
+        assert!(s.receive(b"reply 0 1\0", OWN).is_none());
+        assert_eq!(s.peer, Some(0..=1));
+        assert!(s.receive(b"hello x 1", OWN).is_none());
+        assert_eq!(s.peer, Some(0..=1));
+    }
+
+    #[test]
+    fn negotiates_highest_common() {
+        assert_eq!(negotiate(&(1..=3), &(2..=5)), Some(3));
+        assert_eq!(negotiate(&(1..=1), &(1..=1)), Some(1));
+        assert_eq!(negotiate(&(1..=2), &(3..=4)), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Helpers for talking to an instrument's HTML/JS side.
+//!
+//! [`emit`] sends JSON text to JS in one call, and [`JsBridge`] keeps an
+//! instrument's events under one namespace (`<namespace>/<name>`) with the
+//! broadcast flags set once, so a gauge and its panel agree on names without
+//! repeating them at every call site.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::js::{self, JsBridge};
+//!
+//! js::emit("INFINITY_MFD_READY", "{\"page\":\"map\"}")?;
+//!
+//! let mut mfd = JsBridge::new("infinity.mfd");
+//! mfd.on("softkey", |text| println!("softkey {text}"))?;   // infinity.mfd/softkey
+//!
+//! // in update():
+//! mfd.emit("range", "{\"nm\":20}")?;                        // infinity.mfd/range
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+//!
+//! On the JS side:
+//!
+//! ```text
+//! const listener = RegisterCommBusListener();
+//! listener.on("infinity.mfd/range", (json) => setRange(JSON.parse(json).nm));
+//! listener.callWasm("infinity.mfd/softkey", "3");
+//! ```
+
+use super::{BroadcastFlags, CommBusResult, SubscriptionSet, json, try_call};
+
+/// Send JSON text on `event` to JS.
+pub fn emit(event: &str, json: &str) -> CommBusResult<bool> {
+    try_call(event, json.as_bytes(), BroadcastFlags::JS)
+}
+
+/// Send `value`, encoded with [`json`], on `event` to JS.
+pub fn emit_value<T: json::Json>(event: &str, value: &T) -> CommBusResult<bool> {
+    try_call(event, &json::to_vec(value), BroadcastFlags::JS)
+}
+
+/// The text of a payload from JS, without the trailing NUL of C strings.
+pub fn text(bytes: &[u8]) -> String {
+    let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// One instrument's events, named `<namespace>/<name>`. Listeners are
+/// unregistered when the bridge is dropped.
+pub struct JsBridge {
+    namespace: String,
+    broadcast: BroadcastFlags,
+    subs: SubscriptionSet,
+}
+
+impl JsBridge {
+    /// Events under `namespace`, sent to JS.
+    pub fn new(namespace: &str) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            broadcast: BroadcastFlags::JS,
+            subs: SubscriptionSet::new(),
+        }
+    }
+
+    /// Send to other targets instead, e.g. `JS | WASM` to let another module
+    /// listen in.
+    pub fn with_broadcast(mut self, broadcast: BroadcastFlags) -> Self {
+        self.broadcast = broadcast;
+        self
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// The full event name for `name`.
+    pub fn event(&self, name: &str) -> String {
+        format!("{}/{name}", self.namespace)
+    }
+
+    /// Send raw bytes on `name`.
+    pub fn send(&self, name: &str, payload: &[u8]) -> CommBusResult<bool> {
+        try_call(&self.event(name), payload, self.broadcast)
+    }
+
+    /// Send JSON text on `name`.
+    pub fn emit(&self, name: &str, json: &str) -> CommBusResult<bool> {
+        self.send(name, json.as_bytes())
+    }
+
+    /// Send `value`, encoded with [`json`], on `name`.
+    pub fn emit_value<T: json::Json>(&self, name: &str, value: &T) -> CommBusResult<bool> {
+        self.send(name, &json::to_vec(value))
+    }
+
+    /// Call `cb` with the [`text`] of every message on `name`.
+    pub fn on(&mut self, name: &str, mut cb: impl FnMut(&str) + 'static) -> CommBusResult<()> {
+        self.subs
+            .add(&self.event(name), move |bytes| cb(&text(bytes)))
+    }
+
+    /// Call `cb` with every message on `name` that decodes as `T`; others are
+    /// dropped.
+    pub fn on_value<T: json::Json + 'static>(
+        &mut self,
+        name: &str,
+        mut cb: impl FnMut(T) + 'static,
+    ) -> CommBusResult<()> {
+        self.subs.add(&self.event(name), move |bytes| {
+            if let Ok(value) = json::from_slice(bytes) {
+                cb(value);
+            }
+        })
+    }
+
+    /// Stop listening on `name`.
+    pub fn off(&mut self, name: &str) -> bool {
+        self.subs.remove(&self.event(name)) > 0
+    }
+}
@@ -0,0 +1,502 @@
+//! Minimal JSON codec used by `#[derive(CommBusMessage)]`.
+//!
+//! Payloads are the JSON text of the message struct, so the JS side decodes them with
+//! `JSON.parse` into the generated TypeScript interface and encodes replies with
+//! `JSON.stringify`. Only the types that interface can describe are supported:
+//! numbers, `bool`, `char`/`String`, `Option`, `Vec`, arrays, `Box` and other
+//! derived messages.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Byte offset into the payload.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A value that can be written to and read from comm bus JSON.
+pub trait Json: Sized {
+    fn write_json(&self, out: &mut String);
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError>;
+
+    /// Value used when an object key is absent. Only `Option` has one.
+    #[inline]
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+/// Encode `value` as a JSON payload.
+pub fn to_vec<T: Json>(value: &T) -> Vec<u8> {
+    let mut out = String::new();
+    value.write_json(&mut out);
+    out.into_bytes()
+}
+
+/// Decode a JSON payload. Trailing NULs (C strings sent from JS) are ignored.
+pub fn from_slice<T: Json>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    let mut r = Reader::new(&bytes[..end]);
+    let value = T::read_json(&mut r)?;
+    r.skip_ws();
+    if r.pos < r.src.len() {
+        return Err(r.error("trailing characters"));
+    }
+    Ok(value)
+}
+
+/// Cursor over a JSON payload.
+pub struct Reader<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(src: &'a [u8]) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> DecodeError {
+        DecodeError {
+            offset: self.pos,
+            message: message.into(),
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    /// Next non-whitespace byte, without consuming it.
+    pub fn peek(&mut self) -> Option<u8> {
+        self.skip_ws();
+        self.src.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), DecodeError> {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", b as char)))
+        }
+    }
+
+    fn literal(&mut self, lit: &str) -> bool {
+        self.skip_ws();
+        if self.src[self.pos..].starts_with(lit.as_bytes()) {
+            self.pos += lit.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume `null` if it is next.
+    pub fn null(&mut self) -> bool {
+        self.literal("null")
+    }
+
+    pub fn bool(&mut self) -> Result<bool, DecodeError> {
+        if self.literal("true") {
+            Ok(true)
+        } else if self.literal("false") {
+            Ok(false)
+        } else {
+            Err(self.error("expected a boolean"))
+        }
+    }
+
+    /// The raw text of the next number.
+    pub fn number(&mut self) -> Result<&'a str, DecodeError> {
+        self.skip_ws();
+        let start = self.pos;
+        while let Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a number"));
+        }
+        // Only ASCII was consumed.
+        Ok(std::str::from_utf8(&self.src[start..self.pos]).unwrap_or_default())
+    }
+
+    pub fn string(&mut self) -> Result<String, DecodeError> {
+        self.expect(b'"')?;
+        let mut out = Vec::new();
+        loop {
+            let Some(&b) = self.src.get(self.pos) else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&esc) = self.src.get(self.pos) else {
+                        return Err(self.error("unterminated escape"));
+                    };
+                    self.pos += 1;
+                    match esc {
+                        b'"' | b'\\' | b'/' => out.push(esc),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0c),
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'u' => {
+                            let ch = self.unicode_escape()?;
+                            let mut buf = [0u8; 4];
+                            out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                }
+                _ => out.push(b),
+            }
+        }
+        String::from_utf8(out).map_err(|_| self.error("string is not UTF-8"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, DecodeError> {
+        let digits = self
+            .src
+            .get(self.pos..self.pos + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("invalid \\u escape"))?;
+        self.pos += 4;
+        Ok(digits)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, DecodeError> {
+        let hi = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&hi) {
+            if !self.literal("\\u") {
+                return Err(self.error("unpaired surrogate"));
+            }
+            let lo = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&lo) {
+                return Err(self.error("unpaired surrogate"));
+            }
+            0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+        } else {
+            hi
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid code point"))
+    }
+
+    /// Start an array; follow with [`Reader::next_element`] until it returns `false`.
+    pub fn begin_array(&mut self) -> Result<(), DecodeError> {
+        self.expect(b'[')
+    }
+
+    /// `true` if another element follows, `false` at the closing `]`.
+    pub fn next_element(&mut self, first: bool) -> Result<bool, DecodeError> {
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(false);
+        }
+        if !first {
+            self.expect(b',')?;
+        }
+        Ok(true)
+    }
+
+    /// Start an object; follow with [`Reader::next_key`] until it returns `None`.
+    pub fn begin_object(&mut self) -> Result<(), DecodeError> {
+        self.expect(b'{')
+    }
+
+    /// The next key (with its `:` consumed), or `None` at the closing `}`.
+    pub fn next_key(&mut self, first: bool) -> Result<Option<String>, DecodeError> {
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(None);
+        }
+        if !first {
+            self.expect(b',')?;
+        }
+        let key = self.string()?;
+        self.expect(b':')?;
+        Ok(Some(key))
+    }
+
+    /// Skip over a value of any type, e.g. for a key this side doesn't know.
+    pub fn skip_value(&mut self) -> Result<(), DecodeError> {
+        match self.peek() {
+            Some(b'"') => self.string().map(drop),
+            Some(b'{') => {
+                self.begin_object()?;
+                let mut first = true;
+                while self.next_key(first)?.is_some() {
+                    self.skip_value()?;
+                    first = false;
+                }
+                Ok(())
+            }
+            Some(b'[') => {
+                self.begin_array()?;
+                let mut first = true;
+                while self.next_element(first)? {
+                    self.skip_value()?;
+                    first = false;
+                }
+                Ok(())
+            }
+            Some(b't' | b'f') => self.bool().map(drop),
+            Some(b'n') if self.null() => Ok(()),
+            _ => self.number().map(drop),
+        }
+    }
+}
+
+/// Write `s` as a quoted, escaped JSON string.
+pub fn write_str(s: &str, out: &mut String) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+macro_rules! json_int {
+    ($($t:ty),*) => {$(
+        impl Json for $t {
+            #[inline]
+            fn write_json(&self, out: &mut String) {
+                out.push_str(&self.to_string());
+            }
+
+            fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+                let text = r.number()?;
+                // JS writes whole floats such as `1e3` or `2.0` for integers too.
+                // `MAX as f64` rounds up for 64-bit types, hence `< MAX + 1`.
+                text.parse::<$t>()
+                    .ok()
+                    .or_else(|| {
+                        text.parse::<f64>()
+                            .ok()
+                            .filter(|v| v.fract() == 0.0 && *v >= <$t>::MIN as f64 && *v < <$t>::MAX as f64 + 1.0)
+                            .map(|v| v as $t)
+                    })
+                    .ok_or_else(|| r.error(concat!("expected ", stringify!($t))))
+            }
+        }
+    )*};
+}
+
+json_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! json_float {
+    ($($t:ty),*) => {$(
+        impl Json for $t {
+            fn write_json(&self, out: &mut String) {
+                // JSON has no NaN/Infinity; JSON.stringify writes them as null too.
+                if self.is_finite() {
+                    out.push_str(&self.to_string());
+                } else {
+                    out.push_str("null");
+                }
+            }
+
+            fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+                if r.null() {
+                    return Ok(<$t>::NAN);
+                }
+                let text = r.number()?;
+                text.parse::<$t>()
+                    .map_err(|_| r.error(concat!("expected ", stringify!($t))))
+            }
+        }
+    )*};
+}
+
+json_float!(f32, f64);
+
+impl Json for bool {
+    fn write_json(&self, out: &mut String) {
+        out.push_str(if *self { "true" } else { "false" });
+    }
+
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        r.bool()
+    }
+}
+
+impl Json for String {
+    fn write_json(&self, out: &mut String) {
+        write_str(self, out);
+    }
+
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        r.string()
+    }
+}
+
+impl Json for char {
+    fn write_json(&self, out: &mut String) {
+        write_str(self.encode_utf8(&mut [0u8; 4]), out);
+    }
+
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        let s = r.string()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(r.error("expected a single character")),
+        }
+    }
+}
+
+impl<T: Json> Json for Option<T> {
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Some(v) => v.write_json(out),
+            None => out.push_str("null"),
+        }
+    }
+
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        if r.null() {
+            Ok(None)
+        } else {
+            T::read_json(r).map(Some)
+        }
+    }
+
+    #[inline]
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+impl<T: Json> Json for Box<T> {
+    fn write_json(&self, out: &mut String) {
+        (**self).write_json(out);
+    }
+
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        T::read_json(r).map(Box::new)
+    }
+}
+
+fn write_seq<'a, T: Json + 'a>(items: impl IntoIterator<Item = &'a T>, out: &mut String) {
+    out.push('[');
+    for (i, item) in items.into_iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        item.write_json(out);
+    }
+    out.push(']');
+}
+
+impl<T: Json> Json for Vec<T> {
+    fn write_json(&self, out: &mut String) {
+        write_seq(self, out);
+    }
+
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        r.begin_array()?;
+        let mut items = Vec::new();
+        while r.next_element(items.is_empty())? {
+            items.push(T::read_json(r)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: Json, const N: usize> Json for [T; N] {
+    fn write_json(&self, out: &mut String) {
+        write_seq(self, out);
+    }
+
+    fn read_json(r: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        let items = Vec::<T>::read_json(r)?;
+        <[T; N]>::try_from(items).map_err(|_| r.error(format!("expected {N} elements")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: Json + PartialEq + fmt::Debug>(value: T) {
+        assert_eq!(from_slice::<T>(&to_vec(&value)).unwrap(), value);
+    }
+
+    #[test]
+    fn string_escapes() {
+        let mut out = String::new();
+        write_str("a\"b\\c\nd\te\u{1}", &mut out);
+        assert_eq!(out, r#""a\"b\\c\nd\te\u0001""#);
+        round_trip("quote \" slash \\ tab \t nul \0 é".to_string());
+
+        let decoded: String = from_slice(br#""\/\b\f\r\u00e9""#).unwrap();
+        assert_eq!(decoded, "/\u{8}\u{c}\ré");
+        assert!(from_slice::<String>(br#""\x""#).is_err());
+        assert!(from_slice::<String>(br#""open"#).is_err());
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        let decoded: String = from_slice(br#""\ud83d\ude00""#).unwrap();
+        assert_eq!(decoded, "\u{1f600}");
+        round_trip('\u{1f600}');
+        assert!(from_slice::<String>(br#""\ud83d""#).is_err());
+        assert!(from_slice::<String>(br#""\ud83d\u0041""#).is_err());
+    }
+
+    #[test]
+    fn integer_limits() {
+        round_trip(u64::MAX);
+        round_trip(i64::MIN);
+        assert_eq!(
+            from_slice::<u64>(b"18446744073709551615").unwrap(),
+            u64::MAX
+        );
+        assert!(from_slice::<u64>(b"18446744073709551616").is_err());
+        assert!(from_slice::<u64>(b"1.8446744073709552e19").is_err());
+        assert_eq!(from_slice::<u8>(b"255.0").unwrap(), 255);
+        assert!(from_slice::<u8>(b"256").is_err());
+        assert_eq!(from_slice::<i32>(b"1e3").unwrap(), 1000);
+        assert!(from_slice::<i32>(b"1.5").is_err());
+    }
+
+    #[test]
+    fn trailing_nuls() {
+        assert_eq!(from_slice::<Vec<u8>>(b"[1,2]\0\0").unwrap(), vec![1, 2]);
+        assert!(from_slice::<bool>(b" true \0").unwrap());
+        assert!(from_slice::<bool>(b"true x\0").is_err());
+        assert!(from_slice::<Option<u8>>(b"\0").is_err());
+    }
+
+    #[test]
+    fn floats_and_options() {
+        assert_eq!(to_vec(&f64::NAN), b"null");
+        assert!(from_slice::<f64>(b"null").unwrap().is_nan());
+        round_trip(Some(vec![1.5f64, -2.0]));
+        round_trip(None::<String>);
+        round_trip([1u16, 2, 3]);
+        assert!(from_slice::<[u16; 2]>(b"[1,2,3]").is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! Per-event traffic counters, kept for every [`call`](super::call) and every
+//! message delivered to a [`Subscription`](super::Subscription).
+
+use std::{cell::RefCell, collections::HashMap};
+
+/// Counters for one event since the module started or
+/// [`reset_metrics`] was called.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventMetrics {
+    /// Calls the sim accepted.
+    pub sent: u64,
+    pub sent_bytes: u64,
+    /// Calls the sim refused, or that failed on an invalid event name.
+    pub send_failures: u64,
+    /// Deliveries to this module's subscriptions; a message reaching two
+    /// subscriptions counts twice.
+    pub received: u64,
+    pub received_bytes: u64,
+}
+
+impl EventMetrics {
+    fn add(&mut self, other: &EventMetrics) {
+        self.sent += other.sent;
+        self.sent_bytes += other.sent_bytes;
+        self.send_failures += other.send_failures;
+        self.received += other.received;
+        self.received_bytes += other.received_bytes;
+    }
+}
+
+thread_local! {
+    static METRICS: RefCell<HashMap<String, EventMetrics>> = RefCell::new(HashMap::new());
+}
+
+fn with_event(event: &str, f: impl FnOnce(&mut EventMetrics)) {
+    METRICS.with(|m| {
+        let mut m = m.borrow_mut();
+        match m.get_mut(event) {
+            Some(metrics) => f(metrics),
+            None => f(m.entry(event.to_string()).or_default()),
+        }
+    });
+}
+
+pub(crate) fn record_sent(event: &str, bytes: usize, ok: bool) {
+    with_event(event, |m| {
+        if ok {
+            m.sent += 1;
+            m.sent_bytes += bytes as u64;
+        } else {
+            m.send_failures += 1;
+        }
+    });
+}
+
+pub(crate) fn record_received(event: &str, bytes: usize) {
+    with_event(event, |m| {
+        m.received += 1;
+        m.received_bytes += bytes as u64;
+    });
+}
+
+/// Counters for every event with traffic, sorted by event name.
+pub fn metrics() -> Vec<(String, EventMetrics)> {
+    let mut all: Vec<_> = METRICS.with(|m| {
+        m.borrow()
+            .iter()
+            .map(|(event, metrics)| (event.clone(), *metrics))
+            .collect()
+    });
+    all.sort_by(|a, b| a.0.cmp(&b.0));
+    all
+}
+
+/// Counters for `event`; all zero if it had no traffic.
+pub fn event_metrics(event: &str) -> EventMetrics {
+    METRICS.with(|m| m.borrow().get(event).copied().unwrap_or_default())
+}
+
+/// Counters summed over all events.
+pub fn total_metrics() -> EventMetrics {
+    METRICS.with(|m| {
+        let mut total = EventMetrics::default();
+        for metrics in m.borrow().values() {
+            total.add(metrics);
+        }
+        total
+    })
+}
+
+/// Zero all counters, e.g. to measure load over a fixed window.
+pub fn reset_metrics() {
+    METRICS.with(|m| m.borrow_mut().clear());
+}
@@ -1,10 +1,118 @@
+#[cfg(feature = "postcard")]
+pub mod binary;
+pub mod buffered;
+pub mod channel;
+pub mod chunked;
+pub mod client_data;
+pub mod envelope;
+pub mod js;
+pub mod json;
+mod metrics;
+pub mod router;
+pub mod rpc;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod shared;
+pub mod sniffer;
+#[cfg(feature = "serde")]
+pub mod typed;
+
+pub use metrics::{EventMetrics, event_metrics, metrics, reset_metrics, total_metrics};
+
 use crate::sys::*;
 use std::{
-    ffi::CString,
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque, hash_map::RandomState},
+    ffi::{CString, NulError},
+    fmt,
+    hash::BuildHasher,
+    ops::ControlFlow,
     os::raw::{c_char, c_void},
     ptr::NonNull,
+    rc::Rc,
+    time::SystemTime,
 };
 
+/// Live [`Subscription`]s per module before [`Subscription::try_subscribe`]
+/// refuses more. The sim doesn't say why a registration fails, so the cap turns
+/// a leak (e.g. subscribing every frame) into a distinct error.
+pub const MAX_SUBSCRIPTIONS: usize = 1024;
+
+thread_local! {
+    static LIVE: Cell<usize> = const { Cell::new(0) };
+    static QUEUED: Cell<bool> = const { Cell::new(false) };
+    static QUEUE: RefCell<VecDeque<Queued>> = const { RefCell::new(VecDeque::new()) };
+    static NEXT_SEQ: Cell<u64> = const { Cell::new(0) };
+    static DETACHED: RefCell<HashMap<u64, Detached>> = RefCell::new(HashMap::new());
+    static FINISHED: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+    static NEXT_DETACHED: Cell<u64> = const { Cell::new(1) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommBusError {
+    /// The event name contains a NUL byte.
+    Nul(NulError),
+    /// `fsCommBusRegister` refused the callback.
+    RegistrationFailed,
+    /// [`MAX_SUBSCRIPTIONS`] subscriptions are already live.
+    TooManySubscriptions,
+}
+
+impl fmt::Display for CommBusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommBusError::Nul(e) => write!(f, "invalid event name: {e}"),
+            CommBusError::RegistrationFailed => f.write_str("the sim refused the registration"),
+            CommBusError::TooManySubscriptions => {
+                write!(f, "more than {MAX_SUBSCRIPTIONS} live subscriptions")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommBusError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommBusError::Nul(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for CommBusError {
+    fn from(e: NulError) -> Self {
+        CommBusError::Nul(e)
+    }
+}
+
+pub type CommBusResult<T> = Result<T, CommBusError>;
+
+/// A random id for this module, fixed while it's loaded and never 0.
+///
+/// Every module counts its own request and transfer ids from 1, so protocols
+/// built on the bus ([`rpc`], [`chunked`], [`schema`]) send this along to
+/// keep ids from different modules apart and to recognize their own
+/// broadcasts.
+pub fn sender_id() -> u32 {
+    thread_local! {
+        // Random hasher keys where the runtime has them, plus the clock.
+        static SENDER_ID: u32 = {
+            let hash = RandomState::new().hash_one(SystemTime::now());
+            ((hash >> 32) as u32 ^ hash as u32).max(1)
+        };
+    }
+    SENDER_ID.with(|id| *id)
+}
+
+// What the pre-`CommBusError` signatures returned for failures that aren't NUL
+// bytes.
+fn legacy_error(e: CommBusError) -> NulError {
+    match e {
+        CommBusError::Nul(e) => e,
+        _ => CString::new(vec![0u8]).unwrap_err(),
+    }
+}
+
 bitflags::bitflags! {
         // #[derive(Debug, Copy, Clone)]
     pub struct BroadcastFlags: u8 {
@@ -25,38 +133,150 @@ impl BroadcastFlags {
     }
 }
 
+/// A typed comm bus message, usually implemented with `#[derive(CommBusMessage)]`.
+///
+/// Payloads are JSON (see [`json`]) matching the generated TypeScript interface.
+pub trait CommBusMessage: json::Json {
+    /// Comm bus event name the message is sent on.
+    const EVENT: &'static str;
+
+    /// TypeScript definitions (event name constant + interface) for the JS side.
+    const TYPESCRIPT: &'static str;
+
+    /// Hash of the message layout (struct name, field names and types). Exported to
+    /// TypeScript as `<NAME>_SCHEMA` and compared by [`schema::SchemaHandshake`].
+    const SCHEMA_HASH: u32;
+
+    fn encode(&self) -> Vec<u8> {
+        json::to_vec(self)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, json::DecodeError> {
+        json::from_slice(bytes)
+    }
+
+    /// Send the message on [`Self::EVENT`].
+    fn publish(&self, broadcast: BroadcastFlags) -> CommBusResult<bool> {
+        try_call(Self::EVENT, &self.encode(), broadcast)
+    }
+
+    /// Call `cb` with every message received on [`Self::EVENT`]. Payloads that don't
+    /// decode are dropped.
+    fn subscribe(mut cb: impl FnMut(Self) + 'static) -> CommBusResult<Subscription>
+    where
+        Self: 'static,
+    {
+        Subscription::try_subscribe(Self::EVENT, move |bytes| {
+            if let Ok(msg) = Self::decode(bytes) {
+                cb(msg);
+            }
+        })
+    }
+}
+
 struct CallbackState {
+    event: String,
     cb: Box<dyn FnMut(&[u8]) + 'static>,
 }
 
+/// A message held for [`poll`].
+struct Queued {
+    seq: u64,
+    state: *mut CallbackState,
+    bytes: Vec<u8>,
+}
+
 extern "C" fn commbus_trampoline(buf: *const c_char, buf_size: u32, ctx: *mut c_void) {
     if ctx.is_null() {
         return;
     }
 
+    let bytes = if buf.is_null() || buf_size == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(buf as *const u8, buf_size as usize) }
+    };
     let st = unsafe { &mut *(ctx as *mut CallbackState) };
+    metrics::record_received(&st.event, bytes.len());
 
-    if buf.is_null() || buf_size == 0 {
-        (st.cb)(&[]);
+    if QUEUED.with(Cell::get) {
+        let seq = NEXT_SEQ.with(|n| n.replace(n.get() + 1));
+        QUEUE.with(|q| {
+            q.borrow_mut().push_back(Queued {
+                seq,
+                state: ctx as *mut CallbackState,
+                bytes: bytes.to_vec(),
+            })
+        });
         return;
     }
 
-    let bytes = unsafe { std::slice::from_raw_parts(buf as *const u8, buf_size as usize) };
     (st.cb)(bytes);
 }
 
+/// Hold incoming messages until [`poll`] instead of running callbacks as soon
+/// as the sim delivers them. The export macros call [`poll`] at the start of
+/// every `update()`, so in this mode callbacks always run there, before the
+/// module's own `update` borrows anything.
+///
+/// Turning the mode off doesn't drop messages already queued; the next
+/// [`poll`] still delivers them.
+pub fn set_queued(queued: bool) {
+    QUEUED.with(|q| q.set(queued));
+}
+
+pub fn is_queued() -> bool {
+    QUEUED.with(Cell::get)
+}
+
+/// Messages waiting for [`poll`].
+pub fn pending() -> usize {
+    QUEUE.with(|q| q.borrow().len())
+}
+
+/// Run the callbacks for messages queued since the last call, in arrival
+/// order. Messages that handlers send to this module while it runs wait for
+/// the next call. Messages for subscriptions dropped in the meantime are
+/// discarded.
+///
+/// Also unregisters the [`subscribe_until`] subscriptions that have finished.
+pub fn poll() {
+    let end = NEXT_SEQ.with(Cell::get);
+    loop {
+        // Pop one at a time so a handler can drop subscriptions or publish.
+        let next = QUEUE.with(|q| {
+            let mut q = q.borrow_mut();
+            if q.front().is_some_and(|m| m.seq < end) {
+                q.pop_front()
+            } else {
+                None
+            }
+        });
+        let Some(message) = next else {
+            break;
+        };
+        let st = unsafe { &mut *message.state };
+        (st.cb)(&message.bytes);
+    }
+    sweep_detached();
+}
+
 pub struct Subscription {
     event: CString,
     state: NonNull<CallbackState>,
 }
 
 impl Subscription {
-    pub fn subscribe(
-        event: &str,
-        cb: impl FnMut(&[u8]) + 'static,
-    ) -> Result<Self, std::ffi::NulError> {
+    pub fn try_subscribe(event: &str, cb: impl FnMut(&[u8]) + 'static) -> CommBusResult<Self> {
+        let name = event.to_string();
         let event = CString::new(event)?;
-        let st = Box::new(CallbackState { cb: Box::new(cb) });
+        if LIVE.with(Cell::get) >= MAX_SUBSCRIPTIONS {
+            return Err(CommBusError::TooManySubscriptions);
+        }
+        let st = Box::new(CallbackState {
+            event: name,
+            cb: Box::new(cb),
+        });
         let state_ptr = NonNull::new(Box::into_raw(st)).expect("Box::into_raw never null");
 
         let ok = unsafe {
@@ -71,17 +291,28 @@ impl Subscription {
             unsafe {
                 drop(Box::from_raw(state_ptr.as_ptr()));
             }
-            // Surface a real error value without changing the public API.
-            // Registration failure isn't a NUL error, but the old signature
-            // uses `NulError`, so we synthesize one.
-            return Err(CString::new(vec![0u8]).unwrap_err());
+            return Err(CommBusError::RegistrationFailed);
         }
 
+        LIVE.with(|n| n.set(n.get() + 1));
         Ok(Self {
             event,
             state: state_ptr,
         })
     }
+
+    /// The event this subscription listens to.
+    pub fn event(&self) -> &str {
+        // Built from a `&str`, so always UTF-8.
+        self.event.to_str().unwrap_or_default()
+    }
+
+    /// [`Subscription::try_subscribe`] with the old error type. Failures
+    /// other than a NUL byte come back as a synthesized [`NulError`].
+    #[deprecated(note = "use `Subscription::try_subscribe`, which returns `CommBusError`")]
+    pub fn subscribe(event: &str, cb: impl FnMut(&[u8]) + 'static) -> Result<Self, NulError> {
+        Self::try_subscribe(event, cb).map_err(legacy_error)
+    }
 }
 
 impl Drop for Subscription {
@@ -95,15 +326,87 @@ impl Drop for Subscription {
 
             drop(Box::from_raw(self.state.as_ptr()));
         }
+        let state = self.state.as_ptr();
+        QUEUE.with(|q| q.borrow_mut().retain(|m| m.state != state));
+        LIVE.with(|n| n.set(n.get() - 1));
     }
 }
 
-pub fn call(
-    event: &str,
-    payload: &[u8],
-    broadcast: BroadcastFlags,
-) -> Result<bool, std::ffi::NulError> {
-    let event = CString::new(event)?;
+/// Owns any number of [`Subscription`]s, added and removed by event name;
+/// everything is unregistered when the set is dropped.
+///
+/// ```no_run
+/// use msfs::comm_bus::SubscriptionSet;
+///
+/// let mut subs = SubscriptionSet::new();
+/// subs.add("fms/route", |bytes| println!("route: {} bytes", bytes.len()))?;
+/// subs.add("fms/perf", |bytes| println!("perf: {} bytes", bytes.len()))?;
+///
+/// // later, stop listening to one event:
+/// subs.remove("fms/perf");
+/// # Ok::<(), msfs::comm_bus::CommBusError>(())
+/// ```
+#[derive(Default)]
+pub struct SubscriptionSet {
+    subs: Vec<Subscription>,
+}
+
+impl SubscriptionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe `cb` to `event`. An event can have several callbacks.
+    pub fn add(&mut self, event: &str, cb: impl FnMut(&[u8]) + 'static) -> CommBusResult<()> {
+        self.subs.push(Subscription::try_subscribe(event, cb)?);
+        Ok(())
+    }
+
+    /// Take ownership of an existing subscription.
+    pub fn insert(&mut self, sub: Subscription) {
+        self.subs.push(sub);
+    }
+
+    /// Unsubscribe every callback on `event`. Returns how many were removed.
+    pub fn remove(&mut self, event: &str) -> usize {
+        let before = self.subs.len();
+        self.subs.retain(|s| s.event() != event);
+        before - self.subs.len()
+    }
+
+    pub fn contains(&self, event: &str) -> bool {
+        self.subs.iter().any(|s| s.event() == event)
+    }
+
+    /// The subscribed events, in the order they were added; an event with
+    /// several callbacks appears once per callback.
+    pub fn events(&self) -> impl Iterator<Item = &str> {
+        self.subs.iter().map(Subscription::event)
+    }
+
+    pub fn len(&self) -> usize {
+        self.subs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subs.is_empty()
+    }
+
+    /// Unsubscribe everything.
+    pub fn clear(&mut self) {
+        self.subs.clear();
+    }
+}
+
+pub fn try_call(event: &str, payload: &[u8], broadcast: BroadcastFlags) -> CommBusResult<bool> {
+    let name = event;
+    let event = match CString::new(name) {
+        Ok(event) => event,
+        Err(e) => {
+            metrics::record_sent(name, payload.len(), false);
+            return Err(e.into());
+        }
+    };
     let ok = unsafe {
         fsCommBusCall(
             event.as_ptr(),
@@ -112,5 +415,120 @@ pub fn call(
             broadcast.to_ffi(),
         )
     };
+    metrics::record_sent(name, payload.len(), ok);
     Ok(ok)
 }
+
+/// [`try_call`] with the old error type.
+#[deprecated(note = "use `try_call`, which returns `CommBusError`")]
+pub fn call(event: &str, payload: &[u8], broadcast: BroadcastFlags) -> Result<bool, NulError> {
+    try_call(event, payload, broadcast).map_err(legacy_error)
+}
+
+/// What a [`subscribe_until`] callback returns: [`ControlFlow::Break`] or
+/// `false` to unsubscribe, [`ControlFlow::Continue`] or `true` to keep going.
+pub trait CallbackFlow {
+    fn into_flow(self) -> ControlFlow<()>;
+}
+
+impl CallbackFlow for ControlFlow<()> {
+    fn into_flow(self) -> ControlFlow<()> {
+        self
+    }
+}
+
+impl CallbackFlow for bool {
+    fn into_flow(self) -> ControlFlow<()> {
+        if self {
+            ControlFlow::Continue(())
+        } else {
+            ControlFlow::Break(())
+        }
+    }
+}
+
+/// A subscription owned by the module, from [`subscribe_until`] or [`once`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DetachedId(u64);
+
+struct Detached {
+    _sub: Subscription,
+    done: Rc<Cell<bool>>,
+}
+
+/// Subscribe `cb` to `event` until it returns [`ControlFlow::Break`] (or
+/// `false`). Nothing needs to be kept: the subscription stops receiving
+/// immediately and is unregistered at the next [`poll`], which the export
+/// macros call every update.
+///
+/// ```no_run
+/// use msfs::comm_bus;
+/// use std::ops::ControlFlow;
+///
+/// comm_bus::subscribe_until("FMS_READY", |bytes| {
+///     if bytes == b"ok" {
+///         println!("FMS up");
+///         return ControlFlow::Break(());
+///     }
+///     ControlFlow::Continue(())
+/// })?;
+/// # Ok::<(), msfs::comm_bus::CommBusError>(())
+/// ```
+pub fn subscribe_until<F: CallbackFlow>(
+    event: &str,
+    mut cb: impl FnMut(&[u8]) -> F + 'static,
+) -> CommBusResult<DetachedId> {
+    let id = NEXT_DETACHED.with(|n| n.replace(n.get() + 1));
+    let done = Rc::new(Cell::new(false));
+    let flag = Rc::clone(&done);
+    let sub = Subscription::try_subscribe(event, move |bytes| {
+        if flag.get() {
+            return;
+        }
+        if cb(bytes).into_flow().is_break() {
+            flag.set(true);
+            FINISHED.with(|f| f.borrow_mut().push(id));
+        }
+    })?;
+    DETACHED.with(|d| d.borrow_mut().insert(id, Detached { _sub: sub, done }));
+    Ok(DetachedId(id))
+}
+
+/// Call `cb` with the next message on `event` only.
+pub fn once(event: &str, cb: impl FnOnce(&[u8]) + 'static) -> CommBusResult<DetachedId> {
+    let mut cb = Some(cb);
+    subscribe_until(event, move |bytes| {
+        if let Some(cb) = cb.take() {
+            cb(bytes);
+        }
+        ControlFlow::Break(())
+    })
+}
+
+/// Stop a [`subscribe_until`] subscription from outside its callback. `false`
+/// if it had already finished.
+pub fn unsubscribe(id: DetachedId) -> bool {
+    let Some(done) = DETACHED.with(|d| d.borrow().get(&id.0).map(|d| Rc::clone(&d.done))) else {
+        return false;
+    };
+    if done.replace(true) {
+        return false;
+    }
+    FINISHED.with(|f| f.borrow_mut().push(id.0));
+    true
+}
+
+/// Whether a [`subscribe_until`] subscription is still receiving.
+pub fn is_subscribed(id: DetachedId) -> bool {
+    DETACHED.with(|d| d.borrow().get(&id.0).is_some_and(|d| !d.done.get()))
+}
+
+// Finished subscriptions can't unregister from inside their own callback, so
+// they're dropped here instead.
+fn sweep_detached() {
+    let finished = FINISHED.with(|f| std::mem::take(&mut *f.borrow_mut()));
+    for id in finished {
+        let detached = DETACHED.with(|d| d.borrow_mut().remove(&id));
+        drop(detached);
+    }
+}
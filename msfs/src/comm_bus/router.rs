@@ -0,0 +1,158 @@
+//! Many topics over one comm bus registration.
+//!
+//! A [`Router`] registers a single callback on its namespace's dispatch event
+//! (`<namespace>/*`) and hands each message to the handler for its topic, so
+//! adding a topic at runtime costs a map entry instead of a native
+//! registration. Messages carry the topic in front of the payload:
+//!
+//! ```text
+//! <topic>\n<payload>
+//! ```
+//!
+//! which JS can build with `` `${topic}\n${json}` ``.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, router::{self, Router}};
+//!
+//! let router = Router::new("infinity.fms")?;   // one registration on infinity.fms/*
+//! router.route("route/activate", |_| println!("activate"));
+//! router.route("perf/cost_index", |bytes| println!("CI {}", String::from_utf8_lossy(bytes)));
+//!
+//! // from another module (or JS):
+//! router::send("infinity.fms", "perf/cost_index", b"35", BroadcastFlags::ALL_WASM)?;
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusResult, Subscription, try_call};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+type Handler = Rc<RefCell<dyn FnMut(&[u8])>>;
+type Fallback = Rc<RefCell<dyn FnMut(&str, &[u8])>>;
+
+/// The one event a [`Router`] for `namespace` is registered on.
+pub fn dispatch_event(namespace: &str) -> String {
+    format!("{namespace}/*")
+}
+
+/// `payload` addressed to `topic`.
+pub fn frame(topic: &str, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(topic.len() + 1 + payload.len());
+    out.extend_from_slice(topic.as_bytes());
+    out.push(b'\n');
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Topic and payload of a routed message; `None` without a topic line.
+pub fn unframe(bytes: &[u8]) -> Option<(&str, &[u8])> {
+    let split = bytes.iter().position(|&b| b == b'\n')?;
+    let topic = std::str::from_utf8(&bytes[..split]).ok()?;
+    Some((topic, &bytes[split + 1..]))
+}
+
+/// Send `payload` to the handler for `topic` of the routers on `namespace`.
+pub fn send(
+    namespace: &str,
+    topic: &str,
+    payload: &[u8],
+    broadcast: BroadcastFlags,
+) -> CommBusResult<bool> {
+    try_call(
+        &dispatch_event(namespace),
+        &frame(topic, payload),
+        broadcast,
+    )
+}
+
+#[derive(Default)]
+struct Routes {
+    handlers: HashMap<String, Handler>,
+    fallback: Option<Fallback>,
+}
+
+/// Routes the messages of one namespace to per-topic handlers.
+pub struct Router {
+    namespace: String,
+    routes: Rc<RefCell<Routes>>,
+    _sub: Subscription,
+}
+
+impl Router {
+    /// Register on [`dispatch_event`] for `namespace`.
+    pub fn new(namespace: &str) -> CommBusResult<Self> {
+        let routes = Rc::new(RefCell::new(Routes::default()));
+        let inbox = Rc::clone(&routes);
+        let sub = Subscription::try_subscribe(&dispatch_event(namespace), move |bytes| {
+            let Some((topic, payload)) = unframe(bytes) else {
+                return;
+            };
+            // Handlers may add or remove routes, so none are borrowed while
+            // one runs. A handler that sends to its own topic isn't re-entered.
+            let (handler, fallback) = {
+                let routes = inbox.borrow();
+                (routes.handlers.get(topic).cloned(), routes.fallback.clone())
+            };
+            if let Some(handler) = handler {
+                if let Ok(mut handler) = handler.try_borrow_mut() {
+                    handler(payload);
+                }
+            } else if let Some(fallback) = fallback
+                && let Ok(mut fallback) = fallback.try_borrow_mut()
+            {
+                fallback(topic, payload);
+            }
+        })?;
+
+        Ok(Self {
+            namespace: namespace.to_string(),
+            routes,
+            _sub: sub,
+        })
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Call `handler` with every message for `topic`, replacing any handler
+    /// it had.
+    pub fn route(&self, topic: &str, handler: impl FnMut(&[u8]) + 'static) {
+        let handler: Handler = Rc::new(RefCell::new(handler));
+        self.routes
+            .borrow_mut()
+            .handlers
+            .insert(topic.to_string(), handler);
+    }
+
+    /// Stop routing `topic`.
+    pub fn unroute(&self, topic: &str) -> bool {
+        self.routes.borrow_mut().handlers.remove(topic).is_some()
+    }
+
+    pub fn is_routed(&self, topic: &str) -> bool {
+        self.routes.borrow().handlers.contains_key(topic)
+    }
+
+    /// The routed topics, in no particular order.
+    pub fn topics(&self) -> Vec<String> {
+        self.routes.borrow().handlers.keys().cloned().collect()
+    }
+
+    /// Call `fallback` with the topic and payload of messages no handler is
+    /// routed for.
+    pub fn on_unrouted(&self, fallback: impl FnMut(&str, &[u8]) + 'static) {
+        let fallback: Fallback = Rc::new(RefCell::new(fallback));
+        self.routes.borrow_mut().fallback = Some(fallback);
+    }
+
+    /// Send `payload` to `topic` of this namespace's routers.
+    pub fn send(
+        &self,
+        topic: &str,
+        payload: &[u8],
+        broadcast: BroadcastFlags,
+    ) -> CommBusResult<bool> {
+        send(&self.namespace, topic, payload, broadcast)
+    }
+}
@@ -0,0 +1,298 @@
+//! Request/response over the comm bus.
+//!
+//! A request on `event` carries a correlation id in front of the payload; the
+//! server answers on `<event>:response` with the same id, and [`call`]'s
+//! handle matches the answer to its request:
+//!
+//! ```text
+//! request   event            [id: u64 LE][payload]
+//! response  event:response   [id: u64 LE][payload]
+//! ```
+//!
+//! The high half of the id is the caller's [`sender_id`], the low half counts
+//! its requests, so answers to several modules calling the same server can't
+//! be mixed up. JS peers can speak the same framing on a `Uint8Array`, with
+//! any sender half of their own.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, rpc};
+//! use std::time::Duration;
+//!
+//! // server, e.g. in the FMS module:
+//! let _server = rpc::serve("FMS_DISTANCE", BroadcastFlags::ALL_WASM, |request| {
+//!     let _ = request;
+//!     1234.5f64.to_le_bytes().to_vec()
+//! })?;
+//!
+//! // client, e.g. in a gauge:
+//! let mut pending = rpc::call("FMS_DISTANCE", b"KJFK", Duration::from_secs(2), BroadcastFlags::ALL_WASM)?;
+//!
+//! // in update():
+//! match pending.poll() {
+//!     Some(Ok(reply)) => println!("{} bytes back", reply.len()),
+//!     Some(Err(e)) => println!("request failed: {e}"),
+//!     None => {} // still waiting
+//! }
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusResult, Subscription, sender_id, try_call as bus_call};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+const ID_LEN: usize = 8;
+
+/// Event the answers to requests on `event` are sent on.
+pub fn response_event(event: &str) -> String {
+    format!("{event}:response")
+}
+
+fn frame(id: u64, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(ID_LEN + payload.len());
+    out.extend_from_slice(&id.to_le_bytes());
+    out.extend_from_slice(payload);
+    out
+}
+
+fn unframe(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let id = u64::from_le_bytes(bytes.get(..ID_LEN)?.try_into().ok()?);
+    Some((id, &bytes[ID_LEN..]))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcError {
+    /// No response within the timeout.
+    Timeout,
+    /// The request couldn't be sent.
+    SendFailed,
+    /// [`RpcCall::poll`] was called again after returning the result.
+    Finished,
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcError::Timeout => f.write_str("no response before the timeout"),
+            RpcError::SendFailed => f.write_str("the request could not be sent"),
+            RpcError::Finished => f.write_str("the result was already taken"),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+type Responses = Rc<RefCell<HashMap<u64, Option<Vec<u8>>>>>;
+
+/// One subscription per response event, shared by the calls waiting on it.
+struct Listener {
+    _subscription: Subscription,
+    // Waiting ids, and the response once it arrives.
+    responses: Responses,
+}
+
+thread_local! {
+    static LISTENERS: RefCell<HashMap<String, Listener>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u32> = const { Cell::new(1) };
+}
+
+/// The correlation id of request `n` from the module `sender`.
+fn call_id(sender: u32, n: u32) -> u64 {
+    (sender as u64) << 32 | n as u64
+}
+
+fn next_id() -> u64 {
+    let n = NEXT_ID.with(|n| {
+        let id = n.get();
+        n.set(id.wrapping_add(1).max(1));
+        id
+    });
+    call_id(sender_id(), n)
+}
+
+/// Store a response in `responses` if it answers one of its requests.
+fn deliver(responses: &Responses, bytes: &[u8]) {
+    let Some((id, payload)) = unframe(bytes) else {
+        return;
+    };
+    if let Some(slot) = responses.borrow_mut().get_mut(&id) {
+        *slot = Some(payload.to_vec());
+    }
+}
+
+/// The waiting-table for `response`, subscribing on first use.
+fn responses(response: &str) -> CommBusResult<Responses> {
+    if let Some(responses) =
+        LISTENERS.with(|l| l.borrow().get(response).map(|l| l.responses.clone()))
+    {
+        return Ok(responses);
+    }
+    let responses: Responses = Rc::default();
+    let table = responses.clone();
+    let subscription = Subscription::try_subscribe(response, move |bytes| deliver(&table, bytes))?;
+    LISTENERS.with(|l| {
+        l.borrow_mut().insert(
+            response.to_string(),
+            Listener {
+                _subscription: subscription,
+                responses: responses.clone(),
+            },
+        )
+    });
+    Ok(responses)
+}
+
+/// A request waiting for its response, from [`call`].
+pub struct RpcCall {
+    id: u64,
+    response_event: String,
+    responses: Responses,
+    deadline: Instant,
+    result: Option<Result<Vec<u8>, RpcError>>,
+    done: bool,
+}
+
+impl RpcCall {
+    /// The response, an error, or `None` while still waiting.
+    pub fn poll(&mut self) -> Option<Result<Vec<u8>, RpcError>> {
+        if self.done {
+            return Some(Err(RpcError::Finished));
+        }
+        if self.result.is_none() {
+            let response = self
+                .responses
+                .borrow_mut()
+                .get_mut(&self.id)
+                .and_then(Option::take);
+            if let Some(response) = response {
+                self.result = Some(Ok(response));
+            } else if Instant::now() >= self.deadline {
+                self.result = Some(Err(RpcError::Timeout));
+            }
+        }
+        let result = self.result.take()?;
+        self.done = true;
+        self.forget();
+        Some(result)
+    }
+
+    /// The correlation id sent with the request.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn forget(&mut self) {
+        let empty = {
+            let mut responses = self.responses.borrow_mut();
+            responses.remove(&self.id);
+            responses.is_empty()
+        };
+        if empty {
+            // Last waiting call on this event: drop the subscription.
+            let listener = LISTENERS.with(|l| l.borrow_mut().remove(&self.response_event));
+            drop(listener);
+        }
+    }
+}
+
+impl Drop for RpcCall {
+    fn drop(&mut self) {
+        if !self.done {
+            self.forget();
+        }
+    }
+}
+
+/// Send `payload` on `event` and wait up to `timeout` for the answer on
+/// [`response_event`]. `Err` if the response listener can't be set up; send
+/// failures are reported by [`RpcCall::poll`].
+pub fn call(
+    event: &str,
+    payload: &[u8],
+    timeout: Duration,
+    broadcast: BroadcastFlags,
+) -> CommBusResult<RpcCall> {
+    let response_event = response_event(event);
+    let responses = responses(&response_event)?;
+    let id = next_id();
+    responses.borrow_mut().insert(id, None);
+    let mut rpc = RpcCall {
+        id,
+        response_event,
+        responses,
+        deadline: Instant::now() + timeout,
+        result: None,
+        done: false,
+    };
+    if !bus_call(event, &frame(id, payload), broadcast)? {
+        rpc.result = Some(Err(RpcError::SendFailed));
+    }
+    Ok(rpc)
+}
+
+/// Answers requests on `event` with `handler` until dropped.
+pub struct RpcServer {
+    _subscription: Subscription,
+}
+
+/// Answer every request on `event` with what `handler` returns, sent on
+/// [`response_event`] with `broadcast`.
+pub fn serve(
+    event: &str,
+    broadcast: BroadcastFlags,
+    mut handler: impl FnMut(&[u8]) -> Vec<u8> + 'static,
+) -> CommBusResult<RpcServer> {
+    let response = response_event(event);
+    // Validate once so replies can't fail on the name.
+    std::ffi::CString::new(response.as_str())?;
+    let subscription = Subscription::try_subscribe(event, move |bytes| {
+        let Some((id, request)) = unframe(bytes) else {
+            return;
+        };
+        let reply = handler(request);
+        let _ = bus_call(&response, &frame(id, &reply), broadcast);
+    })?;
+    Ok(RpcServer {
+        _subscription: subscription,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_round_trip() {
+        let id = call_id(0xdead_beef, 7);
+        let framed = frame(id, b"KJFK");
+        assert_eq!(framed.len(), ID_LEN + 4);
+        assert_eq!(unframe(&framed), Some((id, &b"KJFK"[..])));
+        assert_eq!(unframe(&framed[..ID_LEN - 1]), None);
+    }
+
+    #[test]
+    fn two_callers_get_their_own_answers() {
+        // Two modules, each on its first request, waiting on the same event.
+        let (a, b) = (call_id(1, 1), call_id(2, 1));
+        assert_ne!(a, b);
+        let module_a: Responses = Rc::default();
+        let module_b: Responses = Rc::default();
+        module_a.borrow_mut().insert(a, None);
+        module_b.borrow_mut().insert(b, None);
+
+        // The server's answers are broadcast, so both modules see both.
+        for reply in [frame(b, b"for b"), frame(a, b"for a")] {
+            deliver(&module_a, &reply);
+            deliver(&module_b, &reply);
+        }
+        assert_eq!(module_a.borrow()[&a].as_deref(), Some(&b"for a"[..]));
+        assert_eq!(module_b.borrow()[&b].as_deref(), Some(&b"for b"[..]));
+        assert_eq!(module_a.borrow().len(), 1);
+        assert_eq!(module_b.borrow().len(), 1);
+    }
+}
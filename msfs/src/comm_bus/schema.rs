@@ -0,0 +1,275 @@
+//! Schema negotiation between a WASM module and its JS counterpart.
+//!
+//! Each side announces the [`CommBusMessage::SCHEMA_HASH`] of every typed message it
+//! speaks on a shared handshake event. Comparing hashes before trusting a payload lets
+//! a module detect a mismatched panel/toolbar build and fall back instead of
+//! misparsing bytes.
+//!
+//! The announcement is plain text so JS can build it from the generated constants:
+//!
+//! ```text
+//! hello 5eed1234   <- or "reply"; a "hello" asks the peer to answer with its own list
+//! my/event 1a2b3c4d
+//! my/other 0badf00d
+//! ```
+//!
+//! The hex after the kind is the sender's [`sender_id`](super::sender_id), so a
+//! module ignores its own announcements when they come back to it and keeps
+//! each peer's list apart. JS peers may leave it out.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::schema::{SchemaHandshake, SchemaStatus};
+//! use msfs_derive::CommBusMessage;
+//!
+//! #[derive(CommBusMessage)]
+//! #[comm_bus(event = "INFINITY_FMS/state")]
+//! struct FmsState {
+//!     active_leg: u32,
+//! }
+//!
+//! let mut handshake = SchemaHandshake::new("INFINITY_FMS/schema")?;
+//! handshake.declare::<FmsState>();
+//! handshake.announce();
+//!
+//! // before publishing:
+//! if handshake.status::<FmsState>() == SchemaStatus::Mismatch {
+//!     // fall back to a reduced protocol, show a warning, ...
+//! }
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusMessage, CommBusResult, Subscription, sender_id, try_call};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+const HELLO: &str = "hello";
+const REPLY: &str = "reply";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaStatus {
+    /// The peer has not announced this message (yet).
+    Unknown,
+    Match,
+    Mismatch,
+}
+
+struct Shared {
+    event: String,
+    broadcast: BroadcastFlags,
+    local: Vec<(String, u32)>,
+    // By sender; 0 for peers that don't send one.
+    remote: HashMap<u32, HashMap<String, u32>>,
+}
+
+impl Shared {
+    fn encode(&self, kind: &str) -> Vec<u8> {
+        let mut out = format!("{kind} {:08x}", sender_id());
+        for (event, hash) in &self.local {
+            out.push_str(&format!("\n{event} {hash:08x}"));
+        }
+        out.into_bytes()
+    }
+
+    /// What [`call`] needs to send a `kind` announcement, so it can be sent
+    /// without holding a borrow.
+    fn message(&self, kind: &str) -> (String, Vec<u8>, BroadcastFlags) {
+        (self.event.clone(), self.encode(kind), self.broadcast)
+    }
+
+    /// Record an announcement unless it's from `own_id`, our own sender id.
+    /// Returns the reply to send if it was a hello.
+    fn receive(&mut self, bytes: &[u8], own_id: u32) -> Option<(String, Vec<u8>, BroadcastFlags)> {
+        let announcement = parse(bytes);
+        if announcement.sender == Some(own_id) {
+            return None;
+        }
+        self.remote
+            .insert(announcement.sender.unwrap_or(0), announcement.hashes);
+        (announcement.kind == HELLO).then(|| self.message(REPLY))
+    }
+
+    fn remote_hash(&self, event: &str) -> Option<u32> {
+        let local = self.local.iter().find(|(e, _)| e == event).map(|(_, h)| *h);
+        let hashes: Vec<u32> = self
+            .remote
+            .values()
+            .filter_map(|peer| peer.get(event).copied())
+            .collect();
+        hashes
+            .iter()
+            .find(|&&h| Some(h) != local)
+            .or(hashes.first())
+            .copied()
+    }
+}
+
+struct Announcement {
+    kind: String,
+    sender: Option<u32>,
+    hashes: HashMap<String, u32>,
+}
+
+fn parse(bytes: &[u8]) -> Announcement {
+    let text = String::from_utf8_lossy(bytes);
+    let mut lines = text.lines();
+    let mut first = lines.next().unwrap_or_default().split_whitespace();
+    let kind = first.next().unwrap_or_default().to_string();
+    let sender = first.next().and_then(|s| u32::from_str_radix(s, 16).ok());
+    let hashes = lines
+        .filter_map(|line| {
+            let (name, hash) = line.trim().rsplit_once(' ')?;
+            let hash = u32::from_str_radix(hash.trim_start_matches("0x"), 16).ok()?;
+            Some((name.trim().to_string(), hash))
+        })
+        .collect();
+    Announcement {
+        kind,
+        sender,
+        hashes,
+    }
+}
+
+pub struct SchemaHandshake {
+    shared: Rc<RefCell<Shared>>,
+    _sub: Subscription,
+}
+
+impl SchemaHandshake {
+    /// Listen for peer announcements on `event`. Announcements go to JS by default.
+    pub fn new(event: &str) -> CommBusResult<Self> {
+        let shared = Rc::new(RefCell::new(Shared {
+            event: event.to_string(),
+            broadcast: BroadcastFlags::JS,
+            local: Vec::new(),
+            remote: HashMap::new(),
+        }));
+
+        let inbox = Rc::clone(&shared);
+        let sub = Subscription::try_subscribe(event, move |bytes| {
+            let reply = inbox.borrow_mut().receive(bytes, sender_id());
+            // Answer a peer that (re)started after our own announcement went
+            // out. Not borrowed while sending: the call may come straight back.
+            if let Some((event, bytes, broadcast)) = reply {
+                let _ = try_call(&event, &bytes, broadcast);
+            }
+        })?;
+
+        Ok(Self { shared, _sub: sub })
+    }
+
+    /// Announce to other WASM modules instead of (or as well as) JS.
+    pub fn with_broadcast(self, broadcast: BroadcastFlags) -> Self {
+        self.shared.borrow_mut().broadcast = broadcast;
+        self
+    }
+
+    /// Add a message type to the announced list.
+    pub fn declare<M: CommBusMessage>(&mut self) {
+        let mut s = self.shared.borrow_mut();
+        if let Some(entry) = s.local.iter_mut().find(|(e, _)| e == M::EVENT) {
+            entry.1 = M::SCHEMA_HASH;
+        } else {
+            s.local.push((M::EVENT.to_string(), M::SCHEMA_HASH));
+        }
+    }
+
+    /// Send our schema list and ask the peer to answer with theirs.
+    pub fn announce(&self) -> bool {
+        let (event, bytes, broadcast) = self.shared.borrow().message(HELLO);
+        try_call(&event, &bytes, broadcast).unwrap_or(false)
+    }
+
+    /// `true` once any announcement from the peer has been received.
+    pub fn peer_seen(&self) -> bool {
+        !self.shared.borrow().remote.is_empty()
+    }
+
+    /// Hash a peer announced for `event`, if any. When peers disagree, one
+    /// that differs from ours wins, so [`status`](Self::status) can't hide a
+    /// mismatch.
+    pub fn remote_hash(&self, event: &str) -> Option<u32> {
+        self.shared.borrow().remote_hash(event)
+    }
+
+    pub fn status<M: CommBusMessage>(&self) -> SchemaStatus {
+        match self.remote_hash(M::EVENT) {
+            None => SchemaStatus::Unknown,
+            Some(h) if h == M::SCHEMA_HASH => SchemaStatus::Match,
+            Some(_) => SchemaStatus::Mismatch,
+        }
+    }
+
+    /// `true` when the peer announced the same layout for `M`. Use this as the gate
+    /// before decoding a payload; `Unknown` is treated as incompatible.
+    #[inline]
+    pub fn is_compatible<M: CommBusMessage>(&self) -> bool {
+        self.status::<M>() == SchemaStatus::Match
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWN: u32 = 0x5eed;
+
+    fn shared(local: &[(&str, u32)]) -> Shared {
+        Shared {
+            event: "test/schema".to_string(),
+            broadcast: BroadcastFlags::JS,
+            local: local.iter().map(|&(e, h)| (e.to_string(), h)).collect(),
+            remote: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn parses_announcements() {
+        let a = parse(b"hello 0000beef\nmy/event 1a2b3c4d\n  my/other 0x0badf00d \nbad line\n");
+        assert_eq!(a.kind, HELLO);
+        assert_eq!(a.sender, Some(0xbeef));
+        assert_eq!(a.hashes.len(), 2);
+        assert_eq!(a.hashes["my/event"], 0x1a2b_3c4d);
+        assert_eq!(a.hashes["my/other"], 0x0bad_f00d);
+
+        // JS peers may leave the sender out.
+        let a = parse(b"reply\nx 1");
+        assert_eq!((a.kind.as_str(), a.sender), (REPLY, None));
+        assert!(parse(b"").hashes.is_empty());
+    }
+
+    #[test]
+    fn encodes_sender_and_hashes() {
+        let s = shared(&[("a", 0xa), ("b", 0xb)]);
+        let text = String::from_utf8(s.encode(HELLO)).unwrap();
+        let a = parse(text.as_bytes());
+        assert_eq!(a.sender, Some(sender_id()));
+        assert_eq!(
+            a.hashes,
+            HashMap::from([("a".into(), 0xa), ("b".into(), 0xb)])
+        );
+    }
+
+    #[test]
+    fn ignores_own_announcements_and_answers_peers() {
+        let mut s = shared(&[("a", 0xa)]);
+        let own = format!("hello {OWN:08x}\na 0000000a");
+        assert!(s.receive(own.as_bytes(), OWN).is_none());
+        assert!(s.remote.is_empty());
+
+        // Two peers; the second disagrees about `a`. Only hellos are answered.
+        let reply = s.receive(b"hello 00000001\na 0000000a\nb 0000000b", OWN);
+        let (event, bytes, _) = reply.unwrap();
+        assert_eq!(event, "test/schema");
+        assert_eq!(parse(&bytes).kind, REPLY);
+        assert!(s.receive(b"reply 00000002\na 0000000c", OWN).is_none());
+        assert_eq!(s.remote_hash("b"), Some(0xb));
+        // The one that differs from ours wins.
+        assert_eq!(s.remote_hash("a"), Some(0xc));
+        assert_eq!(s.remote_hash("c"), None);
+
+        // A peer's new announcement replaces its old list.
+        s.receive(b"hello 00000002\nc 0000000d", OWN);
+        assert_eq!(s.remote_hash("c"), Some(0xd));
+        assert_eq!(s.remote_hash("a"), Some(0xa));
+    }
+}
@@ -0,0 +1,371 @@
+//! State owned by one module and mirrored by others.
+//!
+//! The owner keeps a [`SharedState`] and changes it with
+//! [`set`](SharedState::set) or [`update`](SharedState::update); each change
+//! goes out as a JSON merge patch of just the fields that changed. Display
+//! gauges keep a [`Replica`] that applies the patches and exposes the current
+//! value with [`get`](Replica::get). A replica that starts late or misses a
+//! patch asks the owner for a full snapshot on `<event>:sync`. Needs the
+//! `serde` feature.
+//!
+//! Messages are JSON, so JS can mirror the state as well:
+//!
+//! ```text
+//! {"seq":12,"full":false,"patch":{"altitude":12000}}
+//! ```
+//!
+//! As in any merge patch, a field serialized as `null` is removed on the
+//! replica, which decodes as `None` for `Option` fields.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, shared::{Replica, SharedState}};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Autopilot {
+//!     engaged: bool,
+//!     altitude: u32,
+//!     heading: u32,
+//! }
+//!
+//! // master system:
+//! let initial = Autopilot { engaged: false, altitude: 10000, heading: 90 };
+//! let mut ap = SharedState::new("INFINITY_AP", initial, BroadcastFlags::ALL_WASM)?;
+//! ap.update(|ap| ap.altitude = 12000)?;   // sends {"altitude":12000}
+//!
+//! // display gauge:
+//! let ap_view = Replica::<Autopilot>::new("INFINITY_AP", BroadcastFlags::ALL_WASM)?;
+//! if let Some(ap) = ap_view.get() {
+//!     println!("ALT {}", ap.altitude);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::{
+    BroadcastFlags, CommBusResult, Subscription, try_call,
+    typed::{self, TypedError},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value, json};
+use std::{cell::RefCell, rc::Rc};
+
+/// Event replicas ask the owner of `event` for a snapshot on.
+pub fn sync_event(event: &str) -> String {
+    format!("{event}:sync")
+}
+
+/// The merge patch turning `old` into `new`, `None` if they're equal.
+pub fn diff(old: &Value, new: &Value) -> Option<Value> {
+    if old == new {
+        return None;
+    }
+    let (Value::Object(old), Value::Object(new)) = (old, new) else {
+        return Some(new.clone());
+    };
+    let mut patch = Map::new();
+    for (key, value) in new {
+        match old.get(key) {
+            Some(previous) => {
+                if let Some(change) = diff(previous, value) {
+                    patch.insert(key.clone(), change);
+                }
+            }
+            None => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    for key in old.keys().filter(|k| !new.contains_key(*k)) {
+        patch.insert(key.clone(), Value::Null);
+    }
+    Some(Value::Object(patch))
+}
+
+/// Apply a merge patch (RFC 7386) to `target`.
+pub fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+fn message(seq: u64, full: bool, patch: &Value) -> Vec<u8> {
+    json!({ "seq": seq, "full": full, "patch": patch })
+        .to_string()
+        .into_bytes()
+}
+
+struct Published {
+    event: String,
+    broadcast: BroadcastFlags,
+    seq: u64,
+    json: Value,
+}
+
+impl Published {
+    fn snapshot(&self) -> Vec<u8> {
+        message(self.seq, true, &self.json)
+    }
+}
+
+/// The owner's copy of a shared state.
+pub struct SharedState<T> {
+    value: T,
+    published: Rc<RefCell<Published>>,
+    _sync: Subscription,
+}
+
+impl<T: Serialize + DeserializeOwned> SharedState<T> {
+    /// Own the state on `event`, answer snapshot requests, and send the first
+    /// snapshot.
+    pub fn new(event: &str, initial: T, broadcast: BroadcastFlags) -> Result<Self, TypedError> {
+        let json = serde_json::to_value(&initial).map_err(TypedError::Encode)?;
+        let published = Rc::new(RefCell::new(Published {
+            event: event.to_string(),
+            broadcast,
+            seq: 0,
+            json,
+        }));
+
+        let inbox = Rc::clone(&published);
+        let sync = Subscription::try_subscribe(&sync_event(event), move |_| {
+            let (event, snapshot, broadcast) = {
+                let p = inbox.borrow();
+                (p.event.clone(), p.snapshot(), p.broadcast)
+            };
+            let _ = try_call(&event, &snapshot, broadcast);
+        })?;
+
+        let state = Self {
+            value: initial,
+            published,
+            _sync: sync,
+        };
+        state.send_snapshot()?;
+        Ok(state)
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Replace the state and send what changed. `Ok(false)` if nothing did.
+    pub fn set(&mut self, value: T) -> Result<bool, TypedError> {
+        self.value = value;
+        self.publish()
+    }
+
+    /// Change the state in place and send what changed. `Ok(false)` if
+    /// nothing did.
+    pub fn update(&mut self, f: impl FnOnce(&mut T)) -> Result<bool, TypedError> {
+        f(&mut self.value);
+        self.publish()
+    }
+
+    /// Send the whole state again, e.g. after a replica reloaded.
+    pub fn send_snapshot(&self) -> Result<bool, TypedError> {
+        let (event, snapshot, broadcast) = {
+            let p = self.published.borrow();
+            (p.event.clone(), p.snapshot(), p.broadcast)
+        };
+        Ok(try_call(&event, &snapshot, broadcast)?)
+    }
+
+    /// Number of changes sent so far.
+    pub fn seq(&self) -> u64 {
+        self.published.borrow().seq
+    }
+
+    fn publish(&mut self) -> Result<bool, TypedError> {
+        let json = serde_json::to_value(&self.value).map_err(TypedError::Encode)?;
+        let (event, bytes, broadcast) = {
+            let mut p = self.published.borrow_mut();
+            let Some(patch) = diff(&p.json, &json) else {
+                return Ok(false);
+            };
+            p.json = json;
+            p.seq += 1;
+            (p.event.clone(), message(p.seq, false, &patch), p.broadcast)
+        };
+        try_call(&event, &bytes, broadcast)?;
+        Ok(true)
+    }
+}
+
+struct Mirror<T> {
+    sync_event: String,
+    broadcast: BroadcastFlags,
+    seq: Option<u64>,
+    json: Value,
+    value: Option<T>,
+}
+
+impl<T: DeserializeOwned> Mirror<T> {
+    /// Apply one message; `false` if it can't be applied and a snapshot is
+    /// needed.
+    fn receive(&mut self, bytes: &[u8]) -> bool {
+        let Ok(message) = typed::decode::<Value>(bytes) else {
+            return true;
+        };
+        let (Some(seq), Some(full), Some(patch)) = (
+            message["seq"].as_u64(),
+            message["full"].as_bool(),
+            message.get("patch"),
+        ) else {
+            return true;
+        };
+        if full {
+            // An older snapshot than what we have is a reply to another replica.
+            if self.seq.is_some_and(|current| current > seq) {
+                return true;
+            }
+            self.json = patch.clone();
+        } else if self.seq == Some(seq.wrapping_sub(1)) {
+            merge(&mut self.json, patch);
+        } else {
+            return self.seq.is_some_and(|current| current >= seq);
+        }
+        self.seq = Some(seq);
+        self.value = serde_json::from_value(self.json.clone()).ok();
+        true
+    }
+}
+
+/// A mirror of the [`SharedState`] on one event.
+pub struct Replica<T> {
+    mirror: Rc<RefCell<Mirror<T>>>,
+    _sub: Subscription,
+}
+
+impl<T: DeserializeOwned + Clone + 'static> Replica<T> {
+    /// Mirror the state on `event`, asking the owner for a snapshot with
+    /// `broadcast`.
+    pub fn new(event: &str, broadcast: BroadcastFlags) -> CommBusResult<Self> {
+        let mirror = Rc::new(RefCell::new(Mirror {
+            sync_event: sync_event(event),
+            broadcast,
+            seq: None,
+            json: Value::Null,
+            value: None,
+        }));
+
+        let inbox = Rc::clone(&mirror);
+        let sub = Subscription::try_subscribe(event, move |bytes| {
+            let applied = inbox.borrow_mut().receive(bytes);
+            if !applied {
+                let (sync_event, broadcast) = {
+                    let m = inbox.borrow();
+                    (m.sync_event.clone(), m.broadcast)
+                };
+                let _ = try_call(&sync_event, &[], broadcast);
+            }
+        })?;
+
+        let replica = Self { mirror, _sub: sub };
+        replica.request_sync()?;
+        Ok(replica)
+    }
+
+    /// The current state, once a snapshot has arrived and decoded as `T`.
+    pub fn get(&self) -> Option<T> {
+        self.mirror.borrow().value.clone()
+    }
+
+    /// Borrow the current state without cloning it.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        self.mirror.borrow().value.as_ref().map(f)
+    }
+
+    /// The owner's change counter as of the last applied message; compare it
+    /// between updates to see whether anything changed.
+    pub fn seq(&self) -> Option<u64> {
+        self.mirror.borrow().seq
+    }
+
+    /// Ask the owner for a full snapshot.
+    pub fn request_sync(&self) -> CommBusResult<bool> {
+        let (sync_event, broadcast) = {
+            let m = self.mirror.borrow();
+            (m.sync_event.clone(), m.broadcast)
+        };
+        try_call(&sync_event, &[], broadcast)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applies(old: Value, new: Value) {
+        let mut replica = old.clone();
+        if let Some(patch) = diff(&old, &new) {
+            merge(&mut replica, &patch);
+        }
+        assert_eq!(replica, new, "from {old}");
+    }
+
+    #[test]
+    fn diff_only_changed_fields() {
+        let old =
+            json!({ "engaged": false, "altitude": 10000, "mode": { "lat": "HDG", "vert": "ALT" } });
+        let new =
+            json!({ "engaged": false, "altitude": 12000, "mode": { "lat": "HDG", "vert": "VS" } });
+        assert_eq!(
+            diff(&old, &new),
+            Some(json!({ "altitude": 12000, "mode": { "vert": "VS" } }))
+        );
+        assert_eq!(diff(&old, &old), None);
+    }
+
+    #[test]
+    fn diff_removals_and_replacements() {
+        let old = json!({ "a": 1, "b": [1, 2], "c": { "x": 1 } });
+        let new = json!({ "b": [1, 3], "c": 5, "d": { "y": 2 } });
+        assert_eq!(
+            diff(&old, &new),
+            Some(json!({ "a": null, "b": [1, 3], "c": 5, "d": { "y": 2 } }))
+        );
+        assert_eq!(diff(&json!(1), &json!({ "a": 1 })), Some(json!({ "a": 1 })));
+    }
+
+    #[test]
+    fn merge_rfc7386_examples() {
+        let mut target = json!({ "a": "b", "c": { "d": "e", "f": "g" } });
+        merge(&mut target, &json!({ "a": "z", "c": { "f": null } }));
+        assert_eq!(target, json!({ "a": "z", "c": { "d": "e" } }));
+
+        let mut target = json!(["a"]);
+        merge(&mut target, &json!({ "a": { "bb": { "ccc": null } } }));
+        assert_eq!(target, json!({ "a": { "bb": {} } }));
+
+        let mut target = json!({ "a": "foo" });
+        merge(&mut target, &json!("bar"));
+        assert_eq!(target, json!("bar"));
+    }
+
+    #[test]
+    fn merge_undoes_diff() {
+        applies(json!({ "a": 1 }), json!({ "a": 2, "b": { "c": [true] } }));
+        applies(
+            json!({ "a": { "b": 1, "c": 2 } }),
+            json!({ "a": { "c": 3 } }),
+        );
+        applies(json!({ "a": 1 }), json!({}));
+        applies(json!([1, 2]), json!({ "a": 1 }));
+        applies(json!({ "a": 1 }), json!("text"));
+    }
+}
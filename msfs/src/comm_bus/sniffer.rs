@@ -0,0 +1,228 @@
+//! Debug tap for comm bus traffic.
+//!
+//! A [`Sniffer`] listens to a list of events and logs every message: time,
+//! event name, size and a hex/ASCII preview of the payload, to stdout or a
+//! file under `\work`. Tie it to an L-var to switch it on from the sim (e.g.
+//! the behaviors debugger) while chasing a cross-module problem; it only
+//! registers on the events while enabled.
+//!
+//! ```text
+//! [  12.345] INFINITY_FMS/route (12 bytes) 7b 22 6c 65 67 73 22 3a 5b 5d 7d 00  |{"legs":[]}.|
+//! ```
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::sniffer::Sniffer;
+//!
+//! let mut sniffer = Sniffer::new(["INFINITY_FMS/route", "INFINITY_FMS/perf"])
+//!     .to_file("\\work/commbus.log")
+//!     .with_toggle("L:INFINITY_DEBUG_COMMBUS")?;
+//!
+//! // in update():
+//! sniffer.update();
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{CommBusResult, SubscriptionSet};
+use crate::io::fs::{self, WriteRequest};
+use crate::vars::{LVar, VarResult};
+use std::{cell::RefCell, fmt::Write as _, rc::Rc, time::Instant};
+
+/// Payload bytes shown per message unless changed with
+/// [`Sniffer::with_preview`].
+pub const DEFAULT_PREVIEW: usize = 32;
+
+/// Where a [`Sniffer`] writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnifferOutput {
+    Stdout,
+    /// Appended to this file, e.g. `\work/commbus.log`, from
+    /// [`Sniffer::update`].
+    File(String),
+}
+
+/// One log line for `bytes` received on `event`, with up to `preview` bytes
+/// of the payload in hex and ASCII.
+pub fn format_entry(time: f64, event: &str, bytes: &[u8], preview: usize) -> String {
+    let shown = &bytes[..bytes.len().min(preview)];
+    let mut line = format!("[{time:8.3}] {event} ({} bytes)", bytes.len());
+    if !shown.is_empty() {
+        line.push(' ');
+        for b in shown {
+            let _ = write!(line, "{b:02x} ");
+        }
+        if shown.len() < bytes.len() {
+            line.push_str("... ");
+        }
+        line.push_str(" |");
+        line.extend(shown.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        }));
+        line.push('|');
+    }
+    line
+}
+
+struct Log {
+    start: Instant,
+    preview: usize,
+    stdout: bool,
+    lines: Vec<String>,
+}
+
+impl Log {
+    fn record(&mut self, event: &str, bytes: &[u8]) {
+        let line = format_entry(
+            self.start.elapsed().as_secs_f64(),
+            event,
+            bytes,
+            self.preview,
+        );
+        if self.stdout {
+            println!("{line}");
+        } else {
+            self.lines.push(line);
+        }
+    }
+}
+
+/// Logs the messages on a set of events while enabled.
+pub struct Sniffer {
+    events: Vec<String>,
+    output: SnifferOutput,
+    toggle: Option<LVar>,
+    log: Rc<RefCell<Log>>,
+    subs: SubscriptionSet,
+    enabled: bool,
+    pending: Option<WriteRequest>,
+}
+
+impl Sniffer {
+    /// A disabled sniffer for `events`, logging to stdout.
+    pub fn new<S: Into<String>>(events: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            events: events.into_iter().map(Into::into).collect(),
+            output: SnifferOutput::Stdout,
+            toggle: None,
+            log: Rc::new(RefCell::new(Log {
+                start: Instant::now(),
+                preview: DEFAULT_PREVIEW,
+                stdout: true,
+                lines: Vec::new(),
+            })),
+            subs: SubscriptionSet::new(),
+            enabled: false,
+            pending: None,
+        }
+    }
+
+    /// Append to `path` instead of printing.
+    pub fn to_file(mut self, path: &str) -> Self {
+        self.output = SnifferOutput::File(path.to_string());
+        self.log.borrow_mut().stdout = false;
+        self
+    }
+
+    /// Show up to `bytes` bytes of each payload.
+    pub fn with_preview(self, bytes: usize) -> Self {
+        self.log.borrow_mut().preview = bytes;
+        self
+    }
+
+    /// Enable the sniffer while the L-var `name` is non-zero, checked in
+    /// [`update`](Self::update).
+    pub fn with_toggle(mut self, name: &str) -> VarResult<Self> {
+        self.toggle = Some(LVar::new(name, "Bool")?);
+        Ok(self)
+    }
+
+    pub fn output(&self) -> &SnifferOutput {
+        &self.output
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start or stop listening.
+    pub fn set_enabled(&mut self, enabled: bool) -> CommBusResult<()> {
+        if enabled == self.enabled {
+            return Ok(());
+        }
+        self.enabled = enabled;
+        if !enabled {
+            self.subs.clear();
+            return Ok(());
+        }
+        for event in self.events.clone() {
+            self.listen(&event)?;
+        }
+        Ok(())
+    }
+
+    /// Add `event` to the list.
+    pub fn watch(&mut self, event: &str) -> CommBusResult<()> {
+        if self.events.iter().any(|e| e == event) {
+            return Ok(());
+        }
+        self.events.push(event.to_string());
+        if self.enabled {
+            self.listen(event)?;
+        }
+        Ok(())
+    }
+
+    /// Remove `event` from the list.
+    pub fn unwatch(&mut self, event: &str) {
+        self.events.retain(|e| e != event);
+        self.subs.remove(event);
+    }
+
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    /// Follow the toggle L-var and write buffered lines to the log file. Call
+    /// every update.
+    pub fn update(&mut self) {
+        if let Some(toggle) = &self.toggle
+            && let Ok(value) = toggle.get()
+        {
+            let _ = self.set_enabled(value != 0.0);
+        }
+        self.flush();
+    }
+
+    fn listen(&mut self, event: &str) -> CommBusResult<()> {
+        let log = Rc::clone(&self.log);
+        let name = event.to_string();
+        self.subs
+            .add(event, move |bytes| log.borrow_mut().record(&name, bytes))
+    }
+
+    fn flush(&mut self) {
+        let SnifferOutput::File(path) = &self.output else {
+            return;
+        };
+        // One append at a time, so lines land in order.
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|w| !w.is_done() && !w.has_error())
+        {
+            return;
+        }
+        let lines = std::mem::take(&mut self.log.borrow_mut().lines);
+        if lines.is_empty() {
+            self.pending = None;
+            return;
+        }
+        let mut text = lines.join("\n");
+        text.push('\n');
+        self.pending = fs::append(path, text.as_bytes()).ok();
+    }
+}
@@ -0,0 +1,107 @@
+//! Comm bus messages as any serde type, encoded as JSON.
+//!
+//! Unlike `#[derive(CommBusMessage)]`, which pins a message type to one event
+//! and generates its TypeScript, these work with any `Serialize` /
+//! `Deserialize` type on any event, so JS can `JSON.parse` what WASM publishes
+//! and send back `JSON.stringify` output without a hand-written codec on
+//! either side. Needs the `serde` feature.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::comm_bus::{BroadcastFlags, typed};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct FlightPlan {
+//!     origin: String,
+//!     destination: String,
+//!     cruise_fl: u32,
+//! }
+//!
+//! let plan = FlightPlan { origin: "EGLL".into(), destination: "KJFK".into(), cruise_fl: 370 };
+//! typed::publish("FMS_PLAN", &plan, BroadcastFlags::JS)?;
+//!
+//! let _sub = typed::subscribe("FMS_PLAN_EDIT", |plan: FlightPlan| {
+//!     println!("new destination {}", plan.destination);
+//! })?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::{BroadcastFlags, CommBusError, CommBusResult, Subscription, try_call};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+
+/// Why a typed message couldn't be sent.
+#[derive(Debug)]
+pub enum TypedError {
+    /// The comm bus rejected the call, e.g. a NUL byte in the event name.
+    Bus(CommBusError),
+    /// The value couldn't be encoded, e.g. a map with non-string keys.
+    Encode(serde_json::Error),
+}
+
+impl fmt::Display for TypedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypedError::Bus(e) => e.fmt(f),
+            TypedError::Encode(e) => write!(f, "could not encode message: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TypedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TypedError::Bus(e) => Some(e),
+            TypedError::Encode(e) => Some(e),
+        }
+    }
+}
+
+impl From<CommBusError> for TypedError {
+    fn from(e: CommBusError) -> Self {
+        TypedError::Bus(e)
+    }
+}
+
+/// Encode `value` as a JSON payload.
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(value)
+}
+
+/// Decode a JSON payload. Trailing NULs (C strings sent from JS) are ignored.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, serde_json::Error> {
+    let end = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    serde_json::from_slice(&bytes[..end])
+}
+
+/// Send `value` as JSON on `event`.
+pub fn publish<T: Serialize + ?Sized>(
+    event: &str,
+    value: &T,
+    broadcast: BroadcastFlags,
+) -> Result<bool, TypedError> {
+    let payload = encode(value).map_err(TypedError::Encode)?;
+    Ok(try_call(event, &payload, broadcast)?)
+}
+
+/// Call `cb` with every message on `event` that decodes as `T`; others are
+/// dropped. Use [`subscribe_with_errors`] to see them.
+pub fn subscribe<T: DeserializeOwned + 'static>(
+    event: &str,
+    mut cb: impl FnMut(T) + 'static,
+) -> CommBusResult<Subscription> {
+    Subscription::try_subscribe(event, move |bytes| {
+        if let Ok(value) = decode(bytes) {
+            cb(value);
+        }
+    })
+}
+
+/// Like [`subscribe`], but also passes on payloads that don't decode.
+pub fn subscribe_with_errors<T: DeserializeOwned + 'static>(
+    event: &str,
+    mut cb: impl FnMut(Result<T, serde_json::Error>) + 'static,
+) -> CommBusResult<Subscription> {
+    Subscription::try_subscribe(event, move |bytes| cb(decode(bytes)))
+}
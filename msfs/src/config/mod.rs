@@ -0,0 +1,275 @@
+//! Typed gauge configuration from install parameters.
+//!
+//! The parameter string a gauge receives from `panel.cfg` is parsed as
+//! `key=value` pairs separated by `&`, `,` or `;` (so both query-string and
+//! list styles work). Implement [`GaugeConfig`] with `#[derive(GaugeConfig)]`:
+//!
+//! ```ignore
+//! use msfs_derive::GaugeConfig;
+//!
+//! #[derive(GaugeConfig)]
+//! struct PfdConfig {
+//!     index: u8,                       // required
+//!     #[config(default = 60.0)]
+//!     refresh_hz: f32,
+//!     #[config(key = "side", default)]
+//!     side_name: String,               // String::default() when absent
+//!     brightness_lvar: Option<String>, // None when absent
+//! }
+//!
+//! // in Gauge::init:
+//! let cfg = match PfdConfig::from_install(install) {
+//!     Ok(cfg) => cfg,
+//!     Err(e) => {
+//!         println!("bad panel.cfg parameters: {e}");
+//!         return false;
+//!     }
+//! };
+//! ```
+//!
+//! Keys match field names (or `key = "..."`) case-insensitively. Unknown keys are
+//! an error unless the struct has `#[config(allow_unknown)]`.
+
+use crate::types::{GaugeInstall, SystemInstall};
+use std::{ffi::CStr, fmt, os::raw::c_char};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// A pair without `=`.
+    Malformed(String),
+    MissingKey(&'static str),
+    UnknownKey(String),
+    DuplicateKey(String),
+    Invalid {
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Malformed(pair) => write!(f, "expected key=value, got {pair:?}"),
+            ConfigError::MissingKey(key) => write!(f, "missing required key {key:?}"),
+            ConfigError::UnknownKey(key) => write!(f, "unknown key {key:?}"),
+            ConfigError::DuplicateKey(key) => write!(f, "key {key:?} given more than once"),
+            ConfigError::Invalid {
+                key,
+                value,
+                expected,
+            } => write!(f, "{key}={value:?}: expected {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+/// A config struct parsed from gauge install parameters.
+pub trait GaugeConfig: Sized {
+    fn from_params(params: &str) -> ConfigResult<Self>;
+
+    fn from_install(install: &GaugeInstall) -> ConfigResult<Self> {
+        Self::from_params(&unsafe { params_str(install.strParameters) })
+    }
+
+    fn from_system_install(install: &SystemInstall) -> ConfigResult<Self> {
+        Self::from_params(&unsafe { params_str(install.strParameters) })
+    }
+}
+
+unsafe fn params_str(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Split a parameter string into trimmed `(key, value)` pairs. Empty segments are skipped.
+pub fn parse_pairs(params: &str) -> ConfigResult<Vec<(&str, &str)>> {
+    params
+        .split(['&', ',', ';'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|pair| {
+            pair.split_once('=')
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .ok_or_else(|| ConfigError::Malformed(pair.to_string()))
+        })
+        .collect()
+}
+
+/// A field type that can be parsed from a single parameter value.
+pub trait ConfigValue: Sized {
+    /// Shown in [`ConfigError::Invalid`].
+    const EXPECTED: &'static str;
+
+    fn parse_value(value: &str) -> Option<Self>;
+}
+
+macro_rules! config_value_fromstr {
+    ($($ty:ty => $expected:literal),* $(,)?) => {
+        $(impl ConfigValue for $ty {
+            const EXPECTED: &'static str = $expected;
+
+            #[inline]
+            fn parse_value(value: &str) -> Option<Self> {
+                value.parse().ok()
+            }
+        })*
+    };
+}
+
+config_value_fromstr! {
+    f32 => "a number",
+    f64 => "a number",
+    i8 => "an integer",
+    i16 => "an integer",
+    i32 => "an integer",
+    i64 => "an integer",
+    u8 => "a non-negative integer",
+    u16 => "a non-negative integer",
+    u32 => "a non-negative integer",
+    u64 => "a non-negative integer",
+    usize => "a non-negative integer",
+    char => "a single character",
+}
+
+impl ConfigValue for bool {
+    const EXPECTED: &'static str = "true/false, yes/no, on/off or 1/0";
+
+    fn parse_value(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl ConfigValue for String {
+    const EXPECTED: &'static str = "a string";
+
+    #[inline]
+    fn parse_value(value: &str) -> Option<Self> {
+        Some(value.to_string())
+    }
+}
+
+/// Used by the derive; not part of the public API.
+#[doc(hidden)]
+pub fn __parse_field<T: ConfigValue>(key: &str, value: &str) -> ConfigResult<T> {
+    T::parse_value(value).ok_or_else(|| ConfigError::Invalid {
+        key: key.to_string(),
+        value: value.to_string(),
+        expected: T::EXPECTED,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use msfs_derive::GaugeConfig;
+
+    #[derive(Debug, PartialEq, GaugeConfig)]
+    struct PfdConfig {
+        index: u8,
+        #[config(default = 60.0)]
+        refresh_hz: f32,
+        #[config(key = "side", default)]
+        side_name: String,
+        brightness_lvar: Option<String>,
+        #[config(default)]
+        dimmed: bool,
+    }
+
+    #[derive(Debug, PartialEq, GaugeConfig)]
+    #[config(allow_unknown)]
+    struct Lenient {
+        index: u8,
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(
+            parse_pairs(" a = 1 &b=2,, c=x=y ;").unwrap(),
+            [("a", "1"), ("b", "2"), ("c", "x=y")]
+        );
+        assert_eq!(parse_pairs("").unwrap(), []);
+        assert_eq!(
+            parse_pairs("a=1&oops"),
+            Err(ConfigError::Malformed("oops".to_string()))
+        );
+    }
+
+    #[test]
+    fn values() {
+        assert_eq!(bool::parse_value("Yes"), Some(true));
+        assert_eq!(bool::parse_value("OFF"), Some(false));
+        assert_eq!(bool::parse_value("2"), None);
+        assert_eq!(u8::parse_value("-1"), None);
+        assert_eq!(char::parse_value("ab"), None);
+        assert_eq!(
+            __parse_field::<u16>("index", "x"),
+            Err(ConfigError::Invalid {
+                key: "index".to_string(),
+                value: "x".to_string(),
+                expected: "a non-negative integer",
+            })
+        );
+    }
+
+    #[test]
+    fn derived_defaults_and_keys() {
+        assert_eq!(
+            PfdConfig::from_params("index=1").unwrap(),
+            PfdConfig {
+                index: 1,
+                refresh_hz: 60.0,
+                side_name: String::new(),
+                brightness_lvar: None,
+                dimmed: false,
+            }
+        );
+        let cfg = PfdConfig::from_params(
+            "INDEX=2;Side=left;refresh_hz=30;brightness_lvar=L:PFD;dimmed=on",
+        )
+        .unwrap();
+        assert_eq!(cfg.index, 2);
+        assert_eq!(cfg.refresh_hz, 30.0);
+        assert_eq!(cfg.side_name, "left");
+        assert_eq!(cfg.brightness_lvar.as_deref(), Some("L:PFD"));
+        assert!(cfg.dimmed);
+    }
+
+    #[test]
+    fn derived_errors() {
+        assert_eq!(
+            PfdConfig::from_params("side=left"),
+            Err(ConfigError::MissingKey("index"))
+        );
+        assert_eq!(
+            PfdConfig::from_params("index=1,side_name=left"),
+            Err(ConfigError::UnknownKey("side_name".to_string()))
+        );
+        assert_eq!(
+            PfdConfig::from_params("index=1,Index=2"),
+            Err(ConfigError::DuplicateKey("Index".to_string()))
+        );
+        assert!(matches!(
+            PfdConfig::from_params("index=300"),
+            Err(ConfigError::Invalid {
+                expected: "a non-negative integer",
+                ..
+            })
+        ));
+        assert_eq!(
+            Lenient::from_params("index=3&extra=1").unwrap(),
+            Lenient { index: 3 }
+        );
+    }
+}
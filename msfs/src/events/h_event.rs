@@ -0,0 +1,53 @@
+//! H events (`H:`), the cockpit interaction events model behaviors listen to.
+
+use crate::events::recorder::{self, Direction, EventKind};
+use crate::vars::calc;
+use std::ffi::{CString, NulError};
+
+/// A named H event, e.g. `AS1000_PFD_SOFTKEYS_1`.
+///
+/// # Examples
+/// ```no_run
+/// use msfs::events::HEvent;
+///
+/// let softkey = HEvent::new("AS1000_PFD_SOFTKEYS_1")?;
+/// softkey.fire();
+/// # Ok::<(), std::ffi::NulError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HEvent {
+    name: String,
+    code: CString,
+}
+
+impl HEvent {
+    /// A leading `H:` on `name` is optional.
+    pub fn new(name: &str) -> Result<Self, NulError> {
+        let name = name
+            .get(..2)
+            .filter(|p| p.eq_ignore_ascii_case("H:"))
+            .map_or(name, |_| &name[2..])
+            .trim();
+        let code = CString::new(format!("(>H:{name})"))?;
+        Ok(Self {
+            name: name.to_string(),
+            code,
+        })
+    }
+
+    /// Event name without the `H:` prefix.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `false` if the event could not be sent.
+    pub fn fire(&self) -> bool {
+        recorder::record(Direction::Fired, EventKind::H, &self.name, &[]);
+        calc::run_cstr(&self.code)
+    }
+}
+
+/// Fire the H event `name` without keeping an [`HEvent`] around.
+pub fn fire_h_event(name: &str) -> bool {
+    HEvent::new(name).is_ok_and(|event| event.fire())
+}
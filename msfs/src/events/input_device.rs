@@ -0,0 +1,301 @@
+//! Joystick and controller state.
+//!
+//! WASM modules can't open input devices, but SimConnect reports what the sim
+//! sees: the list of connected controllers, and axis and button changes for
+//! inputs mapped with a SimConnect input group. Watching an input doesn't
+//! take it away from the sim's own bindings.
+//!
+//! ```no_run
+//! use msfs::events::input_device::{Axis, InputDevices};
+//!
+//! let mut devices = InputDevices::new().expect("SimConnect unavailable");
+//! let pitch = devices.watch_axis(0, Axis::Y).unwrap();
+//! let trigger = devices.watch_button(0, 0).unwrap();
+//!
+//! // in update():
+//! devices.poll();
+//! let stick = devices.axis(pitch).unwrap_or(0.0);
+//! if devices.button(trigger) {
+//!     println!("trigger held, stick at {stick}");
+//! }
+//! ```
+//!
+//! Axis values only arrive when the axis moves, so [`InputDevices::axis`] is
+//! `None` until the first movement after watching it.
+
+use crate::simconnect::{self, Inbox, read_u16, read_u32, with_client};
+use crate::sys::{
+    DWORD, SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CONTROLLERS_LIST,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT, SIMCONNECT_STATE_SIMCONNECT_STATE_ON,
+    SimConnect_ClearInputGroup, SimConnect_EnumerateControllers,
+    SimConnect_MapClientEventToSimEvent, SimConnect_MapInputEventToClientEvent_EX1,
+    SimConnect_SetInputGroupPriority, SimConnect_SetInputGroupState,
+};
+use std::ffi::CString;
+
+// `static const`s in SimConnect.h, which bindgen doesn't emit.
+const GROUP_PRIORITY_HIGHEST: DWORD = 1;
+const UNUSED: DWORD = DWORD::MAX;
+
+// Axis events carry -16384..=16384.
+const AXIS_RANGE: f64 = 16384.0;
+
+// `SIMCONNECT_RECV_EVENT` is packed: dwSize dwVersion dwID uGroupID uEventID dwData.
+const GROUP: usize = 12;
+const EVENT: usize = 16;
+const DATA: usize = 20;
+
+// `SIMCONNECT_RECV_CONTROLLERS_LIST`: the list header, then per controller
+// char DeviceName[256]; uint DeviceId, ProductId, CompositeID; then four
+// u16s of hardware version.
+const LIST_HEADER: usize = 28;
+const CONTROLLER: usize = 276;
+
+/// A joystick axis, as SimConnect names it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+    Rx,
+    Ry,
+    Rz,
+    Slider1,
+    Slider2,
+}
+
+impl Axis {
+    fn name(self) -> &'static str {
+        match self {
+            Axis::X => "XAxis",
+            Axis::Y => "YAxis",
+            Axis::Z => "ZAxis",
+            Axis::Rx => "RxAxis",
+            Axis::Ry => "RyAxis",
+            Axis::Rz => "RzAxis",
+            Axis::Slider1 => "Slider1",
+            Axis::Slider2 => "Slider2",
+        }
+    }
+}
+
+/// One entry of [`InputDevices::controllers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControllerInfo {
+    pub name: String,
+    /// The index used by [`InputDevices::watch_axis`] and
+    /// [`InputDevices::watch_button`].
+    pub device_id: u32,
+    pub product_id: u32,
+    pub composite_id: u32,
+    /// Major, minor, revision, build.
+    pub hardware_version: [u16; 4],
+}
+
+/// An axis watched with [`InputDevices::watch_axis`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AxisId(usize);
+
+/// A button watched with [`InputDevices::watch_button`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ButtonId(usize);
+
+enum Watched {
+    Axis(Option<f64>),
+    Button(bool),
+}
+
+/// Watched device inputs and the controller list, in an input group on the
+/// module's shared SimConnect client.
+pub struct InputDevices {
+    inbox: Inbox,
+    group: DWORD,
+    // By client event id.
+    watched: Vec<(DWORD, Watched)>,
+    controllers: Vec<ControllerInfo>,
+    listed: bool,
+}
+
+impl InputDevices {
+    /// Set up an input group and ask for the controller list. `None` if
+    /// SimConnect is unavailable.
+    pub fn new() -> Option<Self> {
+        let inbox = Inbox::new()?;
+        let group = with_client(|client| {
+            let group = client.next_id();
+            unsafe {
+                SimConnect_SetInputGroupPriority(client.handle(), group, GROUP_PRIORITY_HIGHEST);
+                SimConnect_SetInputGroupState(
+                    client.handle(),
+                    group,
+                    SIMCONNECT_STATE_SIMCONNECT_STATE_ON as DWORD,
+                );
+                SimConnect_EnumerateControllers(client.handle());
+            }
+            group
+        })?;
+        inbox.claim(group);
+        inbox.claim_kind(SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CONTROLLERS_LIST as u32);
+        Some(Self {
+            inbox,
+            group,
+            watched: Vec::new(),
+            controllers: Vec::new(),
+            listed: false,
+        })
+    }
+
+    /// The connected controllers, once the sim has sent the whole list.
+    pub fn controllers(&self) -> Option<&[ControllerInfo]> {
+        self.listed.then_some(self.controllers.as_slice())
+    }
+
+    /// Ask for the controller list again, e.g. after a device was plugged in.
+    pub fn refresh_controllers(&mut self) -> bool {
+        self.controllers.clear();
+        self.listed = false;
+        with_client(|client| unsafe { SimConnect_EnumerateControllers(client.handle()) >= 0 })
+            .unwrap_or(false)
+    }
+
+    /// Start tracking `axis` of joystick `device`.
+    pub fn watch_axis(&mut self, device: u32, axis: Axis) -> Option<AxisId> {
+        let id = self.watch(
+            &format!("joystick:{device}:{}", axis.name()),
+            Watched::Axis(None),
+        )?;
+        Some(AxisId(id))
+    }
+
+    /// Start tracking button `button` (from 0) of joystick `device`.
+    pub fn watch_button(&mut self, device: u32, button: u32) -> Option<ButtonId> {
+        let id = self.watch(
+            &format!("joystick:{device}:button:{button}"),
+            Watched::Button(false),
+        )?;
+        Some(ButtonId(id))
+    }
+
+    fn watch(&mut self, definition: &str, initial: Watched) -> Option<usize> {
+        let definition = CString::new(definition).ok()?;
+        let group = self.group;
+        let is_button = matches!(initial, Watched::Button(_));
+        let event = with_client(|client| {
+            let event = client.next_id();
+            // Buttons send 1 on press and 0 on release; axes only send their
+            // position, in place of the down value.
+            let up = if is_button { event } else { UNUSED };
+            let ok = unsafe {
+                SimConnect_MapClientEventToSimEvent(client.handle(), event, c"".as_ptr()) >= 0
+                    && SimConnect_MapInputEventToClientEvent_EX1(
+                        client.handle(),
+                        group,
+                        definition.as_ptr(),
+                        event,
+                        1,
+                        up,
+                        0,
+                        0,
+                    ) >= 0
+            };
+            ok.then_some(event)
+        })??;
+        self.inbox.claim(event);
+        self.watched.push((event, initial));
+        Some(self.watched.len() - 1)
+    }
+
+    /// The axis position from -1 to 1, or `None` if it hasn't moved yet.
+    pub fn axis(&self, axis: AxisId) -> Option<f64> {
+        match self.watched.get(axis.0) {
+            Some((_, Watched::Axis(value))) => *value,
+            _ => None,
+        }
+    }
+
+    /// Whether the button is held.
+    pub fn button(&self, button: ButtonId) -> bool {
+        matches!(self.watched.get(button.0), Some((_, Watched::Button(true))))
+    }
+
+    /// Process pending SimConnect messages.
+    pub fn poll(&mut self) {
+        for bytes in self.inbox.receive() {
+            self.receive(&bytes);
+        }
+    }
+
+    fn receive(&mut self, bytes: &[u8]) {
+        let Some(id) = simconnect::recv_id(bytes) else {
+            return;
+        };
+        if id as i32 == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as i32 {
+            self.receive_event(bytes);
+        } else if id as i32 == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CONTROLLERS_LIST as i32 {
+            self.receive_controllers(bytes);
+        }
+    }
+
+    fn receive_event(&mut self, bytes: &[u8]) {
+        if read_u32(bytes, GROUP) != Some(self.group as u32) {
+            return;
+        }
+        let (Some(event), Some(data)) = (read_u32(bytes, EVENT), read_u32(bytes, DATA)) else {
+            return;
+        };
+        match self.watched.iter_mut().find(|(id, _)| *id as u32 == event) {
+            Some((_, Watched::Axis(value))) => {
+                *value = Some((data as i32 as f64 / AXIS_RANGE).clamp(-1.0, 1.0));
+            }
+            Some((_, Watched::Button(held))) => *held = data != 0,
+            None => {}
+        }
+    }
+
+    fn receive_controllers(&mut self, bytes: &[u8]) {
+        let (Some(count), Some(entry), Some(out_of)) = (
+            read_u32(bytes, 16),
+            read_u32(bytes, 20),
+            read_u32(bytes, 24),
+        ) else {
+            return;
+        };
+        for i in 0..count as usize {
+            let at = LIST_HEADER + i * CONTROLLER;
+            let Some(name) = bytes.get(at..at + 256) else {
+                break;
+            };
+            let name = name.split(|&b| b == 0).next().unwrap_or_default();
+            let ids = (
+                read_u32(bytes, at + 256),
+                read_u32(bytes, at + 260),
+                read_u32(bytes, at + 264),
+            );
+            let (Some(device_id), Some(product_id), Some(composite_id)) = ids else {
+                break;
+            };
+            let mut hardware_version = [0; 4];
+            for (n, v) in hardware_version.iter_mut().enumerate() {
+                *v = read_u16(bytes, at + 268 + n * 2).unwrap_or(0);
+            }
+            self.controllers.push(ControllerInfo {
+                name: String::from_utf8_lossy(name).into_owned(),
+                device_id,
+                product_id,
+                composite_id,
+                hardware_version,
+            });
+        }
+        if entry + 1 >= out_of {
+            self.listed = true;
+        }
+    }
+}
+
+impl Drop for InputDevices {
+    fn drop(&mut self) {
+        simconnect::try_with_client(|client| unsafe {
+            SimConnect_ClearInputGroup(client.handle(), self.group);
+        });
+    }
+}
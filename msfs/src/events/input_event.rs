@@ -0,0 +1,335 @@
+//! Input events (`B:`), the MSFS 2024 interaction layer that replaces many key events.
+//!
+//! Values and triggers go through the gauge calculator. Listing the input events of
+//! the loaded aircraft needs a SimConnect request, see [`InputEventEnumeration`].
+//! Change listeners ([`InputEvent::subscribe`]) are checked once per module
+//! update by the export macros.
+
+use crate::events::recorder::{self, Direction, EventKind};
+use crate::simconnect::{self, read_u32};
+use crate::sys::{
+    DWORD, HANDLE, SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_DOUBLE,
+    SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_STRING,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENTS,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION, SimConnect_Close,
+    SimConnect_EnumerateInputEvents,
+};
+use crate::vars::calc;
+use std::{
+    cell::RefCell,
+    ffi::{CString, NulError},
+};
+
+/// A named input event, e.g. `LIGHTING_LANDING_1`.
+///
+/// # Examples
+/// ```no_run
+/// use msfs::events::InputEvent;
+///
+/// let landing = InputEvent::new("B:LIGHTING_LANDING_1")?;
+/// if landing.get() == Some(0.0) {
+///     landing.set(1.0);
+/// }
+/// landing.trigger("Toggle");
+/// # Ok::<(), std::ffi::NulError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputEvent {
+    name: String,
+    /// `(B:NAME, Number)`, built once since reads are usually per frame.
+    get_code: CString,
+}
+
+impl InputEvent {
+    /// A leading `B:` on `name` is optional.
+    pub fn new(name: &str) -> Result<Self, NulError> {
+        let name = name
+            .get(..2)
+            .filter(|p| p.eq_ignore_ascii_case("B:"))
+            .map_or(name, |_| &name[2..])
+            .trim();
+        let get_code = CString::new(format!("(B:{name}, Number)"))?;
+        Ok(Self {
+            name: name.to_string(),
+            get_code,
+        })
+    }
+
+    /// Event name without the `B:` prefix.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Current value, or `None` if the aircraft has no such input event.
+    pub fn get(&self) -> Option<f64> {
+        calc::execute_cstr(&self.get_code)
+            .ok()
+            .map(|out| out.number)
+    }
+
+    /// Set the value (the event's `_Set` binding).
+    pub fn set(&self, value: f64) -> bool {
+        self.run("Set", Some(value))
+    }
+
+    /// Fire one of the event's bindings, e.g. `"Toggle"`, `"Inc"`, `"Dec"`, `"On"`.
+    pub fn trigger(&self, binding: &str) -> bool {
+        self.run(binding, None)
+    }
+
+    /// Fire a binding that takes a value, e.g. `trigger_with("Set", 0.5)`.
+    pub fn trigger_with(&self, binding: &str, value: f64) -> bool {
+        self.run(binding, Some(value))
+    }
+
+    fn run(&self, binding: &str, value: Option<f64>) -> bool {
+        let name = format!("{}_{binding}", self.name);
+        let values = value.as_slice();
+        recorder::record(Direction::Fired, EventKind::Input, &name, values);
+        match value {
+            Some(value) => calc::run(&format!("{value} (>B:{name})")),
+            None => calc::run(&format!("(>B:{name})")),
+        }
+    }
+
+    /// Call `callback` with the new value whenever input event `name` changes,
+    /// like a JS `Coherent.on` listener. Values are compared once per update;
+    /// the first value read is the baseline and doesn't fire.
+    ///
+    /// ```no_run
+    /// use msfs::events::InputEvent;
+    ///
+    /// let sub = InputEvent::subscribe("LIGHTING_LANDING_1", |on| {
+    ///     println!("landing lights: {on}");
+    /// })?;
+    /// // later:
+    /// sub.unsubscribe();
+    /// # Ok::<(), std::ffi::NulError>(())
+    /// ```
+    pub fn subscribe(
+        name: &str,
+        callback: impl FnMut(f64) + 'static,
+    ) -> Result<InputEventSubscription, NulError> {
+        let event = InputEvent::new(name)?;
+        Ok(SUBSCRIPTIONS.with(|s| {
+            let mut s = s.borrow_mut();
+            s.next_id += 1;
+            let id = s.next_id;
+            s.entries.push(Subscription {
+                id,
+                event,
+                last: None,
+                callback: Box::new(callback),
+            });
+            InputEventSubscription(id)
+        }))
+    }
+}
+
+/// Handle returned by [`InputEvent::subscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputEventSubscription(u32);
+
+impl InputEventSubscription {
+    /// Stop calling the callback. Safe to call from inside a callback.
+    pub fn unsubscribe(self) {
+        SUBSCRIPTIONS.with(|s| {
+            let mut s = s.borrow_mut();
+            s.entries.retain(|e| e.id != self.0);
+            if s.updating {
+                s.removed.push(self.0);
+            }
+        });
+    }
+}
+
+struct Subscription {
+    id: u32,
+    event: InputEvent,
+    last: Option<f64>,
+    callback: Box<dyn FnMut(f64)>,
+}
+
+#[derive(Default)]
+struct Subscriptions {
+    next_id: u32,
+    entries: Vec<Subscription>,
+    /// Set while [`update`] runs callbacks, which may (un)subscribe.
+    updating: bool,
+    removed: Vec<u32>,
+}
+
+thread_local! {
+    static SUBSCRIPTIONS: RefCell<Subscriptions> = RefCell::new(Subscriptions::default());
+}
+
+/// Read every subscribed input event and call the listeners of those that
+/// changed. Called by the export macros at the start of each update.
+pub fn update() {
+    let mut entries = SUBSCRIPTIONS.with(|s| {
+        let mut s = s.borrow_mut();
+        s.updating = true;
+        std::mem::take(&mut s.entries)
+    });
+    for entry in &mut entries {
+        let Some(value) = entry.event.get() else {
+            continue;
+        };
+        let changed = entry
+            .last
+            .is_some_and(|last| last != value && !(last.is_nan() && value.is_nan()));
+        entry.last = Some(value);
+        let removed = SUBSCRIPTIONS.with(|s| s.borrow().removed.contains(&entry.id));
+        if changed && !removed {
+            recorder::record(
+                Direction::Received,
+                EventKind::Input,
+                entry.event.name(),
+                &[value],
+            );
+            (entry.callback)(value);
+        }
+    }
+    SUBSCRIPTIONS.with(|s| {
+        let mut s = s.borrow_mut();
+        s.updating = false;
+        let removed = std::mem::take(&mut s.removed);
+        entries.retain(|e| !removed.contains(&e.id));
+        // Keep subscriptions added by callbacks.
+        entries.append(&mut s.entries);
+        s.entries = entries;
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEventType {
+    Double,
+    String,
+    Other(i32),
+}
+
+/// One entry of [`InputEventEnumeration`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputEventInfo {
+    pub name: String,
+    /// SimConnect's id for the event.
+    pub hash: u64,
+    pub value_type: InputEventType,
+}
+
+/// Lists the input events of the loaded aircraft.
+///
+/// Opens a SimConnect client, so start it once (e.g. in `init`) and call
+/// [`InputEventEnumeration::poll`] from `update` until it returns the list.
+///
+/// ```no_run
+/// use msfs::events::InputEventEnumeration;
+///
+/// let mut listing = InputEventEnumeration::start().expect("SimConnect unavailable");
+/// // in update():
+/// if let Some(events) = listing.poll() {
+///     for e in events {
+///         println!("B:{}", e.name);
+///     }
+/// }
+/// ```
+pub struct InputEventEnumeration {
+    handle: HANDLE,
+    events: Vec<InputEventInfo>,
+    done: bool,
+}
+
+const REQUEST_ID: DWORD = 1;
+
+// SimConnect records are `#pragma pack(1)` and the list header and descriptor use
+// DWORDs (4 bytes), so parse them from the bytes rather than relying on how the
+// bindings lay out the C++ struct inheritance.
+const LIST_HEADER: usize = 28; // dwSize dwVersion dwID dwRequestID dwArraySize dwEntryNumber dwOutOf
+const DESCRIPTOR: usize = 76; // char Name[64]; DWORD64 Hash; SIMCONNECT_INPUT_EVENT_TYPE eType
+
+impl InputEventEnumeration {
+    /// Open a SimConnect client and request the list. `None` if SimConnect is
+    /// unavailable.
+    pub fn start() -> Option<Self> {
+        let handle = simconnect::open("infinity-rs input events")?;
+        unsafe {
+            if SimConnect_EnumerateInputEvents(handle, REQUEST_ID) < 0 {
+                SimConnect_Close(handle);
+                return None;
+            }
+        }
+        Some(Self {
+            handle,
+            events: Vec::new(),
+            done: false,
+        })
+    }
+
+    /// Process pending SimConnect messages. Returns the list once complete.
+    pub fn poll(&mut self) -> Option<&[InputEventInfo]> {
+        if !self.done {
+            let handle = self.handle;
+            simconnect::dispatch(handle, |bytes| self.receive(bytes));
+        }
+        self.done.then_some(self.events.as_slice())
+    }
+
+    fn receive(&mut self, bytes: &[u8]) {
+        let Some(id) = simconnect::recv_id(bytes) else {
+            return;
+        };
+        if id as i32 == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION as i32 {
+            // The request failed; report whatever arrived (usually nothing).
+            self.done = true;
+            return;
+        }
+        if id as i32 != SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_ENUMERATE_INPUT_EVENTS as i32
+            || read_u32(bytes, 12) != Some(REQUEST_ID as u32)
+        {
+            return;
+        }
+        let (Some(count), Some(entry), Some(out_of)) = (
+            read_u32(bytes, 16),
+            read_u32(bytes, 20),
+            read_u32(bytes, 24),
+        ) else {
+            return;
+        };
+
+        for i in 0..count as usize {
+            let at = LIST_HEADER + i * DESCRIPTOR;
+            let Some(desc) = bytes.get(at..at + DESCRIPTOR) else {
+                break;
+            };
+            let name = &desc[..64];
+            let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(64)];
+            let hash = u64::from_le_bytes(desc[64..72].try_into().unwrap_or_default());
+            let value_type = match read_u32(desc, 72).unwrap_or_default() as i32 {
+                t if t == SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_DOUBLE as i32 => {
+                    InputEventType::Double
+                }
+                t if t == SIMCONNECT_INPUT_EVENT_TYPE_SIMCONNECT_INPUT_EVENT_TYPE_STRING as i32 => {
+                    InputEventType::String
+                }
+                t => InputEventType::Other(t),
+            };
+            self.events.push(InputEventInfo {
+                name: String::from_utf8_lossy(name).into_owned(),
+                hash,
+                value_type,
+            });
+        }
+
+        if entry + 1 >= out_of {
+            self.done = true;
+        }
+    }
+}
+
+impl Drop for InputEventEnumeration {
+    fn drop(&mut self) {
+        unsafe {
+            SimConnect_Close(self.handle);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Key event interception.
+//!
+//! An [`Interceptor`] is a [`NotificationGroup`] at the highest maskable
+//! priority. Masked events stop there, so the sim's own handler doesn't run,
+//! and the Rust handler decides whether to [`forward`](Intercepted::forward)
+//! them, possibly with changed parameters:
+//!
+//! ```no_run
+//! use msfs::events::intercept::Interceptor;
+//!
+//! let mut interceptor = Interceptor::new().expect("SimConnect unavailable");
+//! interceptor.intercept("GEAR_TOGGLE", |event| {
+//!     let hydraulics_ok = true;
+//!     if hydraulics_ok {
+//!         event.forward();
+//!     }
+//! });
+//! ```
+//!
+//! Forwarded events are re-sent below the interceptor's priority, so they
+//! reach the sim without coming back to the handler. Handlers run from
+//! [`notification::update`](super::notification::update), which the export
+//! macros call each update.
+
+use crate::events::MAX_KEY_EVENT_PARAMS;
+use crate::events::notification::{NotificationGroup, Priority};
+
+/// A key event caught by an [`Interceptor`] or [`NotificationGroup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Intercepted {
+    /// The event's parameters; unused ones are `0`. Changes are sent on if
+    /// the event is forwarded.
+    pub params: [u32; MAX_KEY_EVENT_PARAMS],
+    masked: bool,
+    forward: bool,
+}
+
+impl Intercepted {
+    pub(crate) fn new(params: [u32; MAX_KEY_EVENT_PARAMS], masked: bool) -> Self {
+        Self {
+            params,
+            masked,
+            forward: false,
+        }
+    }
+
+    /// Pass the event on to the sim (and lower-priority listeners) after the
+    /// handler returns. Only needed for masked events, which are dropped
+    /// otherwise.
+    pub fn forward(&mut self) {
+        self.forward = true;
+    }
+
+    pub(crate) fn is_forwarded(&self) -> bool {
+        self.forward
+    }
+
+    /// Whether the sim's handler was held back for this event, i.e. it came
+    /// from [`Interceptor::intercept`] rather than [`Interceptor::observe`].
+    pub fn is_masked(&self) -> bool {
+        self.masked
+    }
+}
+
+/// Key event handlers that run before the sim's.
+pub struct Interceptor {
+    group: NotificationGroup,
+}
+
+impl Interceptor {
+    /// `None` if SimConnect is unavailable.
+    pub fn new() -> Option<Self> {
+        NotificationGroup::new(Priority::HIGHEST_MASKABLE).map(|group| Self { group })
+    }
+
+    /// Take over key event `name` (a leading `K:` is optional): the sim only
+    /// sees it if `callback` [forwards](Intercepted::forward) it.
+    pub fn intercept(
+        &mut self,
+        name: &str,
+        callback: impl FnMut(&mut Intercepted) + 'static,
+    ) -> bool {
+        self.group.intercept(name, callback)
+    }
+
+    /// Listen to key event `name` without holding it back from the sim.
+    pub fn observe(
+        &mut self,
+        name: &str,
+        callback: impl FnMut(&mut Intercepted) + 'static,
+    ) -> bool {
+        self.group.observe(name, callback)
+    }
+
+    /// Names of the handled events, in the order they were added.
+    pub fn events(&self) -> impl Iterator<Item = &str> {
+        self.group.events()
+    }
+
+    /// Run handlers for events received so far, for code outside the export
+    /// macros, which already do this each update.
+    pub fn poll(&mut self) {
+        super::notification::update();
+    }
+}
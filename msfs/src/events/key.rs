@@ -0,0 +1,658 @@
+//! Key events (`K:`).
+
+use crate::events::recorder::{self, Direction, EventKind};
+use crate::vars::calc;
+use std::ffi::{CString, NulError};
+
+/// Most parameters a key event takes (`_EX1` events).
+pub const MAX_KEY_EVENT_PARAMS: usize = 5;
+
+/// Parameters an event not in [`KeyEventId`] takes unless declared with
+/// [`KeyEvent::with_arity`]: none, or the event's value.
+pub const DEFAULT_KEY_EVENT_ARITY: (usize, usize) = (0, 1);
+
+/// Fewer or more parameters than the event takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArityError {
+    pub min: usize,
+    pub max: usize,
+    pub got: usize,
+}
+
+impl std::fmt::Display for ArityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.min == self.max {
+            write!(f, "event takes {} parameters, got {}", self.max, self.got)
+        } else {
+            write!(
+                f,
+                "event takes {} to {} parameters, got {}",
+                self.min, self.max, self.got
+            )
+        }
+    }
+}
+
+impl std::error::Error for ArityError {}
+
+/// A named key event, e.g. `TOGGLE_MASTER_BATTERY`.
+///
+/// # Examples
+/// ```no_run
+/// use msfs::events::KeyEvent;
+///
+/// let battery = KeyEvent::new("TOGGLE_MASTER_BATTERY")?;
+/// battery.fire();
+///
+/// let heading_bug = KeyEvent::new("HEADING_BUG_SET")?;
+/// heading_bug.fire_with(&[270]);
+/// # Ok::<(), std::ffi::NulError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyEvent {
+    name: String,
+    /// `(>K:NAME)`, built once since `fire()` is the common case.
+    code: CString,
+    min_params: usize,
+    max_params: usize,
+}
+
+impl KeyEvent {
+    /// A leading `K:` on `name` is optional.
+    ///
+    /// Events in [`KeyEventId`] get their parameter counts from the SDK's
+    /// event list; others take [`DEFAULT_KEY_EVENT_ARITY`] unless declared
+    /// with [`KeyEvent::with_arity`].
+    pub fn new(name: &str) -> Result<Self, NulError> {
+        let name = strip_prefix(name);
+        let code = CString::new(format!("(>K:{name})"))?;
+        let (min_params, max_params) =
+            KeyEventId::from_name(name).map_or(DEFAULT_KEY_EVENT_ARITY, KeyEventId::arity);
+        Ok(Self {
+            name: name.to_string(),
+            code,
+            min_params,
+            max_params,
+        })
+    }
+
+    /// Declare how many parameters [`KeyEvent::fire_with`] accepts, e.g.
+    /// `(1, 5)` for an `_EX1` event. `max` is capped at
+    /// [`MAX_KEY_EVENT_PARAMS`] and `min` at `max`.
+    pub fn with_arity(mut self, min: usize, max: usize) -> Self {
+        self.max_params = max.min(MAX_KEY_EVENT_PARAMS);
+        self.min_params = min.min(self.max_params);
+        self
+    }
+
+    /// [`KeyEvent::with_arity`] keeping the current minimum.
+    pub fn with_max_params(self, max_params: usize) -> Self {
+        let min = self.min_params;
+        self.with_arity(min, max_params)
+    }
+
+    pub fn min_params(&self) -> usize {
+        self.min_params
+    }
+
+    pub fn max_params(&self) -> usize {
+        self.max_params
+    }
+
+    /// `Err` if `params` has fewer or more values than the event takes.
+    pub fn check_params(&self, params: &[u32]) -> Result<(), ArityError> {
+        check_params((self.min_params, self.max_params), params)
+    }
+
+    /// Event name without the `K:` prefix.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Fire with no parameter. Returns `false` if the event requires
+    /// parameters or could not be sent.
+    pub fn fire(&self) -> bool {
+        if self.min_params > 0 {
+            return false;
+        }
+        recorder::record(Direction::Fired, EventKind::Key, &self.name, &[]);
+        calc::run_cstr(&self.code)
+    }
+
+    /// Fire with [`KeyEvent::min_params`] to [`KeyEvent::max_params`]
+    /// parameters.
+    ///
+    /// Returns `false` if the parameter count is wrong or the event could not
+    /// be sent.
+    pub fn fire_with(&self, params: &[u32]) -> bool {
+        if self.check_params(params).is_err() {
+            return false;
+        }
+        if params.is_empty() {
+            return self.fire();
+        }
+        record_fired(&self.name, params);
+        code(&self.name, params).is_some_and(|code| calc::run(&code))
+    }
+}
+
+pub(crate) fn strip_prefix(name: &str) -> &str {
+    name.get(..2)
+        .filter(|p| p.eq_ignore_ascii_case("K:"))
+        .map_or(name, |_| &name[2..])
+        .trim()
+}
+
+fn check_params((min, max): (usize, usize), params: &[u32]) -> Result<(), ArityError> {
+    if !(min..=max).contains(&params.len()) {
+        Err(ArityError {
+            min,
+            max,
+            got: params.len(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn record_fired(name: &str, params: &[u32]) {
+    let values: Vec<f64> = params.iter().map(|&p| p as f64).collect();
+    recorder::record(Direction::Fired, EventKind::Key, name, &values);
+}
+
+/// Calculator code firing `name` with `params`, or `None` if there are too many.
+fn code(name: &str, params: &[u32]) -> Option<String> {
+    Some(match params.len() {
+        0 => format!("(>K:{name})"),
+        1 => format!("{} (>K:{name})", params[0]),
+        n if n <= MAX_KEY_EVENT_PARAMS => {
+            let values: Vec<String> = params.iter().map(u32::to_string).collect();
+            format!("{} (>K:{n}:{name})", values.join(" "))
+        }
+        _ => return None,
+    })
+}
+
+/// Fire the key event `name` with as many parameters as it takes, see
+/// [`KeyEvent::new`], without keeping a [`KeyEvent`] around.
+pub fn fire_key_event(name: &str, params: &[u32]) -> bool {
+    KeyEvent::new(name).is_ok_and(|event| event.fire_with(params))
+}
+
+macro_rules! key_events {
+    ($($variant:ident => $name:literal, $min:literal..=$max:literal;)*) => {
+        /// Key events from the SDK's event list, so event names are checked at
+        /// compile time, with the number of parameters each takes (optional
+        /// ones are usually an engine, circuit or radio index). Anything
+        /// missing can still be fired by name with [`KeyEvent`].
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum KeyEventId {
+            $(
+                #[doc = concat!("`", $name, "`")]
+                $variant,
+            )*
+        }
+
+        impl KeyEventId {
+            pub const ALL: &'static [KeyEventId] = &[$(KeyEventId::$variant,)*];
+
+            /// The event's name, without the `K:` prefix.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(KeyEventId::$variant => $name,)*
+                }
+            }
+
+            /// Fewest and most parameters the event takes.
+            pub const fn arity(self) -> (usize, usize) {
+                match self {
+                    $(KeyEventId::$variant => ($min, $max),)*
+                }
+            }
+        }
+    };
+}
+
+key_events! {
+    // Electrical
+    ToggleMasterBattery => "TOGGLE_MASTER_BATTERY", 0..=1;
+    MasterBatteryOn => "MASTER_BATTERY_ON", 0..=1;
+    MasterBatteryOff => "MASTER_BATTERY_OFF", 0..=1;
+    MasterBatterySet => "MASTER_BATTERY_SET", 1..=2;
+    ToggleMasterAlternator => "TOGGLE_MASTER_ALTERNATOR", 0..=1;
+    AlternatorOn => "ALTERNATOR_ON", 0..=1;
+    AlternatorOff => "ALTERNATOR_OFF", 0..=1;
+    AlternatorSet => "ALTERNATOR_SET", 1..=2;
+    ToggleAlternator1 => "TOGGLE_ALTERNATOR1", 0..=0;
+    ToggleAlternator2 => "TOGGLE_ALTERNATOR2", 0..=0;
+    ToggleAvionicsMaster => "TOGGLE_AVIONICS_MASTER", 0..=0;
+    AvionicsMasterSet => "AVIONICS_MASTER_SET", 1..=1;
+    AvionicsMaster1On => "AVIONICS_MASTER_1_ON", 0..=0;
+    AvionicsMaster1Off => "AVIONICS_MASTER_1_OFF", 0..=0;
+    AvionicsMaster2On => "AVIONICS_MASTER_2_ON", 0..=0;
+    AvionicsMaster2Off => "AVIONICS_MASTER_2_OFF", 0..=0;
+    ToggleExternalPower => "TOGGLE_EXTERNAL_POWER", 0..=1;
+    ApuStarter => "APU_STARTER", 0..=1;
+    ApuOffSwitch => "APU_OFF_SWITCH", 0..=1;
+    ApuGeneratorSwitchToggle => "APU_GENERATOR_SWITCH_TOGGLE", 0..=1;
+    ApuBleedAirSourceToggle => "APU_BLEED_AIR_SOURCE_TOGGLE", 0..=0;
+    ElectricalCircuitToggle => "ELECTRICAL_CIRCUIT_TOGGLE", 1..=1;
+    ElectricalBusToBusConnectionToggle => "ELECTRICAL_BUS_TO_BUS_CONNECTION_TOGGLE", 2..=2;
+
+    // Lights
+    ToggleBeaconLights => "TOGGLE_BEACON_LIGHTS", 0..=1;
+    BeaconLightsSet => "BEACON_LIGHTS_SET", 1..=2;
+    ToggleTaxiLights => "TOGGLE_TAXI_LIGHTS", 0..=1;
+    TaxiLightsSet => "TAXI_LIGHTS_SET", 1..=2;
+    LandingLightsToggle => "LANDING_LIGHTS_TOGGLE", 0..=1;
+    LandingLightsOn => "LANDING_LIGHTS_ON", 0..=1;
+    LandingLightsOff => "LANDING_LIGHTS_OFF", 0..=1;
+    LandingLightsSet => "LANDING_LIGHTS_SET", 1..=2;
+    StrobesToggle => "STROBES_TOGGLE", 0..=0;
+    StrobesOn => "STROBES_ON", 0..=1;
+    StrobesOff => "STROBES_OFF", 0..=1;
+    StrobesSet => "STROBES_SET", 1..=2;
+    ToggleNavLights => "TOGGLE_NAV_LIGHTS", 0..=1;
+    NavLightsSet => "NAV_LIGHTS_SET", 1..=2;
+    PanelLightsToggle => "PANEL_LIGHTS_TOGGLE", 0..=1;
+    PanelLightsSet => "PANEL_LIGHTS_SET", 1..=2;
+    ToggleLogoLights => "TOGGLE_LOGO_LIGHTS", 0..=1;
+    LogoLightsSet => "LOGO_LIGHTS_SET", 1..=2;
+    ToggleWingLights => "TOGGLE_WING_LIGHTS", 0..=1;
+    WingLightsSet => "WING_LIGHTS_SET", 1..=2;
+    ToggleRecognitionLights => "TOGGLE_RECOGNITION_LIGHTS", 0..=1;
+    RecognitionLightsSet => "RECOGNITION_LIGHTS_SET", 1..=2;
+    ToggleCabinLights => "TOGGLE_CABIN_LIGHTS", 0..=1;
+    CabinLightsSet => "CABIN_LIGHTS_SET", 1..=2;
+    AllLightsToggle => "ALL_LIGHTS_TOGGLE", 0..=0;
+    LightPotentiometerSet => "LIGHT_POTENTIOMETER_SET", 2..=2;
+
+    // Engines
+    EngineAutoStart => "ENGINE_AUTO_START", 0..=0;
+    EngineAutoShutdown => "ENGINE_AUTO_SHUTDOWN", 0..=0;
+    ThrottleFull => "THROTTLE_FULL", 0..=0;
+    ThrottleIncr => "THROTTLE_INCR", 0..=0;
+    ThrottleIncrSmall => "THROTTLE_INCR_SMALL", 0..=0;
+    ThrottleDecr => "THROTTLE_DECR", 0..=0;
+    ThrottleDecrSmall => "THROTTLE_DECR_SMALL", 0..=0;
+    ThrottleCut => "THROTTLE_CUT", 0..=0;
+    ThrottleSet => "THROTTLE_SET", 1..=1;
+    Throttle1Set => "THROTTLE1_SET", 1..=1;
+    Throttle2Set => "THROTTLE2_SET", 1..=1;
+    Throttle3Set => "THROTTLE3_SET", 1..=1;
+    Throttle4Set => "THROTTLE4_SET", 1..=1;
+    AxisThrottleSet => "AXIS_THROTTLE_SET", 1..=1;
+    AxisThrottle1Set => "AXIS_THROTTLE1_SET", 1..=1;
+    AxisThrottle2Set => "AXIS_THROTTLE2_SET", 1..=1;
+    ThrottleReverseThrustToggle => "THROTTLE_REVERSE_THRUST_TOGGLE", 0..=0;
+    PropPitchIncr => "PROP_PITCH_INCR", 0..=0;
+    PropPitchDecr => "PROP_PITCH_DECR", 0..=0;
+    PropPitchSet => "PROP_PITCH_SET", 1..=1;
+    AxisPropellerSet => "AXIS_PROPELLER_SET", 1..=1;
+    MixtureRich => "MIXTURE_RICH", 0..=0;
+    MixtureLean => "MIXTURE_LEAN", 0..=0;
+    MixtureIncr => "MIXTURE_INCR", 0..=0;
+    MixtureDecr => "MIXTURE_DECR", 0..=0;
+    MixtureSet => "MIXTURE_SET", 1..=1;
+    AxisMixtureSet => "AXIS_MIXTURE_SET", 1..=1;
+    MagnetoOff => "MAGNETO_OFF", 0..=1;
+    MagnetoRight => "MAGNETO_RIGHT", 0..=1;
+    MagnetoLeft => "MAGNETO_LEFT", 0..=1;
+    MagnetoBoth => "MAGNETO_BOTH", 0..=1;
+    MagnetoStart => "MAGNETO_START", 0..=1;
+    MagnetoSet => "MAGNETO_SET", 1..=2;
+    ToggleStarter1 => "TOGGLE_STARTER1", 0..=0;
+    ToggleStarter2 => "TOGGLE_STARTER2", 0..=0;
+    SetStarter1Held => "SET_STARTER1_HELD", 1..=1;
+    SetStarter2Held => "SET_STARTER2_HELD", 1..=1;
+    ToggleEngine1Failure => "TOGGLE_ENGINE1_FAILURE", 0..=0;
+    ToggleEngine2Failure => "TOGGLE_ENGINE2_FAILURE", 0..=0;
+    FuelPump => "FUEL_PUMP", 0..=0;
+    ToggleElectFuelPump => "TOGGLE_ELECT_FUEL_PUMP", 0..=1;
+    ToggleElectFuelPump1 => "TOGGLE_ELECT_FUEL_PUMP1", 0..=0;
+    ToggleElectFuelPump2 => "TOGGLE_ELECT_FUEL_PUMP2", 0..=0;
+    ElectFuelPump1Set => "ELECT_FUEL_PUMP1_SET", 1..=1;
+    ElectFuelPump2Set => "ELECT_FUEL_PUMP2_SET", 1..=1;
+    AntiIceToggle => "ANTI_ICE_TOGGLE", 0..=0;
+    AntiIceSet => "ANTI_ICE_SET", 1..=1;
+    PitotHeatToggle => "PITOT_HEAT_TOGGLE", 0..=1;
+    PitotHeatOn => "PITOT_HEAT_ON", 0..=1;
+    PitotHeatOff => "PITOT_HEAT_OFF", 0..=1;
+    PitotHeatSet => "PITOT_HEAT_SET", 1..=2;
+    TogglePropellerDeice => "TOGGLE_PROPELLER_DEICE", 0..=1;
+    CarbHeatToggle => "CARB_HEAT_TOGGLE", 0..=0;
+    CarbHeatSet => "CARB_HEAT_SET", 1..=1;
+    Cowlflap1Set => "COWLFLAP1_SET", 1..=1;
+    Cowlflap2Set => "COWLFLAP2_SET", 1..=1;
+    FuelsystemPumpToggle => "FUELSYSTEM_PUMP_TOGGLE", 1..=1;
+    FuelsystemValveToggle => "FUELSYSTEM_VALVE_TOGGLE", 1..=1;
+    FuelSelectorOff => "FUEL_SELECTOR_OFF", 0..=0;
+    FuelSelectorAll => "FUEL_SELECTOR_ALL", 0..=0;
+    FuelSelectorLeft => "FUEL_SELECTOR_LEFT", 0..=0;
+    FuelSelectorRight => "FUEL_SELECTOR_RIGHT", 0..=0;
+    FuelSelectorSet => "FUEL_SELECTOR_SET", 1..=1;
+
+    // Flight controls
+    ElevatorTrimUp => "ELEVATOR_TRIM_UP", 0..=0;
+    ElevatorTrimDown => "ELEVATOR_TRIM_DOWN", 0..=0;
+    ElevatorTrimSet => "ELEVATOR_TRIM_SET", 1..=1;
+    AxisElevTrimSet => "AXIS_ELEV_TRIM_SET", 1..=1;
+    AileronTrimLeft => "AILERON_TRIM_LEFT", 0..=0;
+    AileronTrimRight => "AILERON_TRIM_RIGHT", 0..=0;
+    AileronTrimSet => "AILERON_TRIM_SET", 1..=1;
+    RudderTrimLeft => "RUDDER_TRIM_LEFT", 0..=0;
+    RudderTrimRight => "RUDDER_TRIM_RIGHT", 0..=0;
+    RudderTrimSet => "RUDDER_TRIM_SET", 1..=1;
+    RudderTrimReset => "RUDDER_TRIM_RESET", 0..=0;
+    AxisElevatorSet => "AXIS_ELEVATOR_SET", 1..=1;
+    AxisAileronsSet => "AXIS_AILERONS_SET", 1..=1;
+    AxisRudderSet => "AXIS_RUDDER_SET", 1..=1;
+    ElevatorSet => "ELEVATOR_SET", 1..=1;
+    AileronSet => "AILERON_SET", 1..=1;
+    RudderSet => "RUDDER_SET", 1..=1;
+    CenterAilerRudder => "CENTER_AILER_RUDDER", 0..=0;
+    FlapsUp => "FLAPS_UP", 0..=0;
+    FlapsDown => "FLAPS_DOWN", 0..=0;
+    FlapsIncr => "FLAPS_INCR", 0..=0;
+    FlapsDecr => "FLAPS_DECR", 0..=0;
+    FlapsSet => "FLAPS_SET", 1..=1;
+    Flaps1 => "FLAPS_1", 0..=0;
+    Flaps2 => "FLAPS_2", 0..=0;
+    Flaps3 => "FLAPS_3", 0..=0;
+    AxisFlapsSet => "AXIS_FLAPS_SET", 1..=1;
+    SpoilersToggle => "SPOILERS_TOGGLE", 0..=0;
+    SpoilersOn => "SPOILERS_ON", 0..=0;
+    SpoilersOff => "SPOILERS_OFF", 0..=0;
+    SpoilersSet => "SPOILERS_SET", 1..=1;
+    SpoilersArmToggle => "SPOILERS_ARM_TOGGLE", 0..=0;
+    SpoilersArmOn => "SPOILERS_ARM_ON", 0..=0;
+    SpoilersArmOff => "SPOILERS_ARM_OFF", 0..=0;
+    SpoilersArmSet => "SPOILERS_ARM_SET", 1..=1;
+    AxisSpoilerSet => "AXIS_SPOILER_SET", 1..=1;
+    YawDamperToggle => "YAW_DAMPER_TOGGLE", 0..=0;
+    YawDamperOn => "YAW_DAMPER_ON", 0..=0;
+    YawDamperOff => "YAW_DAMPER_OFF", 0..=0;
+    YawDamperSet => "YAW_DAMPER_SET", 1..=1;
+
+    // Gear and brakes
+    GearToggle => "GEAR_TOGGLE", 0..=0;
+    GearUp => "GEAR_UP", 0..=0;
+    GearDown => "GEAR_DOWN", 0..=0;
+    GearSet => "GEAR_SET", 1..=1;
+    GearPump => "GEAR_PUMP", 0..=0;
+    Brakes => "BRAKES", 0..=0;
+    BrakesLeft => "BRAKES_LEFT", 0..=0;
+    BrakesRight => "BRAKES_RIGHT", 0..=0;
+    AxisLeftBrakeSet => "AXIS_LEFT_BRAKE_SET", 1..=1;
+    AxisRightBrakeSet => "AXIS_RIGHT_BRAKE_SET", 1..=1;
+    ParkingBrakes => "PARKING_BRAKES", 0..=0;
+    ParkingBrakeSet => "PARKING_BRAKE_SET", 1..=1;
+    AntiskidBrakesToggle => "ANTISKID_BRAKES_TOGGLE", 0..=0;
+    SetAutobrakeControl => "SET_AUTOBRAKE_CONTROL", 0..=0;
+    IncreaseAutobrakeControl => "INCREASE_AUTOBRAKE_CONTROL", 0..=0;
+    DecreaseAutobrakeControl => "DECREASE_AUTOBRAKE_CONTROL", 0..=0;
+    ToggleTailWheelLock => "TOGGLE_TAIL_WHEEL_LOCK", 0..=0;
+    ToggleWaterRudder => "TOGGLE_WATER_RUDDER", 0..=0;
+    SteeringSet => "STEERING_SET", 1..=1;
+
+    // Autopilot
+    ApMaster => "AP_MASTER", 0..=0;
+    AutopilotOn => "AUTOPILOT_ON", 0..=0;
+    AutopilotOff => "AUTOPILOT_OFF", 0..=0;
+    AutopilotDisengageToggle => "AUTOPILOT_DISENGAGE_TOGGLE", 0..=0;
+    ApPanelHeadingHold => "AP_PANEL_HEADING_HOLD", 0..=0;
+    ApHdgHold => "AP_HDG_HOLD", 0..=0;
+    ApHdgHoldOn => "AP_HDG_HOLD_ON", 0..=0;
+    ApHdgHoldOff => "AP_HDG_HOLD_OFF", 0..=0;
+    ApPanelAltitudeHold => "AP_PANEL_ALTITUDE_HOLD", 0..=0;
+    ApAltHold => "AP_ALT_HOLD", 0..=0;
+    ApAltHoldOn => "AP_ALT_HOLD_ON", 0..=0;
+    ApAltHoldOff => "AP_ALT_HOLD_OFF", 0..=0;
+    ApAttHold => "AP_ATT_HOLD", 0..=0;
+    ApLocHold => "AP_LOC_HOLD", 0..=0;
+    ApAprHold => "AP_APR_HOLD", 0..=0;
+    ApAprHoldOn => "AP_APR_HOLD_ON", 0..=0;
+    ApAprHoldOff => "AP_APR_HOLD_OFF", 0..=0;
+    ApBcHold => "AP_BC_HOLD", 0..=0;
+    ApNav1Hold => "AP_NAV1_HOLD", 0..=0;
+    ApNav1HoldOn => "AP_NAV1_HOLD_ON", 0..=0;
+    ApNav1HoldOff => "AP_NAV1_HOLD_OFF", 0..=0;
+    ApNavSelectSet => "AP_NAV_SELECT_SET", 1..=1;
+    ApWingLeveler => "AP_WING_LEVELER", 0..=0;
+    ApVsHold => "AP_VS_HOLD", 0..=0;
+    ApVsOn => "AP_VS_ON", 0..=0;
+    ApVsOff => "AP_VS_OFF", 0..=0;
+    ApVsSet => "AP_VS_SET", 1..=1;
+    ApVsVarInc => "AP_VS_VAR_INC", 0..=1;
+    ApVsVarDec => "AP_VS_VAR_DEC", 0..=1;
+    ApVsVarSetEnglish => "AP_VS_VAR_SET_ENGLISH", 1..=2;
+    ApVsVarSetMetric => "AP_VS_VAR_SET_METRIC", 1..=2;
+    ApAltVarInc => "AP_ALT_VAR_INC", 0..=1;
+    ApAltVarDec => "AP_ALT_VAR_DEC", 0..=1;
+    ApAltVarSetEnglish => "AP_ALT_VAR_SET_ENGLISH", 1..=2;
+    ApAltVarSetMetric => "AP_ALT_VAR_SET_METRIC", 1..=2;
+    ApSpdVarInc => "AP_SPD_VAR_INC", 0..=1;
+    ApSpdVarDec => "AP_SPD_VAR_DEC", 0..=1;
+    ApSpdVarSet => "AP_SPD_VAR_SET", 1..=2;
+    ApMachVarInc => "AP_MACH_VAR_INC", 0..=0;
+    ApMachVarDec => "AP_MACH_VAR_DEC", 0..=0;
+    ApMachVarSet => "AP_MACH_VAR_SET", 1..=1;
+    ApPanelSpeedHold => "AP_PANEL_SPEED_HOLD", 0..=0;
+    ApAirspeedHold => "AP_AIRSPEED_HOLD", 0..=0;
+    ApAirspeedOn => "AP_AIRSPEED_ON", 0..=0;
+    ApAirspeedOff => "AP_AIRSPEED_OFF", 0..=0;
+    ApPanelMachHold => "AP_PANEL_MACH_HOLD", 0..=0;
+    ApMachHold => "AP_MACH_HOLD", 0..=0;
+    ApFlightLevelChange => "AP_FLIGHT_LEVEL_CHANGE", 0..=0;
+    ApFlightLevelChangeOn => "AP_FLIGHT_LEVEL_CHANGE_ON", 0..=0;
+    ApFlightLevelChangeOff => "AP_FLIGHT_LEVEL_CHANGE_OFF", 0..=0;
+    ApPitchRefIncUp => "AP_PITCH_REF_INC_UP", 0..=0;
+    ApPitchRefIncDn => "AP_PITCH_REF_INC_DN", 0..=0;
+    ApPitchRefSet => "AP_PITCH_REF_SET", 1..=1;
+    ApBankHold => "AP_BANK_HOLD", 0..=0;
+    ApMaxBankInc => "AP_MAX_BANK_INC", 0..=0;
+    ApMaxBankDec => "AP_MAX_BANK_DEC", 0..=0;
+    ApMaxBankSet => "AP_MAX_BANK_SET", 1..=1;
+    AutoThrottleArm => "AUTO_THROTTLE_ARM", 0..=0;
+    AutoThrottleToGa => "AUTO_THROTTLE_TO_GA", 0..=0;
+    AutopilotAirspeedHoldCurrent => "AUTOPILOT_AIRSPEED_HOLD_CURRENT", 0..=0;
+    ToggleFlightDirector => "TOGGLE_FLIGHT_DIRECTOR", 0..=0;
+    FlightLevelChange => "FLIGHT_LEVEL_CHANGE", 0..=0;
+    HeadingBugInc => "HEADING_BUG_INC", 0..=1;
+    HeadingBugDec => "HEADING_BUG_DEC", 0..=1;
+    HeadingBugSet => "HEADING_BUG_SET", 1..=2;
+    HeadingBugSelect => "HEADING_BUG_SELECT", 0..=0;
+    HeadingSlotIndexSet => "HEADING_SLOT_INDEX_SET", 1..=1;
+    AltitudeSlotIndexSet => "ALTITUDE_SLOT_INDEX_SET", 1..=1;
+    SpeedSlotIndexSet => "SPEED_SLOT_INDEX_SET", 1..=1;
+    VsSlotIndexSet => "VS_SLOT_INDEX_SET", 1..=1;
+
+    // Radios and navigation
+    ComRadioWholeInc => "COM_RADIO_WHOLE_INC", 0..=0;
+    ComRadioWholeDec => "COM_RADIO_WHOLE_DEC", 0..=0;
+    ComRadioFractInc => "COM_RADIO_FRACT_INC", 0..=0;
+    ComRadioFractDec => "COM_RADIO_FRACT_DEC", 0..=0;
+    ComRadioSwap => "COM_RADIO_SWAP", 0..=0;
+    ComRadioSet => "COM_RADIO_SET", 1..=1;
+    ComRadioSetHz => "COM_RADIO_SET_HZ", 1..=1;
+    ComStbyRadioSet => "COM_STBY_RADIO_SET", 1..=1;
+    ComStbyRadioSetHz => "COM_STBY_RADIO_SET_HZ", 1..=1;
+    Com2RadioSwap => "COM2_RADIO_SWAP", 0..=0;
+    Com2RadioSetHz => "COM2_RADIO_SET_HZ", 1..=1;
+    Com2StbyRadioSetHz => "COM2_STBY_RADIO_SET_HZ", 1..=1;
+    Nav1RadioWholeInc => "NAV1_RADIO_WHOLE_INC", 0..=0;
+    Nav1RadioWholeDec => "NAV1_RADIO_WHOLE_DEC", 0..=0;
+    Nav1RadioFractInc => "NAV1_RADIO_FRACT_INC", 0..=0;
+    Nav1RadioFractDec => "NAV1_RADIO_FRACT_DEC", 0..=0;
+    Nav1RadioSwap => "NAV1_RADIO_SWAP", 0..=0;
+    Nav1RadioSetHz => "NAV1_RADIO_SET_HZ", 1..=1;
+    Nav1StbySetHz => "NAV1_STBY_SET_HZ", 1..=1;
+    Nav2RadioSwap => "NAV2_RADIO_SWAP", 0..=0;
+    Nav2RadioSetHz => "NAV2_RADIO_SET_HZ", 1..=1;
+    Nav2StbySetHz => "NAV2_STBY_SET_HZ", 1..=1;
+    AdfSet => "ADF_SET", 1..=1;
+    AdfCompleteSet => "ADF_COMPLETE_SET", 1..=1;
+    Adf1RadioSwap => "ADF1_RADIO_SWAP", 0..=0;
+    XpndrSet => "XPNDR_SET", 1..=2;
+    XpndrIdentOn => "XPNDR_IDENT_ON", 0..=0;
+    XpndrIdentOff => "XPNDR_IDENT_OFF", 0..=0;
+    XpndrIdentToggle => "XPNDR_IDENT_TOGGLE", 0..=0;
+    Vor1ObiInc => "VOR1_OBI_INC", 0..=0;
+    Vor1ObiDec => "VOR1_OBI_DEC", 0..=0;
+    Vor1Set => "VOR1_SET", 1..=1;
+    Vor2ObiInc => "VOR2_OBI_INC", 0..=0;
+    Vor2ObiDec => "VOR2_OBI_DEC", 0..=0;
+    Vor2Set => "VOR2_SET", 1..=1;
+    ToggleGpsDrivesNav1 => "TOGGLE_GPS_DRIVES_NAV1", 0..=0;
+    KohlsmanInc => "KOHLSMAN_INC", 0..=1;
+    KohlsmanDec => "KOHLSMAN_DEC", 0..=1;
+    KohlsmanSet => "KOHLSMAN_SET", 1..=2;
+    Barometric => "BAROMETRIC", 0..=1;
+    BarometricStdPressure => "BAROMETRIC_STD_PRESSURE", 0..=1;
+    GyroDriftSet => "GYRO_DRIFT_SET", 1..=1;
+    GyroDriftInc => "GYRO_DRIFT_INC", 0..=0;
+    GyroDriftDec => "GYRO_DRIFT_DEC", 0..=0;
+
+    // Sim
+    PauseToggle => "PAUSE_TOGGLE", 0..=0;
+    PauseOn => "PAUSE_ON", 0..=0;
+    PauseOff => "PAUSE_OFF", 0..=0;
+    PauseSet => "PAUSE_SET", 1..=1;
+    SimRateIncr => "SIM_RATE_INCR", 0..=0;
+    SimRateDecr => "SIM_RATE_DECR", 0..=0;
+    SimRateSet => "SIM_RATE_SET", 1..=1;
+    SlewToggle => "SLEW_TOGGLE", 0..=0;
+    SlewOn => "SLEW_ON", 0..=0;
+    SlewOff => "SLEW_OFF", 0..=0;
+    SoundToggle => "SOUND_TOGGLE", 0..=0;
+    SmokeToggle => "SMOKE_TOGGLE", 0..=0;
+    TogglePushback => "TOGGLE_PUSHBACK", 0..=0;
+    TugHeading => "TUG_HEADING", 1..=1;
+    TugSpeed => "TUG_SPEED", 1..=1;
+    ToggleAircraftExit => "TOGGLE_AIRCRAFT_EXIT", 0..=0;
+    ToggleJetway => "TOGGLE_JETWAY", 0..=0;
+    RequestFuelKey => "REQUEST_FUEL_KEY", 0..=0;
+}
+
+impl KeyEventId {
+    /// Look up an event by name (case-insensitive, optional `K:` prefix).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = strip_prefix(name);
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|id| id.name().eq_ignore_ascii_case(name))
+    }
+
+    pub const fn min_params(self) -> usize {
+        self.arity().0
+    }
+
+    pub const fn max_params(self) -> usize {
+        self.arity().1
+    }
+
+    /// `Err` if `params` has fewer or more values than the event takes.
+    pub fn check_params(self, params: &[u32]) -> Result<(), ArityError> {
+        check_params(self.arity(), params)
+    }
+
+    /// Fire with [`KeyEventId::min_params`] to [`KeyEventId::max_params`]
+    /// parameters, see [`send`].
+    #[inline]
+    pub fn send(self, params: &[u32]) -> bool {
+        send(self, params)
+    }
+}
+
+impl std::fmt::Display for KeyEventId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<KeyEventId> for KeyEvent {
+    fn from(id: KeyEventId) -> Self {
+        KeyEvent::new(id.name()).expect("catalog names contain no NUL")
+    }
+}
+
+/// Fire `event` with [`KeyEventId::min_params`] to [`KeyEventId::max_params`]
+/// parameters. Returns `false` if the parameter count is wrong or the event
+/// could not be sent.
+///
+/// ```no_run
+/// use msfs::events::key::{KeyEventId, send};
+///
+/// send(KeyEventId::ToggleMasterBattery, &[]);
+/// send(KeyEventId::HeadingBugSet, &[270]);
+/// ```
+pub fn send(event: KeyEventId, params: &[u32]) -> bool {
+    if event.check_params(params).is_err() {
+        return false;
+    }
+    record_fired(event.name(), params);
+    code(event.name(), params).is_some_and(|code| calc::run(&code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_arity_bounds() {
+        let id = KeyEventId::HeadingBugSet;
+        assert_eq!(id.arity(), (1, 2));
+        assert_eq!(
+            id.check_params(&[]),
+            Err(ArityError {
+                min: 1,
+                max: 2,
+                got: 0
+            })
+        );
+        assert!(id.check_params(&[270]).is_ok());
+        assert!(id.check_params(&[270, 1]).is_ok());
+        assert!(id.check_params(&[270, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn unknown_events_take_default_arity() {
+        let event = KeyEvent::new("SOME_CUSTOM_EVENT").unwrap();
+        assert_eq!(
+            (event.min_params(), event.max_params()),
+            DEFAULT_KEY_EVENT_ARITY
+        );
+        assert!(event.check_params(&[]).is_ok());
+        assert!(event.check_params(&[1]).is_ok());
+        assert!(event.check_params(&[1, 2]).is_err());
+
+        let event = event.with_arity(2, 9);
+        assert_eq!(event.max_params(), MAX_KEY_EVENT_PARAMS);
+        assert!(event.check_params(&[1]).is_err());
+    }
+
+    #[test]
+    fn looks_up_names_with_prefix() {
+        let id = Some(KeyEventId::ToggleMasterBattery);
+        assert_eq!(KeyEventId::from_name("TOGGLE_MASTER_BATTERY"), id);
+        assert_eq!(KeyEventId::from_name("K:TOGGLE_MASTER_BATTERY"), id);
+        assert_eq!(KeyEventId::from_name("k:toggle_master_battery"), id);
+        assert_eq!(KeyEventId::from_name("K:"), None);
+        assert_eq!(KeyEvent::new("k:STROBES_ON").unwrap().name(), "STROBES_ON");
+    }
+
+    #[test]
+    fn builds_calculator_code() {
+        assert_eq!(code("STROBES_ON", &[]).unwrap(), "(>K:STROBES_ON)");
+        assert_eq!(
+            code("HEADING_BUG_SET", &[270]).unwrap(),
+            "270 (>K:HEADING_BUG_SET)"
+        );
+        assert_eq!(
+            code("HEADING_BUG_SET", &[270, 1]).unwrap(),
+            "270 1 (>K:2:HEADING_BUG_SET)"
+        );
+        assert_eq!(code("X", &[0; MAX_KEY_EVENT_PARAMS + 1]), None);
+    }
+}
@@ -0,0 +1,25 @@
+//! Sim event helpers.
+//!
+//! Key (`K:`), H (`H:`) and input (`B:`) events are fired through the gauge
+//! calculator, the same path XML gauges use, so any event the sim knows by name can
+//! be sent. Masking key events before the sim sees them ([`intercept`],
+//! [`notification`]) and reading controllers ([`input_device`]) go through
+//! SimConnect instead.
+
+pub mod h_event;
+pub mod input_device;
+pub mod input_event;
+pub mod intercept;
+pub mod key;
+pub mod notification;
+pub mod recorder;
+
+pub use h_event::{HEvent, fire_h_event};
+pub use input_event::{
+    InputEvent, InputEventEnumeration, InputEventInfo, InputEventSubscription, InputEventType,
+};
+pub use intercept::{Intercepted, Interceptor};
+pub use key::{
+    ArityError, DEFAULT_KEY_EVENT_ARITY, KeyEvent, KeyEventId, MAX_KEY_EVENT_PARAMS, fire_key_event,
+};
+pub use notification::{NotificationGroup, Priority};
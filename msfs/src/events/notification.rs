@@ -0,0 +1,355 @@
+//! Prioritized key event notification groups.
+//!
+//! Every [`NotificationGroup`] in a module shares one SimConnect client but
+//! has its own [`Priority`]. The sim hands each event to the groups from the
+//! highest priority down, and a group that masks an event stops it there
+//! unless its handler [forwards](super::Intercepted::forward) it, so several
+//! systems can handle the same event without racing each other:
+//!
+//! ```no_run
+//! use msfs::events::notification::{NotificationGroup, Priority};
+//!
+//! // A failure system sees GEAR_TOGGLE first and may swallow it...
+//! let mut failures = NotificationGroup::new(Priority::HIGHEST_MASKABLE).unwrap();
+//! failures.intercept("GEAR_TOGGLE", |event| {
+//!     let gear_failed = false;
+//!     if !gear_failed {
+//!         event.forward();
+//!     }
+//! });
+//!
+//! // ...and the gear system only gets the toggles it let through.
+//! let mut gear = NotificationGroup::new(Priority::HIGHEST_MASKABLE.lower(1)).unwrap();
+//! gear.intercept("GEAR_TOGGLE", |event| {
+//!     println!("gear handle moved");
+//!     event.forward();
+//! });
+//! ```
+//!
+//! The export macros call [`update`] at the start of each update, which runs
+//! the handlers of everything received since.
+
+use crate::events::recorder::{self, Direction, EventKind};
+use crate::events::{Intercepted, MAX_KEY_EVENT_PARAMS, key};
+use crate::simconnect::{self, Inbox, read_u32, with_client};
+use crate::sys::{
+    BOOL, DWORD, SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_EX1, SimConnect_AddClientEventToNotificationGroup,
+    SimConnect_ClearNotificationGroup, SimConnect_MapClientEventToSimEvent,
+    SimConnect_SetNotificationGroupPriority, SimConnect_TransmitClientEvent_EX1,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::CString,
+    rc::{Rc, Weak},
+};
+
+// `static const`s in SimConnect.h, which bindgen doesn't emit.
+const EVENT_FLAG_GROUPID_IS_PRIORITY: DWORD = 0x10;
+const OBJECT_ID_USER: DWORD = 0;
+
+// `SIMCONNECT_RECV_EVENT` is packed: dwSize dwVersion dwID uGroupID uEventID
+// dwData. `SIMCONNECT_RECV_EVENT_EX1` has dwData0..dwData4 in its place.
+const GROUP: usize = 12;
+const EVENT: usize = 16;
+const DATA: usize = 20;
+
+/// Where a group sits in the order events are handed out. Smaller values
+/// come first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Priority(u32);
+
+impl Priority {
+    /// Sees events first, but can't mask them.
+    pub const HIGHEST: Priority = Priority(1);
+    /// The first priority that can mask events.
+    pub const HIGHEST_MASKABLE: Priority = Priority(10_000_000);
+    pub const STANDARD: Priority = Priority(1_900_000_000);
+    pub const DEFAULT: Priority = Priority(2_000_000_000);
+    pub const LOWEST: Priority = Priority(4_000_000_000);
+
+    pub const fn new(value: u32) -> Self {
+        Priority(value)
+    }
+
+    pub const fn value(self) -> u32 {
+        self.0
+    }
+
+    /// `by` steps after `self`.
+    pub const fn lower(self, by: u32) -> Self {
+        Priority(self.0.saturating_add(by))
+    }
+
+    /// Whether groups at this priority can hold events back.
+    pub const fn can_mask(self) -> bool {
+        self.0 >= Self::HIGHEST_MASKABLE.0
+    }
+}
+
+type Callback = Box<dyn FnMut(&mut Intercepted)>;
+
+struct Handler {
+    name: String,
+    masked: bool,
+    // Taken while it runs.
+    callback: Option<Callback>,
+}
+
+struct GroupState {
+    priority: Priority,
+    // By client event id.
+    handlers: HashMap<DWORD, Handler>,
+}
+
+struct Received {
+    group: DWORD,
+    event: DWORD,
+    params: [u32; MAX_KEY_EVENT_PARAMS],
+}
+
+struct Groups {
+    inbox: Inbox,
+    groups: HashMap<DWORD, Weak<RefCell<GroupState>>>,
+}
+
+thread_local! {
+    static GROUPS: RefCell<Option<Groups>> = const { RefCell::new(None) };
+}
+
+/// A set of key event handlers at one [`Priority`].
+///
+/// Handlers run from [`update`]. Dropping the group removes its handlers.
+pub struct NotificationGroup {
+    id: DWORD,
+    state: Rc<RefCell<GroupState>>,
+    names: Vec<String>,
+}
+
+impl NotificationGroup {
+    /// A new, empty group. `None` if SimConnect is unavailable.
+    pub fn new(priority: Priority) -> Option<Self> {
+        let state = Rc::new(RefCell::new(GroupState {
+            priority,
+            handlers: HashMap::new(),
+        }));
+        GROUPS.with(|g| {
+            let mut g = g.borrow_mut();
+            if g.is_none() {
+                *g = Some(Groups {
+                    inbox: Inbox::new()?,
+                    groups: HashMap::new(),
+                });
+            }
+            Some(())
+        })?;
+        let id = with_client(|client| {
+            let id = client.next_id();
+            unsafe {
+                SimConnect_SetNotificationGroupPriority(
+                    client.handle(),
+                    id,
+                    priority.value() as DWORD,
+                );
+            }
+            id
+        })?;
+        GROUPS.with(|g| {
+            if let Some(g) = g.borrow_mut().as_mut() {
+                g.inbox.claim(id);
+                g.groups.insert(id, Rc::downgrade(&state));
+            }
+        });
+        Some(Self {
+            id,
+            state,
+            names: Vec::new(),
+        })
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.state.borrow().priority
+    }
+
+    pub fn set_priority(&mut self, priority: Priority) -> bool {
+        self.state.borrow_mut().priority = priority;
+        with_client(|client| unsafe {
+            SimConnect_SetNotificationGroupPriority(
+                client.handle(),
+                self.id,
+                priority.value() as DWORD,
+            ) >= 0
+        })
+        .unwrap_or(false)
+    }
+
+    /// Hold key event `name` (a leading `K:` is optional) back from the sim
+    /// and lower-priority groups unless `callback`
+    /// [forwards](Intercepted::forward) it. Needs a priority that
+    /// [can mask](Priority::can_mask); above that, events are only observed.
+    pub fn intercept(
+        &mut self,
+        name: &str,
+        callback: impl FnMut(&mut Intercepted) + 'static,
+    ) -> bool {
+        self.add(name, true, Box::new(callback))
+    }
+
+    /// Listen to key event `name` without holding it back.
+    pub fn observe(
+        &mut self,
+        name: &str,
+        callback: impl FnMut(&mut Intercepted) + 'static,
+    ) -> bool {
+        self.add(name, false, Box::new(callback))
+    }
+
+    fn add(&mut self, name: &str, masked: bool, callback: Callback) -> bool {
+        let name = key::strip_prefix(name);
+        let Ok(sim_name) = CString::new(name) else {
+            return false;
+        };
+        let masked = masked && self.priority().can_mask();
+        // Each group maps its own client event, so groups never share handlers.
+        let event = with_client(|client| {
+            let event = client.next_id();
+            let ok = unsafe {
+                SimConnect_MapClientEventToSimEvent(client.handle(), event, sim_name.as_ptr()) >= 0
+                    && SimConnect_AddClientEventToNotificationGroup(
+                        client.handle(),
+                        self.id,
+                        event,
+                        masked as BOOL,
+                    ) >= 0
+            };
+            ok.then_some(event)
+        })
+        .flatten();
+        let Some(event) = event else {
+            return false;
+        };
+        self.state.borrow_mut().handlers.insert(
+            event,
+            Handler {
+                name: name.to_string(),
+                masked,
+                callback: Some(callback),
+            },
+        );
+        self.names.push(name.to_string());
+        true
+    }
+
+    /// Names of the handled events, in the order they were added.
+    pub fn events(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+}
+
+impl Drop for NotificationGroup {
+    fn drop(&mut self) {
+        let _ = GROUPS.try_with(|g| {
+            if let Ok(mut g) = g.try_borrow_mut()
+                && let Some(g) = g.as_mut()
+            {
+                g.inbox.release(self.id);
+                g.groups.remove(&self.id);
+            }
+        });
+        simconnect::try_with_client(|client| unsafe {
+            SimConnect_ClearNotificationGroup(client.handle(), self.id);
+        });
+    }
+}
+
+/// Receive pending key events and run their groups' handlers, then send
+/// forwarded events on below the forwarding group. Called by the export
+/// macros at the start of each update; does nothing until a group exists.
+pub fn update() {
+    let received = GROUPS.with(|g| {
+        let g = g.borrow();
+        let Some(g) = g.as_ref() else {
+            return Vec::new();
+        };
+        g.inbox
+            .receive()
+            .iter()
+            .filter_map(|bytes| parse(bytes))
+            .filter_map(|r| Some((g.groups.get(&r.group)?.upgrade()?, r)))
+            .collect::<Vec<_>>()
+    });
+    // Nothing is borrowed while handlers run, so they may add or drop groups.
+    for (group, r) in received {
+        let (name, masked, callback) = {
+            let mut g = group.borrow_mut();
+            let Some(handler) = g.handlers.get_mut(&r.event) else {
+                continue;
+            };
+            (
+                handler.name.clone(),
+                handler.masked,
+                handler.callback.take(),
+            )
+        };
+        let Some(mut callback) = callback else {
+            continue;
+        };
+        let values = r.params.map(|p| p as f64);
+        recorder::record(Direction::Received, EventKind::Key, &name, &values);
+        let mut intercepted = Intercepted::new(r.params, masked);
+        callback(&mut intercepted);
+        let priority = {
+            let mut g = group.borrow_mut();
+            if let Some(handler) = g.handlers.get_mut(&r.event) {
+                handler.callback = Some(callback);
+            }
+            g.priority
+        };
+        if masked && intercepted.is_forwarded() {
+            let values = intercepted.params.map(|p| p as f64);
+            recorder::record(Direction::Fired, EventKind::Key, &name, &values);
+            transmit(r.event, priority, intercepted.params);
+        }
+    }
+}
+
+fn transmit(event: DWORD, below: Priority, params: [u32; MAX_KEY_EVENT_PARAMS]) {
+    with_client(|client| {
+        let [p0, p1, p2, p3, p4] = params;
+        unsafe {
+            SimConnect_TransmitClientEvent_EX1(
+                client.handle(),
+                OBJECT_ID_USER,
+                event,
+                below.value() as DWORD,
+                EVENT_FLAG_GROUPID_IS_PRIORITY,
+                p0 as DWORD,
+                p1 as DWORD,
+                p2 as DWORD,
+                p3 as DWORD,
+                p4 as DWORD,
+            );
+        }
+    });
+}
+
+fn parse(bytes: &[u8]) -> Option<Received> {
+    let id = simconnect::recv_id(bytes)?;
+    let count = if id as i32 == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as i32 {
+        1
+    } else if id as i32 == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_EX1 as i32 {
+        MAX_KEY_EVENT_PARAMS
+    } else {
+        return None;
+    };
+    let mut params = [0; MAX_KEY_EVENT_PARAMS];
+    for (i, param) in params.iter_mut().enumerate().take(count) {
+        *param = read_u32(bytes, DATA + i * 4).unwrap_or(0);
+    }
+    Some(Received {
+        group: read_u32(bytes, GROUP)? as DWORD,
+        event: read_u32(bytes, EVENT)? as DWORD,
+        params,
+    })
+}
@@ -0,0 +1,386 @@
+//! Event recording and replay.
+//!
+//! While recording, every key, H and input event the module fires, and every
+//! key or input event it receives through [`NotificationGroup`] handlers or
+//! [`InputEvent::subscribe`] listeners, is kept with its time. Save the
+//! recording to `\work` from the sim:
+//!
+//! ```no_run
+//! use msfs::events::recorder;
+//!
+//! // in init():
+//! recorder::start();
+//!
+//! // when done, e.g. in kill():
+//! recorder::save("\\work/events.log")?;
+//! # Ok::<(), msfs::io::IoError>(())
+//! ```
+//!
+//! and replay it against a [`System`] in the native test harness: received
+//! events are handed back to the system at their recorded time, and the
+//! events it fires in response are compared to what it fired in the sim.
+//!
+//! ```no_run
+//! # fn run<S: msfs::modules::System>(system: &mut S, ctx: &msfs::context::Context) {
+//! use msfs::events::recorder::Replay;
+//!
+//! let text = std::fs::read_to_string("events.log").unwrap();
+//! let replay = Replay::parse(&text);
+//! let fired = replay.run(system, ctx, 1.0 / 30.0, |system, event| {
+//!     // route `event` to the handler the system registered for it
+//! });
+//! assert_eq!(replay.first_mismatch(&fired), None);
+//! # }
+//! ```
+//!
+//! [`NotificationGroup`]: super::NotificationGroup
+//! [`InputEvent::subscribe`]: super::InputEvent::subscribe
+
+use crate::context::Context;
+use crate::io::{IoResult, fs};
+use crate::modules::System;
+use std::{cell::RefCell, time::Instant};
+
+/// Whether the module sent the event or got it from the sim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Fired,
+    Received,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// `K:`
+    Key,
+    /// `H:`
+    H,
+    /// `B:`; the name includes the binding, e.g. `LIGHTING_LANDING_1_Toggle`.
+    Input,
+}
+
+impl EventKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            EventKind::Key => "K",
+            EventKind::H => "H",
+            EventKind::Input => "B",
+        }
+    }
+}
+
+/// One recorded event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// Seconds since [`start`].
+    pub time: f64,
+    pub direction: Direction,
+    pub kind: EventKind,
+    pub name: String,
+    /// Key event parameters, or the input event value.
+    pub values: Vec<f64>,
+}
+
+impl RecordedEvent {
+    /// Whether `other` is the same event, ignoring when it happened.
+    pub fn same_event(&self, other: &RecordedEvent) -> bool {
+        self.direction == other.direction
+            && self.kind == other.kind
+            && self.name == other.name
+            && self.values == other.values
+    }
+
+    /// One line of the recording format:
+    /// `time <tab> fired|received <tab> K|H|B <tab> name <tab> values`, with
+    /// values separated by commas.
+    pub fn to_line(&self) -> String {
+        let direction = match self.direction {
+            Direction::Fired => "fired",
+            Direction::Received => "received",
+        };
+        let values: Vec<String> = self.values.iter().map(f64::to_string).collect();
+        format!(
+            "{:.6}\t{direction}\t{}\t{}\t{}",
+            self.time,
+            self.kind.prefix(),
+            self.name,
+            values.join(",")
+        )
+    }
+
+    /// Parse a line written by [`RecordedEvent::to_line`].
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let time = fields.next()?.trim().parse().ok()?;
+        let direction = match fields.next()? {
+            "fired" => Direction::Fired,
+            "received" => Direction::Received,
+            _ => return None,
+        };
+        let kind = match fields.next()? {
+            "K" => EventKind::Key,
+            "H" => EventKind::H,
+            "B" => EventKind::Input,
+            _ => return None,
+        };
+        let name = fields.next()?.to_string();
+        let values = match fields.next().map(str::trim) {
+            None | Some("") => Vec::new(),
+            Some(values) => values
+                .split(',')
+                .map(|v| v.parse().ok())
+                .collect::<Option<_>>()?,
+        };
+        Some(Self {
+            time,
+            direction,
+            kind,
+            name,
+            values,
+        })
+    }
+}
+
+struct Recording {
+    start: Instant,
+    events: Vec<RecordedEvent>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// Start a new recording, dropping any previous one.
+pub fn start() {
+    RECORDING.with(|r| {
+        *r.borrow_mut() = Some(Recording {
+            start: Instant::now(),
+            events: Vec::new(),
+        })
+    });
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.with(|r| r.borrow().is_some())
+}
+
+/// Stop recording and return what was recorded.
+pub fn stop() -> Vec<RecordedEvent> {
+    RECORDING
+        .with(|r| r.borrow_mut().take())
+        .map_or_else(Vec::new, |r| r.events)
+}
+
+/// A copy of the events recorded so far.
+pub fn events() -> Vec<RecordedEvent> {
+    RECORDING.with(|r| {
+        r.borrow()
+            .as_ref()
+            .map_or_else(Vec::new, |r| r.events.clone())
+    })
+}
+
+/// Write the events recorded so far to `path`, one [line](RecordedEvent::to_line)
+/// each. Recording continues.
+pub fn save(path: &str) -> IoResult<fs::WriteRequest> {
+    fs::write(path, to_text(&events()).as_bytes())
+}
+
+/// The recording format for `events`.
+pub fn to_text(events: &[RecordedEvent]) -> String {
+    let mut text = String::new();
+    for event in events {
+        text.push_str(&event.to_line());
+        text.push('\n');
+    }
+    text
+}
+
+/// Called where events are fired or received; does nothing unless recording.
+pub(crate) fn record(direction: Direction, kind: EventKind, name: &str, values: &[f64]) {
+    RECORDING.with(|r| {
+        if let Some(recording) = r.borrow_mut().as_mut() {
+            recording.events.push(RecordedEvent {
+                time: recording.start.elapsed().as_secs_f64(),
+                direction,
+                kind,
+                name: name.to_string(),
+                values: values.to_vec(),
+            });
+        }
+    });
+}
+
+/// Where a replay's fired events first differ from the recording, see
+/// [`Replay::first_mismatch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch<'a> {
+    /// Position among the fired events.
+    pub index: usize,
+    /// The recorded event, `None` if the replay fired more.
+    pub expected: Option<&'a RecordedEvent>,
+    /// The replayed event, `None` if the replay fired fewer.
+    pub actual: Option<&'a RecordedEvent>,
+}
+
+/// A recording being played back against a [`System`].
+pub struct Replay {
+    events: Vec<RecordedEvent>,
+}
+
+impl Replay {
+    pub fn new(mut events: Vec<RecordedEvent>) -> Self {
+        events.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { events }
+    }
+
+    /// Parse the recording format; lines that don't parse are skipped.
+    pub fn parse(text: &str) -> Self {
+        Self::new(text.lines().filter_map(RecordedEvent::parse_line).collect())
+    }
+
+    /// The recorded events the module received, in order.
+    pub fn received(&self) -> impl Iterator<Item = &RecordedEvent> {
+        self.events
+            .iter()
+            .filter(|e| e.direction == Direction::Received)
+    }
+
+    /// The recorded events the module fired, in order.
+    pub fn fired(&self) -> impl Iterator<Item = &RecordedEvent> {
+        self.events
+            .iter()
+            .filter(|e| e.direction == Direction::Fired)
+    }
+
+    /// Drive `system` through the recording in steps of `dt` seconds. Before
+    /// each update, every received event that is due is passed to `deliver`
+    /// along with the system. Returns the events the system fired, recorded
+    /// as by [`start`].
+    pub fn run<S: System>(
+        &self,
+        system: &mut S,
+        ctx: &Context,
+        dt: f32,
+        mut deliver: impl FnMut(&mut S, &RecordedEvent),
+    ) -> Vec<RecordedEvent> {
+        let end = self.events.last().map_or(0.0, |e| e.time);
+        let previous = RECORDING.with(|r| r.borrow_mut().take());
+        start();
+        let mut received = self.received().peekable();
+        let mut time = 0.0;
+        loop {
+            while let Some(event) = received.next_if(|e| e.time <= time) {
+                deliver(system, event);
+            }
+            system.update(ctx, dt);
+            if time > end {
+                break;
+            }
+            time += dt as f64;
+        }
+        let fired = stop()
+            .into_iter()
+            .filter(|e| e.direction == Direction::Fired)
+            .collect();
+        RECORDING.with(|r| *r.borrow_mut() = previous);
+        fired
+    }
+
+    /// The first difference between the recorded fired events and `fired`,
+    /// compared in order and ignoring times.
+    pub fn first_mismatch<'a>(&'a self, fired: &'a [RecordedEvent]) -> Option<Mismatch<'a>> {
+        let mut expected = self.fired();
+        let mut actual = fired.iter();
+        let mut index = 0;
+        loop {
+            match (expected.next(), actual.next()) {
+                (None, None) => return None,
+                (Some(e), Some(a)) if e.same_event(a) => index += 1,
+                (expected, actual) => {
+                    return Some(Mismatch {
+                        index,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(time: f64, direction: Direction, name: &str, values: &[f64]) -> RecordedEvent {
+        RecordedEvent {
+            time,
+            direction,
+            kind: EventKind::Key,
+            name: name.to_string(),
+            values: values.to_vec(),
+        }
+    }
+
+    #[test]
+    fn line_round_trip() {
+        let events = [
+            event(1.25, Direction::Fired, "AP_MASTER", &[]),
+            event(
+                0.0,
+                Direction::Received,
+                "HEADING_BUG_SET",
+                &[270.0, -1.5, 1e300],
+            ),
+            RecordedEvent {
+                kind: EventKind::Input,
+                ..event(3.0, Direction::Fired, "LIGHTING_LANDING_1_Toggle", &[1.0])
+            },
+            RecordedEvent {
+                kind: EventKind::H,
+                ..event(4.5, Direction::Fired, "A320_Neo_CDU_1_BTN_1", &[])
+            },
+        ];
+        for e in &events {
+            assert_eq!(RecordedEvent::parse_line(&e.to_line()).as_ref(), Some(e));
+        }
+        assert_eq!(
+            event(0.0, Direction::Received, "HEADING_BUG_SET", &[270.0, -1.5]).to_line(),
+            "0.000000\treceived\tK\tHEADING_BUG_SET\t270,-1.5"
+        );
+    }
+
+    #[test]
+    fn parse_line_rejects_malformed() {
+        assert!(RecordedEvent::parse_line("").is_none());
+        assert!(RecordedEvent::parse_line("x\tfired\tK\tA").is_none());
+        assert!(RecordedEvent::parse_line("1\tsent\tK\tA").is_none());
+        assert!(RecordedEvent::parse_line("1\tfired\tL\tA").is_none());
+        assert!(RecordedEvent::parse_line("1\tfired\tK").is_none());
+        assert!(RecordedEvent::parse_line("1\tfired\tK\tA\t1,x").is_none());
+
+        let e = RecordedEvent::parse_line(" 2.5 \tfired\tK\tA\t ").unwrap();
+        assert_eq!((e.time, e.values.len()), (2.5, 0));
+    }
+
+    #[test]
+    fn replay_parse_and_mismatch() {
+        let text = "2\tfired\tK\tB\t\nnot a line\r\n1\tfired\tK\tA\t1\r\n0.5\treceived\tK\tX\t\n";
+        let replay = Replay::parse(text);
+        let names: Vec<_> = replay.fired().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["A", "B"]);
+        assert_eq!(replay.received().count(), 1);
+
+        let same = [
+            event(9.0, Direction::Fired, "A", &[1.0]),
+            event(9.0, Direction::Fired, "B", &[]),
+        ];
+        assert_eq!(replay.first_mismatch(&same), None);
+
+        let fewer = &same[..1];
+        let mismatch = replay.first_mismatch(fewer).unwrap();
+        assert_eq!(mismatch.index, 1);
+        assert_eq!(mismatch.expected.map(|e| e.name.as_str()), Some("B"));
+        assert_eq!(mismatch.actual, None);
+    }
+}
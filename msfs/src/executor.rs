@@ -0,0 +1,157 @@
+//! A single-threaded executor for `async` code, pumped from `update()`.
+//!
+//! [`spawn`] a future and the export macros [`poll`] every task that was
+//! woken at the start of each update, so async code runs on the sim thread
+//! like any callback. Futures from the crate, e.g.
+//! [`http_request_async`](crate::network::http_request_async), wake their
+//! task when the sim reports progress; [`next_update`] waits one update.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::executor;
+//! use msfs::network::{HttpParams, Method, http_request_async};
+//!
+//! executor::spawn(async {
+//!     let resp = http_request_async(Method::Get, "https://example.com/airports.json", HttpParams::default()).await;
+//!     match resp {
+//!         Ok(resp) if resp.is_success() => println!("{} bytes", resp.data.len()),
+//!         _ => println!("download failed"),
+//!     }
+//! });
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+type Task = Pin<Box<dyn Future<Output = ()> + 'static>>;
+
+thread_local! {
+    static TASKS: RefCell<HashMap<u64, Task>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u64> = const { Cell::new(1) };
+    static RUNNING: Cell<bool> = const { Cell::new(false) };
+    static NEXT_UPDATE: RefCell<Vec<Waker>> = const { RefCell::new(Vec::new()) };
+}
+
+// Wakers must be `Send + Sync`; a module only ever has the sim thread.
+static READY: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+struct TaskWaker {
+    id: u64,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if let Ok(mut ready) = READY.lock() {
+            ready.push(self.id);
+        }
+    }
+}
+
+/// A task started with [`spawn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskHandle {
+    id: u64,
+}
+
+impl TaskHandle {
+    /// `true` once the future completed or the task was aborted.
+    pub fn is_finished(self) -> bool {
+        TASKS.with(|t| !t.borrow().contains_key(&self.id))
+    }
+
+    /// Drop the future without polling it again. Does nothing while the task
+    /// itself is running.
+    pub fn abort(self) {
+        TASKS.with(|t| t.borrow_mut().remove(&self.id));
+    }
+}
+
+/// Run `future` on the executor. It is first polled by the next [`poll`], or
+/// the running one when spawned from a task.
+pub fn spawn(future: impl Future<Output = ()> + 'static) -> TaskHandle {
+    let id = NEXT_ID.with(|n| {
+        let id = n.get();
+        n.set(id + 1);
+        id
+    });
+    TASKS.with(|t| t.borrow_mut().insert(id, Box::pin(future)));
+    if let Ok(mut ready) = READY.lock() {
+        ready.push(id);
+    }
+    TaskHandle { id }
+}
+
+/// Number of tasks not yet finished.
+pub fn pending() -> usize {
+    TASKS.with(|t| t.borrow().len())
+}
+
+/// Poll every woken task until none is ready. Called by the export macros at
+/// the start of each update.
+pub fn poll() {
+    if RUNNING.with(|r| r.replace(true)) {
+        return;
+    }
+    for waker in NEXT_UPDATE.with(|w| std::mem::take(&mut *w.borrow_mut())) {
+        waker.wake();
+    }
+    loop {
+        let mut ready = match READY.lock() {
+            Ok(mut ready) => std::mem::take(&mut *ready),
+            Err(_) => break,
+        };
+        if ready.is_empty() {
+            break;
+        }
+        ready.sort_unstable();
+        ready.dedup();
+        for id in ready {
+            // Out of the map while it runs, so it can spawn or abort tasks.
+            let Some(mut task) = TASKS.with(|t| t.borrow_mut().remove(&id)) else {
+                continue;
+            };
+            let waker = Waker::from(Arc::new(TaskWaker { id }));
+            if task
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_pending()
+            {
+                TASKS.with(|t| t.borrow_mut().insert(id, task));
+            }
+        }
+    }
+    RUNNING.with(|r| r.set(false));
+}
+
+/// Wait until the next [`poll`], i.e. the next update.
+pub fn next_update() -> NextUpdate {
+    NextUpdate { waited: false }
+}
+
+/// Future returned by [`next_update`].
+pub struct NextUpdate {
+    waited: bool,
+}
+
+impl Future for NextUpdate {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.waited {
+            return Poll::Ready(());
+        }
+        self.waited = true;
+        NEXT_UPDATE.with(|w| w.borrow_mut().push(cx.waker().clone()));
+        Poll::Pending
+    }
+}
@@ -7,8 +7,23 @@ use crate::{
 #[macro_export]
 macro_rules! export_system {
     (name=$name:ident, state=$state:ty, ctor=$ctor:expr $(,)?) => {
+        $crate::export_system_abi!(
+            abi = $crate::abi::Fs2024,
+            name = $name,
+            state = $state,
+            ctor = $ctor
+        );
+    };
+}
+
+/// `export_system!` against any [`Abi`](crate::abi::Abi), e.g. a native test harness's mock types.
+#[macro_export]
+macro_rules! export_system_abi {
+    (abi=$abi:ty, name=$name:ident, state=$state:ty, ctor=$ctor:expr $(,)?) => {
         $crate::__paste::paste! {
             static mut [<$name _SYSTEM>]: ::core::option::Option<$state> = None;
+            static mut [<$name _SYSTEM_INITIALIZED>]: bool = false;
+            static mut [<$name _SYSTEM_PAUSED>]: bool = false;
 
             #[inline(always)]
             unsafe fn [<$name _with>]<R>(f: impl FnOnce(&mut $state) -> R) -> Option<R> {
@@ -17,39 +32,83 @@ macro_rules! export_system {
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _system_init>](
-                ctx: $crate::sys::FsContext,
-                p_install: *mut $crate::sys::sSystemInstallData,
+                ctx: <$abi as $crate::abi::Abi>::Context,
+                p_install: *mut <$abi as $crate::abi::Abi>::SystemInstall,
             ) -> bool {
                 unsafe { [<$name _SYSTEM>] = Some($ctor); }
                 unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    let install = &mut *p_install;
-                    [<$name _with>](|s| <$state as $crate::modules::System>::init(s, &ctx, install))
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    <$abi as $crate::abi::Abi>::with_system_install(p_install, |install| {
+                        [<$name _with>](|s| {
+                            <$state as $crate::modules::System>::panel_service(s, &ctx, $crate::modules::PanelService::PreInstall);
+                            let ok = <$state as $crate::modules::System>::init(s, &ctx, install);
+                            if ok {
+                                <$state as $crate::modules::System>::panel_service(s, &ctx, $crate::modules::PanelService::PostInstall);
+                            }
+                            ok
+                        })
                         .unwrap_or(false)
+                    })
                 }
             }
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _system_update>](
-                ctx: $crate::sys::FsContext,
+                ctx: <$abi as $crate::abi::Abi>::Context,
                 dt: f32,
             ) -> bool {
-                unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    [<$name _with>](|s| <$state as $crate::modules::System>::update(s, &ctx, dt))
-                        .unwrap_or(false)
-                }
+                $crate::vars::frame::begin();
+                $crate::events::input_event::update();
+                $crate::events::notification::update();
+                $crate::comm_bus::poll();
+                $crate::network::update();
+                $crate::executor::poll();
+                let ok = unsafe {
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    [<$name _with>](|s| {
+                        if ![<$name _SYSTEM_INITIALIZED>] {
+                            [<$name _SYSTEM_INITIALIZED>] = true;
+                            <$state as $crate::modules::System>::panel_service(s, &ctx, $crate::modules::PanelService::PostInitialize);
+                        }
+                        if <$state as $crate::modules::System>::PAUSE_SERVICES {
+                            let paused = $crate::modules::sim_paused();
+                            if paused != [<$name _SYSTEM_PAUSED>] {
+                                [<$name _SYSTEM_PAUSED>] = paused;
+                                let service = if paused {
+                                    $crate::modules::PanelService::Paused
+                                } else {
+                                    $crate::modules::PanelService::Resumed
+                                };
+                                <$state as $crate::modules::System>::panel_service(s, &ctx, service);
+                            }
+                        }
+                        <$state as $crate::modules::System>::panel_service(s, &ctx, $crate::modules::PanelService::PreUpdate);
+                        let ok = <$state as $crate::modules::System>::update(s, &ctx, dt);
+                        <$state as $crate::modules::System>::panel_service(s, &ctx, $crate::modules::PanelService::PostUpdate);
+                        ok
+                    })
+                    .unwrap_or(false)
+                };
+                $crate::vars::frame::end();
+                ok
             }
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _system_kill>](
-                ctx: $crate::sys::FsContext,
+                ctx: <$abi as $crate::abi::Abi>::Context,
             ) -> bool {
                 unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    let ok = [<$name _with>](|s| <$state as $crate::modules::System>::kill(s, &ctx))
-                        .unwrap_or(false);
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    let ok = [<$name _with>](|s| {
+                        <$state as $crate::modules::System>::panel_service(s, &ctx, $crate::modules::PanelService::PreKill);
+                        let ok = <$state as $crate::modules::System>::kill(s, &ctx);
+                        <$state as $crate::modules::System>::panel_service(s, &ctx, $crate::modules::PanelService::PostKill);
+                        ok
+                    })
+                    .unwrap_or(false);
                     [<$name _SYSTEM>] = None;
+                    [<$name _SYSTEM_INITIALIZED>] = false;
+                    [<$name _SYSTEM_PAUSED>] = false;
                     ok
                 }
             }
@@ -60,8 +119,23 @@ macro_rules! export_system {
 #[macro_export]
 macro_rules! export_gauge {
     (name=$name:ident, state=$state:ty, ctor=$ctor:expr $(,)?) => {
+        $crate::export_gauge_abi!(
+            abi = $crate::abi::Fs2024,
+            name = $name,
+            state = $state,
+            ctor = $ctor
+        );
+    };
+}
+
+/// `export_gauge!` against any [`Abi`](crate::abi::Abi), e.g. a native test harness's mock types.
+#[macro_export]
+macro_rules! export_gauge_abi {
+    (abi=$abi:ty, name=$name:ident, state=$state:ty, ctor=$ctor:expr $(,)?) => {
         $crate::__paste::paste! {
             static mut [<$name _GAUGE>]: ::core::option::Option<$state> = None;
+            static mut [<$name _GAUGE_INITIALIZED>]: bool = false;
+            static mut [<$name _GAUGE_PAUSED>]: bool = false;
 
             #[inline(always)]
             unsafe fn [<$name _with>]<R>(f: impl FnOnce(&mut $state) -> R) -> Option<R> {
@@ -70,67 +144,131 @@ macro_rules! export_gauge {
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _gauge_init>](
-                ctx: $crate::sys::FsContext,
-                p_install: *mut $crate::sys::sGaugeInstallData,
+                ctx: <$abi as $crate::abi::Abi>::Context,
+                p_install: *mut <$abi as $crate::abi::Abi>::GaugeInstall,
             ) -> bool {
                 unsafe { [<$name _GAUGE>] = Some($ctor); }
                 unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    let install = &mut *p_install;
-                    [<$name _with>](|g| <$state as $crate::modules::Gauge>::init(g, &ctx, install))
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    <$abi as $crate::abi::Abi>::with_gauge_install(p_install, |install| {
+                        [<$name _with>](|g| {
+                            <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PreInstall);
+                            let ok = <$state as $crate::modules::Gauge>::init(g, &ctx, install);
+                            if ok {
+                                <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PostInstall);
+                            }
+                            ok
+                        })
                         .unwrap_or(false)
+                    })
                 }
             }
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _gauge_update>](
-                ctx: $crate::sys::FsContext,
+                ctx: <$abi as $crate::abi::Abi>::Context,
                 dt: f32,
             ) -> bool {
-                unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    [<$name _with>](|g| <$state as $crate::modules::Gauge>::update(g, &ctx, dt))
-                        .unwrap_or(false)
-                }
+                $crate::vars::frame::begin();
+                $crate::events::input_event::update();
+                $crate::events::notification::update();
+                $crate::comm_bus::poll();
+                $crate::network::update();
+                $crate::executor::poll();
+                let ok = unsafe {
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    [<$name _with>](|g| {
+                        if ![<$name _GAUGE_INITIALIZED>] {
+                            [<$name _GAUGE_INITIALIZED>] = true;
+                            <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PostInitialize);
+                        }
+                        if <$state as $crate::modules::Gauge>::PAUSE_SERVICES {
+                            let paused = $crate::modules::sim_paused();
+                            if paused != [<$name _GAUGE_PAUSED>] {
+                                [<$name _GAUGE_PAUSED>] = paused;
+                                let service = if paused {
+                                    $crate::modules::PanelService::Paused
+                                } else {
+                                    $crate::modules::PanelService::Resumed
+                                };
+                                <$state as $crate::modules::Gauge>::panel_service(g, &ctx, service);
+                            }
+                        }
+                        <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PreUpdate);
+                        let ok = <$state as $crate::modules::Gauge>::update(g, &ctx, dt);
+                        <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PostUpdate);
+                        ok
+                    })
+                    .unwrap_or(false)
+                };
+                $crate::vars::frame::end();
+                ok
             }
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _gauge_draw>](
-                ctx: $crate::sys::FsContext,
-                p_draw: *mut $crate::sys::sGaugeDrawData,
+                ctx: <$abi as $crate::abi::Abi>::Context,
+                p_draw: *mut <$abi as $crate::abi::Abi>::GaugeDraw,
             ) -> bool {
-                unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    let draw = &mut *p_draw;
-                    [<$name _with>](|g| <$state as $crate::modules::Gauge>::draw(g, &ctx, draw))
+                $crate::vars::frame::begin();
+                let ok = unsafe {
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    <$abi as $crate::abi::Abi>::with_gauge_draw(p_draw, |draw| {
+                        [<$name _with>](|g| {
+                            if !<$state as $crate::modules::Gauge>::needs_redraw(g, &ctx) {
+                                return true;
+                            }
+                            <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PreDraw);
+                            let ok = <$state as $crate::modules::Gauge>::draw(g, &ctx, draw);
+                            <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PostDraw);
+                            ok
+                        })
                         .unwrap_or(false)
-                }
+                    })
+                };
+                $crate::vars::frame::end();
+                ok
             }
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _gauge_kill>](
-                ctx: $crate::sys::FsContext,
+                ctx: <$abi as $crate::abi::Abi>::Context,
             ) -> bool {
                 unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    let ok = [<$name _with>](|g| <$state as $crate::modules::Gauge>::kill(g, &ctx))
-                        .unwrap_or(false);
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    let ok = [<$name _with>](|g| {
+                        <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PreKill);
+                        let ok = <$state as $crate::modules::Gauge>::kill(g, &ctx);
+                        <$state as $crate::modules::Gauge>::panel_service(g, &ctx, $crate::modules::PanelService::PostKill);
+                        ok
+                    })
+                    .unwrap_or(false);
                     [<$name _GAUGE>] = None;
+                    [<$name _GAUGE_INITIALIZED>] = false;
+                    [<$name _GAUGE_PAUSED>] = false;
                     ok
                 }
             }
 
             #[unsafe(no_mangle)]
             pub extern "C" fn [<$name _gauge_mouse_handler>](
-                ctx: $crate::sys::FsContext,
+                ctx: <$abi as $crate::abi::Abi>::Context,
                 x: f32,
                 y: f32,
                 flags: i32,
             ) {
+                $crate::vars::frame::begin();
                 unsafe {
-                    let ctx = $crate::context::Context::from_raw(ctx);
-                    let _ = [<$name _with>](|g| <$state as $crate::modules::Gauge>::mouse(g, &ctx, x, y, flags));
+                    let ctx = <$abi as $crate::abi::Abi>::context(ctx);
+                    let tip = [<$name _with>](|g| {
+                        let event = $crate::input::MouseEvent::from_raw(x, y, flags);
+                        <$state as $crate::modules::Gauge>::mouse_event(g, &ctx, event);
+                        <$state as $crate::modules::Gauge>::tooltip(g, &ctx, x, y)
+                    })
+                    .flatten();
+                    $crate::tooltip::publish(stringify!($name), tip);
                 }
+                $crate::vars::frame::end();
             }
         }
     };
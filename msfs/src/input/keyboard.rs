@@ -0,0 +1,243 @@
+//! Keyboard input through a SimConnect input group.
+//!
+//! WASM modules don't see raw key presses; what the SDK allows is mapping a
+//! key combination, written the way SimConnect spells it (`"Ctrl+Shift+D"`,
+//! `"F5"`, `"Shift+A"`), to an event of our own. [`Keyboard`] wraps that in
+//! callbacks, and [`Keyboard::commands`] builds typed lines on top of it for
+//! debug consoles:
+//!
+//! ```no_run
+//! use msfs::input::keyboard::{KeyState, Keyboard};
+//!
+//! let mut keyboard = Keyboard::new().expect("SimConnect unavailable");
+//! keyboard.commands(|line| println!("command: {line}"));
+//! keyboard.bind("Ctrl+Shift+D", |state| {
+//!     if state == KeyState::Down {
+//!         println!("dump state");
+//!     }
+//! });
+//!
+//! // in update():
+//! keyboard.poll();
+//! ```
+//!
+//! Key names SimConnect doesn't know are not reported back; the binding just
+//! never fires.
+
+use crate::simconnect::{self, read_u32};
+use crate::sys::{
+    BOOL, DWORD, HANDLE, SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT,
+    SIMCONNECT_STATE_SIMCONNECT_STATE_OFF, SIMCONNECT_STATE_SIMCONNECT_STATE_ON, SimConnect_Close,
+    SimConnect_MapClientEventToSimEvent, SimConnect_MapInputEventToClientEvent_EX1,
+    SimConnect_RemoveInputEvent, SimConnect_SetInputGroupPriority, SimConnect_SetInputGroupState,
+};
+use std::{cell::RefCell, ffi::CString, rc::Rc};
+
+const GROUP_ID: DWORD = 1;
+// `static const`s in SimConnect.h, which bindgen doesn't emit.
+const GROUP_PRIORITY_HIGHEST_MASKABLE: DWORD = 10_000_000;
+
+// `SIMCONNECT_RECV_EVENT` is packed: dwSize dwVersion dwID uGroupID uEventID dwData.
+const GROUP: usize = 12;
+const EVENT: usize = 16;
+const DATA: usize = 20;
+
+/// Whether a bound key went down or came back up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyState {
+    Down,
+    Up,
+}
+
+/// A key from [`Keyboard::text_input`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextKey {
+    /// A letter (upper case with Shift), digit or space.
+    Char(char),
+    Enter,
+    Backspace,
+    Escape,
+}
+
+/// A binding made with [`Keyboard::bind`], for [`Keyboard::unbind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding(usize);
+
+type Callback = Box<dyn FnMut(KeyState)>;
+
+struct Binding {
+    keys: CString,
+    callback: Callback,
+}
+
+/// Key bindings owned by one SimConnect client.
+///
+/// Bound keys are masked by default: while the keyboard is enabled the sim's
+/// own bindings for them don't fire. Use [`Keyboard::set_enabled`] to only
+/// capture keys while a console is open.
+pub struct Keyboard {
+    handle: HANDLE,
+    // Indexed by client event id; `None` once unbound.
+    bindings: Vec<Option<Binding>>,
+    masking: bool,
+}
+
+impl Keyboard {
+    /// Open a SimConnect client with an enabled, highest-priority input group.
+    /// `None` if SimConnect is unavailable.
+    pub fn new() -> Option<Self> {
+        let handle = simconnect::open("infinity-rs keyboard")?;
+        unsafe {
+            SimConnect_SetInputGroupPriority(handle, GROUP_ID, GROUP_PRIORITY_HIGHEST_MASKABLE);
+            SimConnect_SetInputGroupState(
+                handle,
+                GROUP_ID,
+                SIMCONNECT_STATE_SIMCONNECT_STATE_ON as DWORD,
+            );
+        }
+        Some(Self {
+            handle,
+            bindings: Vec::new(),
+            masking: true,
+        })
+    }
+
+    /// Whether later [`bind`](Keyboard::bind)s hide their keys from the sim.
+    pub fn set_masking(&mut self, masking: bool) {
+        self.masking = masking;
+    }
+
+    /// Call `callback` when `keys` goes down and up. `None` if `keys` has a
+    /// nul byte or SimConnect rejected the mapping.
+    pub fn bind(
+        &mut self,
+        keys: &str,
+        callback: impl FnMut(KeyState) + 'static,
+    ) -> Option<KeyBinding> {
+        let keys = CString::new(keys).ok()?;
+        let id = self.bindings.len() as DWORD;
+        let ok = unsafe {
+            SimConnect_MapClientEventToSimEvent(self.handle, id, c"".as_ptr()) >= 0
+                && SimConnect_MapInputEventToClientEvent_EX1(
+                    self.handle,
+                    GROUP_ID,
+                    keys.as_ptr(),
+                    id,
+                    1,
+                    id,
+                    0,
+                    self.masking as BOOL,
+                ) >= 0
+        };
+        if !ok {
+            return None;
+        }
+        self.bindings.push(Some(Binding {
+            keys,
+            callback: Box::new(callback),
+        }));
+        Some(KeyBinding(id as usize))
+    }
+
+    /// Remove a binding. Returns `false` if it was already removed.
+    pub fn unbind(&mut self, binding: KeyBinding) -> bool {
+        let Some(binding) = self.bindings.get_mut(binding.0).and_then(Option::take) else {
+            return false;
+        };
+        unsafe { SimConnect_RemoveInputEvent(self.handle, GROUP_ID, binding.keys.as_ptr()) >= 0 }
+    }
+
+    /// Bind the keys needed to type a command: letters (upper case with
+    /// Shift), digits, space, Enter, Backspace and Esc. Returns `false` if any
+    /// of them couldn't be bound.
+    pub fn text_input(&mut self, callback: impl FnMut(TextKey) + 'static) -> bool {
+        let callback = Rc::new(RefCell::new(callback));
+        let mut keys: Vec<(String, TextKey)> = Vec::new();
+        for c in 'A'..='Z' {
+            keys.push((c.to_string(), TextKey::Char(c.to_ascii_lowercase())));
+            keys.push((format!("Shift+{c}"), TextKey::Char(c)));
+        }
+        keys.extend(('0'..='9').map(|c| (c.to_string(), TextKey::Char(c))));
+        keys.push(("Space".into(), TextKey::Char(' ')));
+        keys.push(("Enter".into(), TextKey::Enter));
+        keys.push(("Backspace".into(), TextKey::Backspace));
+        keys.push(("Esc".into(), TextKey::Escape));
+
+        let mut ok = true;
+        for (name, key) in keys {
+            let callback = callback.clone();
+            ok &= self
+                .bind(&name, move |state| {
+                    if state == KeyState::Down {
+                        (callback.borrow_mut())(key);
+                    }
+                })
+                .is_some();
+        }
+        ok
+    }
+
+    /// [`text_input`](Keyboard::text_input) collected into lines: `callback`
+    /// gets each line when Enter is pressed. Backspace deletes a character and
+    /// Esc drops the line.
+    pub fn commands(&mut self, mut callback: impl FnMut(&str) + 'static) -> bool {
+        let mut line = String::new();
+        self.text_input(move |key| match key {
+            TextKey::Char(c) => line.push(c),
+            TextKey::Backspace => {
+                line.pop();
+            }
+            TextKey::Escape => line.clear(),
+            TextKey::Enter => {
+                callback(&line);
+                line.clear();
+            }
+        })
+    }
+
+    /// Turn every binding on or off; while off, the keys go to the sim as
+    /// usual. Starts on.
+    pub fn set_enabled(&mut self, enabled: bool) -> bool {
+        let state = if enabled {
+            SIMCONNECT_STATE_SIMCONNECT_STATE_ON
+        } else {
+            SIMCONNECT_STATE_SIMCONNECT_STATE_OFF
+        };
+        unsafe { SimConnect_SetInputGroupState(self.handle, GROUP_ID, state as DWORD) >= 0 }
+    }
+
+    /// Process pending SimConnect messages, calling the bindings' callbacks.
+    pub fn poll(&mut self) {
+        let handle = self.handle;
+        simconnect::dispatch(handle, |bytes| self.receive(bytes));
+    }
+
+    fn receive(&mut self, bytes: &[u8]) {
+        if simconnect::recv_id(bytes).map(|id| id as i32)
+            != Some(SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as i32)
+            || read_u32(bytes, GROUP) != Some(GROUP_ID as u32)
+        {
+            return;
+        }
+        let (Some(event), Some(data)) = (read_u32(bytes, EVENT), read_u32(bytes, DATA)) else {
+            return;
+        };
+        let Some(Some(binding)) = self.bindings.get_mut(event as usize) else {
+            return;
+        };
+        let state = if data != 0 {
+            KeyState::Down
+        } else {
+            KeyState::Up
+        };
+        (binding.callback)(state);
+    }
+}
+
+impl Drop for Keyboard {
+    fn drop(&mut self) {
+        unsafe {
+            SimConnect_Close(self.handle);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Gauge input: typed mouse events and keyboard capture.
+
+pub mod keyboard;
+pub mod mouse;
+
+pub use keyboard::{KeyBinding, KeyState, Keyboard, TextKey};
+pub use mouse::{MouseButton, MouseEvent, MouseEventKind, MouseFlags};
@@ -0,0 +1,146 @@
+//! Gauge mouse input.
+
+use crate::sys::{
+    MOUSE_DOWN_REPEAT, MOUSE_LEAVE, MOUSE_LEFTDOUBLE, MOUSE_LEFTDRAG, MOUSE_LEFTRELEASE,
+    MOUSE_LEFTSINGLE, MOUSE_MIDDLEDOUBLE, MOUSE_MIDDLEDRAG, MOUSE_MIDDLERELEASE,
+    MOUSE_MIDDLESINGLE, MOUSE_MOVE, MOUSE_MOVE_REPEAT, MOUSE_RIGHTDOUBLE, MOUSE_RIGHTDRAG,
+    MOUSE_RIGHTRELEASE, MOUSE_RIGHTSINGLE, MOUSE_WHEEL_DOWN, MOUSE_WHEEL_FLIP, MOUSE_WHEEL_SKIP,
+    MOUSE_WHEEL_UP,
+};
+
+bitflags::bitflags! {
+    /// The `flags` the sim passes to a gauge's mouse handler (`MOUSE_*` in
+    /// `gauges.h`).
+    pub struct MouseFlags: u32 {
+        const LEFT_DOWN      = MOUSE_LEFTSINGLE;
+        const RIGHT_DOWN     = MOUSE_RIGHTSINGLE;
+        const MIDDLE_DOWN    = MOUSE_MIDDLESINGLE;
+        const LEFT_DOUBLE    = MOUSE_LEFTDOUBLE;
+        const RIGHT_DOUBLE   = MOUSE_RIGHTDOUBLE;
+        const MIDDLE_DOUBLE  = MOUSE_MIDDLEDOUBLE;
+        const LEFT_DRAG      = MOUSE_LEFTDRAG;
+        const RIGHT_DRAG     = MOUSE_RIGHTDRAG;
+        const MIDDLE_DRAG    = MOUSE_MIDDLEDRAG;
+        const LEFT_RELEASE   = MOUSE_LEFTRELEASE;
+        const RIGHT_RELEASE  = MOUSE_RIGHTRELEASE;
+        const MIDDLE_RELEASE = MOUSE_MIDDLERELEASE;
+        const MOVE           = MOUSE_MOVE;
+        const MOVE_REPEAT    = MOUSE_MOVE_REPEAT;
+        const DOWN_REPEAT    = MOUSE_DOWN_REPEAT;
+        const WHEEL_UP       = MOUSE_WHEEL_UP;
+        const WHEEL_DOWN     = MOUSE_WHEEL_DOWN;
+        const WHEEL_FLIP     = MOUSE_WHEEL_FLIP;
+        const WHEEL_SKIP     = MOUSE_WHEEL_SKIP;
+        const LEAVE          = MOUSE_LEAVE;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// What a mouse event is, with the buttons folded into [`MouseButton`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    DoubleClick(MouseButton),
+    Release(MouseButton),
+    Drag(MouseButton),
+    Move,
+    WheelUp,
+    WheelDown,
+    /// The cursor left the gauge.
+    Leave,
+    /// Flags this type doesn't model, e.g. `DOWN_REPEAT` alone.
+    Other,
+}
+
+/// One call of the gauge mouse handler, see
+/// [`Gauge::mouse_event`](crate::modules::Gauge::mouse_event).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MouseEvent {
+    pub x: f32,
+    pub y: f32,
+    pub kind: MouseEventKind,
+    /// Everything the sim set, for combinations `kind` doesn't cover.
+    pub flags: MouseFlags,
+    raw: i32,
+}
+
+impl MouseEvent {
+    /// Decode the handler's raw arguments. Unknown bits are left out of
+    /// `flags` but kept in [`MouseEvent::raw_flags`].
+    pub fn from_raw(x: f32, y: f32, raw: i32) -> Self {
+        let flags = MouseFlags::from_bits_truncate(raw as u32);
+        Self {
+            x,
+            y,
+            kind: MouseEventKind::from_flags(flags),
+            flags,
+            raw,
+        }
+    }
+
+    /// The flags exactly as the sim passed them, as given to
+    /// [`Gauge::mouse`](crate::modules::Gauge::mouse).
+    pub fn raw_flags(&self) -> i32 {
+        self.raw
+    }
+}
+
+impl MouseEventKind {
+    /// The most specific kind `flags` describes: double clicks, then clicks,
+    /// releases, drags, the wheel, leave and finally moves.
+    pub fn from_flags(flags: MouseFlags) -> Self {
+        use MouseButton::*;
+        let button = |left, right, middle| {
+            if flags.contains(left) {
+                Some(Left)
+            } else if flags.contains(right) {
+                Some(Right)
+            } else if flags.contains(middle) {
+                Some(Middle)
+            } else {
+                None
+            }
+        };
+        if let Some(b) = button(
+            MouseFlags::LEFT_DOUBLE,
+            MouseFlags::RIGHT_DOUBLE,
+            MouseFlags::MIDDLE_DOUBLE,
+        ) {
+            MouseEventKind::DoubleClick(b)
+        } else if let Some(b) = button(
+            MouseFlags::LEFT_DOWN,
+            MouseFlags::RIGHT_DOWN,
+            MouseFlags::MIDDLE_DOWN,
+        ) {
+            MouseEventKind::Down(b)
+        } else if let Some(b) = button(
+            MouseFlags::LEFT_RELEASE,
+            MouseFlags::RIGHT_RELEASE,
+            MouseFlags::MIDDLE_RELEASE,
+        ) {
+            MouseEventKind::Release(b)
+        } else if let Some(b) = button(
+            MouseFlags::LEFT_DRAG,
+            MouseFlags::RIGHT_DRAG,
+            MouseFlags::MIDDLE_DRAG,
+        ) {
+            MouseEventKind::Drag(b)
+        } else if flags.contains(MouseFlags::WHEEL_UP) {
+            MouseEventKind::WheelUp
+        } else if flags.contains(MouseFlags::WHEEL_DOWN) {
+            MouseEventKind::WheelDown
+        } else if flags.contains(MouseFlags::LEAVE) {
+            MouseEventKind::Leave
+        } else if flags.intersects(MouseFlags::MOVE | MouseFlags::MOVE_REPEAT) {
+            MouseEventKind::Move
+        } else {
+            MouseEventKind::Other
+        }
+    }
+}
@@ -4,6 +4,7 @@ use std::{
     ffi::CString,
     os::raw::{c_char, c_void},
     ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 pub mod fs;
@@ -66,6 +67,15 @@ impl std::fmt::Display for IoError {
     }
 }
 
+impl std::error::Error for IoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IoError::Nul(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 pub type IoResult<T> = Result<T, IoError>;
 
 bitflags::bitflags! {
@@ -80,6 +90,14 @@ bitflags::bitflags! {
     }
 }
 
+// Operations submitted to the sim whose callback has not fired yet.
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of open/read/write operations still waiting for their callback.
+pub fn in_flight() -> usize {
+    IN_FLIGHT.load(Ordering::Relaxed)
+}
+
 struct OpenCb(Box<dyn FnOnce(File) + 'static>);
 
 struct ReadCb(Box<dyn FnOnce(&[u8], i32) + 'static>);
@@ -92,6 +110,7 @@ extern "C" fn open_trampoline(file: FsIOFile, user: *mut c_void) {
     }
 
     let cb = unsafe { Box::from_raw(user as *mut OpenCb) };
+    IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
     (cb.0)(File(file));
 }
 
@@ -106,6 +125,7 @@ extern "C" fn read_trampoline(
         return;
     }
     let cb = unsafe { Box::from_raw(user as *mut ReadCb) };
+    IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
     let slice = if buf.is_null() || bytes_read <= 0 {
         &[]
     } else {
@@ -125,6 +145,7 @@ extern "C" fn write_trampoline(
         return;
     }
     let cb = unsafe { Box::from_raw(user as *mut WriteCb) };
+    IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
     (cb.0)(byte_offset, bytes_written);
 }
 
@@ -179,6 +200,7 @@ impl File {
         on_done: impl FnOnce(&[u8], i32) + 'static,
     ) -> IoResult<()> {
         let cb = Box::into_raw(Box::new(ReadCb(Box::new(on_done))));
+        IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
         let code = unsafe {
             fsIORead(
                 self.0,
@@ -192,6 +214,7 @@ impl File {
         if let Some(e) = IoError::from_raw(code) {
             unsafe {
                 drop(Box::from_raw(cb));
+                IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
             }
             return Err(e);
         }
@@ -205,6 +228,7 @@ impl File {
         on_done: impl FnOnce(i32, i32) + 'static,
     ) -> IoResult<()> {
         let cb = Box::into_raw(Box::new(WriteCb(Box::new(on_done))));
+        IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
         let code = unsafe {
             fsIOWrite(
                 self.0,
@@ -218,6 +242,7 @@ impl File {
         if let Some(e) = IoError::from_raw(code) {
             unsafe {
                 drop(Box::from_raw(cb));
+                IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
             }
             return Err(e);
         }
@@ -242,6 +267,7 @@ impl Drop for File {
 pub fn open(path: &str, flags: OpenFlags, on_done: impl FnOnce(File) + 'static) -> IoResult<File> {
     let path_c = CString::new(path)?;
     let cb = Box::into_raw(Box::new(OpenCb(Box::new(on_done))));
+    IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
     let raw = unsafe {
         fsIOOpen(
             path_c.as_ptr(),
@@ -253,6 +279,7 @@ pub fn open(path: &str, flags: OpenFlags, on_done: impl FnOnce(File) + 'static)
     if raw as u32 == FS_IO_ERROR_FILE {
         unsafe {
             drop(Box::from_raw(cb));
+            IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
         }
         return Err(IoError::FileNotFound);
     }
@@ -268,6 +295,7 @@ pub fn open_read(
 ) -> IoResult<File> {
     let path_c = CString::new(path)?;
     let cb = Box::into_raw(Box::new(ReadCb(Box::new(on_done))));
+    IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
     let raw = unsafe {
         fsIOOpenRead(
             path_c.as_ptr(),
@@ -281,6 +309,7 @@ pub fn open_read(
     if raw as u32 == FS_IO_ERROR_FILE {
         unsafe {
             drop(Box::from_raw(cb));
+            IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
         }
         return Err(IoError::FileNotFound);
     }
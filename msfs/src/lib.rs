@@ -4,15 +4,25 @@ extern crate self as msfs;
 pub use paste as __paste;
 
 pub mod abi;
+pub mod avionics;
+pub mod bus;
 pub mod comm_bus;
+pub mod config;
 pub mod context;
 pub mod events;
+pub mod executor;
 pub mod exports;
+pub mod input;
 pub mod io;
+pub mod metrics;
 pub mod modules;
 pub mod network;
 pub mod prelude;
+mod simconnect;
+pub mod sound;
 pub mod sys;
+pub mod toolbar;
+pub mod tooltip;
 pub mod types;
 pub mod utils;
 pub mod vars;
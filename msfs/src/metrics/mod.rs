@@ -0,0 +1,142 @@
+//! Runtime metrics published as LVars.
+//!
+//! Create a [`Metrics`] in the module state to opt in. Every second it writes the
+//! averages for the last window to `L:<prefix>_*` so they can be watched from the
+//! dev-mode LVar window:
+//!
+//! | LVar | Meaning |
+//! |---|---|
+//! | `<prefix>_FPS` | update ticks per second |
+//! | `<prefix>_FRAME_MS` / `<prefix>_FRAME_MAX_MS` | average / worst `dt` |
+//! | `<prefix>_UPDATE_MS` / `<prefix>_UPDATE_MAX_MS` | average / worst time spent in [`Metrics::measure`] |
+//! | `<prefix>_IO_IN_FLIGHT` | pending [`crate::io`] operations |
+//! | `<prefix>_NET_IN_FLIGHT` | pending [`crate::network`] requests |
+//! | `<prefix>_MEMORY_KB` | WASM linear memory size |
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::metrics::Metrics;
+//!
+//! let mut metrics = Metrics::new("INFINITY_FMS")?;
+//!
+//! // in update():
+//! # let dt = 1.0 / 30.0;
+//! metrics.measure(dt, || {
+//!     // ... module work ...
+//! });
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use crate::vars::{LVar, VarResult};
+use std::time::Instant;
+
+const PUBLISH_INTERVAL: f32 = 1.0;
+
+struct MetricVars {
+    fps: LVar,
+    frame_ms: LVar,
+    frame_max_ms: LVar,
+    update_ms: LVar,
+    update_max_ms: LVar,
+    io_in_flight: LVar,
+    net_in_flight: LVar,
+    memory_kb: LVar,
+}
+
+#[derive(Default)]
+struct Window {
+    elapsed: f32,
+    frames: u32,
+    frame_max: f32,
+    update_total: f64,
+    update_max: f64,
+    updates: u32,
+}
+
+pub struct Metrics {
+    vars: MetricVars,
+    window: Window,
+    interval: f32,
+}
+
+impl Metrics {
+    /// Register the metric LVars under `prefix` (without the `L:`).
+    pub fn new(prefix: &str) -> VarResult<Self> {
+        let var = |name: &str| LVar::new(&format!("L:{prefix}_{name}"), "Number");
+        Ok(Self {
+            vars: MetricVars {
+                fps: var("FPS")?,
+                frame_ms: var("FRAME_MS")?,
+                frame_max_ms: var("FRAME_MAX_MS")?,
+                update_ms: var("UPDATE_MS")?,
+                update_max_ms: var("UPDATE_MAX_MS")?,
+                io_in_flight: var("IO_IN_FLIGHT")?,
+                net_in_flight: var("NET_IN_FLIGHT")?,
+                memory_kb: var("MEMORY_KB")?,
+            },
+            window: Window::default(),
+            interval: PUBLISH_INTERVAL,
+        })
+    }
+
+    /// Seconds between publishes. Defaults to 1s.
+    pub fn with_interval(mut self, seconds: f32) -> Self {
+        self.interval = seconds.max(f32::EPSILON);
+        self
+    }
+
+    /// Count a frame of `dt` seconds. Call once per tick if not using [`measure`](Self::measure).
+    pub fn frame(&mut self, dt: f32) {
+        let w = &mut self.window;
+        w.elapsed += dt;
+        w.frames += 1;
+        w.frame_max = w.frame_max.max(dt);
+
+        if w.elapsed >= self.interval {
+            self.publish();
+        }
+    }
+
+    /// Run `f`, recording how long it took, then count the frame.
+    pub fn measure<R>(&mut self, dt: f32, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let out = f();
+        let took = start.elapsed().as_secs_f64();
+
+        let w = &mut self.window;
+        w.update_total += took;
+        w.update_max = w.update_max.max(took);
+        w.updates += 1;
+
+        self.frame(dt);
+        out
+    }
+
+    fn publish(&mut self) {
+        let w = std::mem::take(&mut self.window);
+        let frames = w.frames.max(1) as f64;
+        let updates = w.updates.max(1) as f64;
+        let v = &self.vars;
+
+        let _ = v.fps.set(w.frames as f64 / w.elapsed as f64);
+        let _ = v.frame_ms.set(w.elapsed as f64 * 1000.0 / frames);
+        let _ = v.frame_max_ms.set(w.frame_max as f64 * 1000.0);
+        let _ = v.update_ms.set(w.update_total * 1000.0 / updates);
+        let _ = v.update_max_ms.set(w.update_max * 1000.0);
+        let _ = v.io_in_flight.set(crate::io::in_flight() as f64);
+        let _ = v.net_in_flight.set(crate::network::in_flight() as f64);
+        let _ = v.memory_kb.set(memory_bytes() as f64 / 1024.0);
+    }
+}
+
+/// Current size of the module's linear memory.
+pub fn memory_bytes() -> usize {
+    #[cfg(target_arch = "wasm32")]
+    {
+        core::arch::wasm32::memory_size::<0>() * 65536
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        0
+    }
+}
@@ -1,12 +1,118 @@
 use crate::{
     context::Context,
+    input::MouseEvent,
+    simconnect::{self, Inbox, read_u32, with_client},
+    sys::{DWORD, SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT, SimConnect_SubscribeToSystemEvent},
     types::{GaugeDraw, GaugeInstall, SystemInstall},
 };
+use std::cell::RefCell;
+
+/// Lifecycle notifications, named after the legacy `PANEL_SERVICE_*` ids.
+///
+/// The sim's per-module entry points (`_init`, `_update`, `_draw`, `_kill`)
+/// don't pass a service id, so none of these come from the sim: the export
+/// macros synthesize them around the calls they already make, and every
+/// module gets them without exporting a `gauge_callback`. Each variant says
+/// when it's sent. [`Paused`](Self::Paused) and [`Resumed`](Self::Resumed)
+/// have no legacy id; they follow [`sim_paused`], and are only sent to
+/// modules that set `PAUSE_SERVICES`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PanelService {
+    /// Before `init`.
+    PreInstall,
+    /// After `init` returned `true`.
+    PostInstall,
+    /// Once, before the first update after `init`, i.e. once the flight has loaded.
+    PostInitialize,
+    /// Before each `update`.
+    PreUpdate,
+    /// After each `update`.
+    PostUpdate,
+    /// Before `draw`. Gauges only, and only for frames that are drawn (see
+    /// [`Gauge::needs_redraw`]).
+    PreDraw,
+    /// After `draw`.
+    PostDraw,
+    /// Before `kill`, e.g. when the flight is unloaded.
+    PreKill,
+    /// After `kill`, just before the module's state is dropped.
+    PostKill,
+    /// Before the first update after the sim was paused (menu, active pause,
+    /// ...). Only with [`System::PAUSE_SERVICES`] or [`Gauge::PAUSE_SERVICES`].
+    Paused,
+    /// Before the first update after the sim was unpaused. Only with
+    /// `PAUSE_SERVICES`.
+    Resumed,
+}
+
+// `SIMCONNECT_RECV_EVENT` is packed: dwSize dwVersion dwID uGroupID uEventID dwData.
+const EVENT: usize = 16;
+const DATA: usize = 20;
+
+struct PauseWatch {
+    inbox: Inbox,
+    event: DWORD,
+    // `Pause_EX1` flags; 0 when running.
+    state: u32,
+}
+
+thread_local! {
+    // `None` until the first call, `Some(None)` if SimConnect is unavailable.
+    static PAUSE: RefCell<Option<Option<PauseWatch>>> = const { RefCell::new(None) };
+}
+
+fn watch_pause() -> Option<PauseWatch> {
+    let inbox = Inbox::new()?;
+    let event = with_client(|client| {
+        let event = client.next_id();
+        let ok = unsafe {
+            SimConnect_SubscribeToSystemEvent(client.handle(), event, c"Pause_EX1".as_ptr()) >= 0
+        };
+        ok.then_some(event)
+    })??;
+    inbox.claim(event);
+    Some(PauseWatch {
+        inbox,
+        event,
+        state: 0,
+    })
+}
+
+/// Whether the sim is paused in any way (menu, active pause, ...), from
+/// SimConnect's `Pause_EX1` event on the module's shared client. The first
+/// call opens the client and subscribes. `false` until the sim first reports
+/// a pause, and if SimConnect is unavailable.
+pub fn sim_paused() -> bool {
+    PAUSE.with(|p| {
+        let mut p = p.borrow_mut();
+        let Some(watch) = p.get_or_insert_with(watch_pause) else {
+            return false;
+        };
+        for bytes in watch.inbox.receive() {
+            if simconnect::recv_id(&bytes)
+                == Some(SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as u32)
+                && read_u32(&bytes, EVENT) == Some(watch.event as u32)
+                && let Some(state) = read_u32(&bytes, DATA)
+            {
+                watch.state = state;
+            }
+        }
+        watch.state != 0
+    })
+}
 
 pub trait System: 'static {
     fn init(&mut self, ctx: &Context, install: &SystemInstall) -> bool;
     fn update(&mut self, ctx: &Context, dt: f32) -> bool;
     fn kill(&mut self, ctx: &Context) -> bool;
+
+    /// Send [`PanelService::Paused`] and [`PanelService::Resumed`]. Off by
+    /// default, since watching for pauses opens the module's SimConnect
+    /// client.
+    const PAUSE_SERVICES: bool = false;
+
+    /// Called on each [`PanelService`] transition.
+    fn panel_service(&mut self, _ctx: &Context, _service: PanelService) {}
 }
 
 pub trait Gauge: 'static {
@@ -15,5 +121,34 @@ pub trait Gauge: 'static {
     fn draw(&mut self, ctx: &Context, draw: &mut GaugeDraw) -> bool;
     fn kill(&mut self, ctx: &Context) -> bool;
 
+    /// Return `false` when nothing visible changed since the last frame.
+    ///
+    /// The export layer then skips `draw` entirely, so no NVG frame is recorded
+    /// and the previous frame stays on screen. Useful for mostly static displays.
+    fn needs_redraw(&mut self, _ctx: &Context) -> bool {
+        true
+    }
+
+    /// Raw mouse handler; `flags` are `MOUSE_*` bits. Prefer
+    /// [`Gauge::mouse_event`], which decodes them.
     fn mouse(&mut self, _ctx: &Context, _x: f32, _y: f32, _flags: i32) {}
+
+    /// Decoded mouse handler, called by the export layer. Defaults to
+    /// forwarding to [`Gauge::mouse`].
+    fn mouse_event(&mut self, ctx: &Context, event: MouseEvent) {
+        self.mouse(ctx, event.x, event.y, event.raw_flags());
+    }
+
+    /// Tooltip id for the cursor position, or `None` to hide the tooltip.
+    ///
+    /// Checked after every mouse event; changes are published through [`crate::tooltip`].
+    fn tooltip(&mut self, _ctx: &Context, _x: f32, _y: f32) -> Option<u32> {
+        None
+    }
+
+    /// See [`System::PAUSE_SERVICES`].
+    const PAUSE_SERVICES: bool = false;
+
+    /// Called on each [`PanelService`] transition.
+    fn panel_service(&mut self, _ctx: &Context, _service: PanelService) {}
 }
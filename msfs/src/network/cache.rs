@@ -0,0 +1,371 @@
+//! Opt-in HTTP cache in `\work`, revalidated with `ETag` / `Last-Modified`.
+//!
+//! An [`HttpCache`] stores every GET response that carries a validator in a
+//! directory under `\work`, keyed by URL, and keeps an index of the
+//! validators there so it survives the session. Later requests for the same
+//! URL send `If-None-Match` / `If-Modified-Since`; on `304 Not Modified` the
+//! body is read back from the file and handed over as a `200`, the way
+//! browsers do. Large once-per-session downloads such as airport databases
+//! then cost a round trip instead of the whole body.
+//!
+//! Responses are delivered from [`update`](HttpCache::update), which also
+//! loads the index and writes the files; call it every update.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::{HttpParams, cache::{CacheStatus, HttpCache}};
+//!
+//! let mut cache = HttpCache::new("\\work/http-cache");
+//! cache.get("https://example.com/airports.json", HttpParams::default(), |resp, status| {
+//!     if resp.is_success() {
+//!         println!("{} bytes ({status:?})", resp.data.len());
+//!     }
+//! })?;
+//!
+//! // in update():
+//! cache.update();
+//! # Ok::<(), msfs::network::NetError>(())
+//! ```
+
+use super::*;
+use crate::io::fs::{self, ReadRequest, WriteRequest};
+use std::{mem, rc::Rc};
+
+const INDEX_FILE: &str = "index.txt";
+
+/// Where a response handed out by an [`HttpCache`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// From the server; stored if it had a validator.
+    Miss,
+    /// The server answered `304 Not Modified` and the body came from disk.
+    Hit,
+}
+
+type CacheHandler = Box<dyn FnOnce(HttpResponse, CacheStatus) + 'static>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// What a request needs to be sent again without validators.
+struct Retry {
+    url: String,
+    headers: Vec<String>,
+    timeout: Option<Duration>,
+}
+
+struct Done {
+    retry: Retry,
+    conditional: bool,
+    response: HttpResponse,
+    entry: Entry,
+    on_done: CacheHandler,
+}
+
+struct Reading {
+    retry: Retry,
+    request: ReadRequest,
+    response: HttpResponse,
+    on_done: CacheHandler,
+}
+
+fn fnv64(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn parse_index(text: &str) -> HashMap<String, Entry> {
+    let field = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let etag = fields.next()?;
+            let last_modified = fields.next()?;
+            let url = fields.next()?;
+            Some((
+                url.to_string(),
+                Entry {
+                    etag: field(etag),
+                    last_modified: field(last_modified),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn format_index(index: &HashMap<String, Entry>) -> String {
+    let mut lines: Vec<String> = index
+        .iter()
+        .map(|(url, e)| {
+            format!(
+                "{}\t{}\t{url}\n",
+                e.etag.as_deref().unwrap_or_default(),
+                e.last_modified.as_deref().unwrap_or_default()
+            )
+        })
+        .collect();
+    lines.sort();
+    lines.concat()
+}
+
+// Tabs and line breaks would corrupt the index.
+fn storable(s: &str) -> bool {
+    !s.contains(['\t', '\r', '\n'])
+}
+
+/// A cache of GET responses in a `\work` directory.
+pub struct HttpCache {
+    dir: String,
+    index: HashMap<String, Entry>,
+    loading: Option<ReadRequest>,
+    done: Rc<RefCell<Vec<Done>>>,
+    reading: Vec<Reading>,
+    writes: Vec<WriteRequest>,
+    index_write: Option<WriteRequest>,
+    index_dirty: bool,
+}
+
+impl HttpCache {
+    /// A cache in `dir`, e.g. `\work/http-cache`. Starts loading its index;
+    /// requests sent before it's loaded are plain GETs.
+    pub fn new(dir: &str) -> Self {
+        let dir = dir.trim_end_matches(['/', '\\']).to_string();
+        let loading = fs::read(&format!("{dir}/{INDEX_FILE}"), |_| {}).ok();
+        Self {
+            dir,
+            index: HashMap::new(),
+            loading,
+            done: Rc::new(RefCell::new(Vec::new())),
+            reading: Vec::new(),
+            writes: Vec::new(),
+            index_write: None,
+            index_dirty: false,
+        }
+    }
+
+    pub fn dir(&self) -> &str {
+        &self.dir
+    }
+
+    /// GET `url`, revalidating a cached copy. `on_done` runs from
+    /// [`update`](Self::update).
+    pub fn get(
+        &mut self,
+        url: &str,
+        params: HttpParams,
+        on_done: impl FnOnce(HttpResponse, CacheStatus) + 'static,
+    ) -> NetResult<RequestHandle> {
+        let retry = Retry {
+            url: url.to_string(),
+            headers: params.headers.clone(),
+            timeout: params.timeout,
+        };
+        self.send(retry, params, true, Box::new(on_done))
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.index.contains_key(url)
+    }
+
+    /// URLs with a cached response.
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Stop revalidating `url`; the next request downloads it in full.
+    pub fn forget(&mut self, url: &str) {
+        if self.index.remove(url).is_some() {
+            self.index_dirty = true;
+        }
+    }
+
+    /// Forget every URL. The body files stay until overwritten.
+    pub fn clear(&mut self) {
+        self.index_dirty |= !self.index.is_empty();
+        self.index.clear();
+    }
+
+    /// Deliver finished responses, load the index and write pending files.
+    /// Call every update.
+    pub fn update(&mut self) {
+        self.load_index();
+
+        let done = mem::take(&mut *self.done.borrow_mut());
+        for done in done {
+            self.finish(done);
+        }
+
+        for reading in mem::take(&mut self.reading) {
+            if reading.request.is_done() {
+                let mut response = reading.response;
+                response.error_code = 200;
+                response.data = reading.request.take_data().unwrap_or_default();
+                (reading.on_done)(response, CacheStatus::Hit);
+            } else if reading.request.has_error() {
+                // The body file is gone; fetch it again in full.
+                self.forget(&reading.retry.url);
+                self.resend(reading.retry, reading.response, reading.on_done);
+            } else {
+                self.reading.push(reading);
+            }
+        }
+
+        self.writes.retain(|w| !w.is_done() && !w.has_error());
+        self.flush_index();
+    }
+
+    fn body_path(&self, url: &str) -> String {
+        format!("{}/{:016x}.body", self.dir, fnv64(url))
+    }
+
+    fn send(
+        &mut self,
+        retry: Retry,
+        mut params: HttpParams,
+        conditional: bool,
+        on_done: CacheHandler,
+    ) -> NetResult<RequestHandle> {
+        let entry = conditional.then(|| self.index.get(&retry.url)).flatten();
+        if let Some(entry) = entry {
+            if let Some(etag) = &entry.etag {
+                params.headers.push(format!("If-None-Match: {etag}"));
+            }
+            if let Some(date) = &entry.last_modified {
+                params.headers.push(format!("If-Modified-Since: {date}"));
+            }
+        }
+        let conditional = entry.is_some();
+        let url = retry.url.clone();
+        let done = Rc::clone(&self.done);
+        http_request(Method::Get, &url, params, move |response| {
+            let entry = Entry {
+                etag: response.header("ETag").filter(|v| storable(v)),
+                last_modified: response.header("Last-Modified").filter(|v| storable(v)),
+            };
+            done.borrow_mut().push(Done {
+                retry,
+                conditional,
+                response,
+                entry,
+                on_done,
+            });
+        })
+    }
+
+    /// Send `retry` again without validators, or hand `response` over if
+    /// that fails.
+    fn resend(&mut self, retry: Retry, response: HttpResponse, on_done: CacheHandler) {
+        let params = HttpParams {
+            headers: retry.headers.clone(),
+            timeout: retry.timeout,
+            ..Default::default()
+        };
+        // `on_done` is moved into the request, so keep a way to report a
+        // failed send.
+        let slot = Rc::new(RefCell::new(Some(on_done)));
+        let inner = Rc::clone(&slot);
+        let sent = self.send(
+            retry,
+            params,
+            false,
+            Box::new(move |resp, status| {
+                if let Some(on_done) = inner.borrow_mut().take() {
+                    on_done(resp, status);
+                }
+            }),
+        );
+        if sent.is_err()
+            && let Some(on_done) = slot.borrow_mut().take()
+        {
+            on_done(response, CacheStatus::Miss);
+        }
+    }
+
+    fn finish(&mut self, done: Done) {
+        let Done {
+            retry,
+            conditional,
+            response,
+            entry,
+            on_done,
+        } = done;
+
+        if response.error_code == 304 && conditional {
+            match fs::read(&self.body_path(&retry.url), |_| {}) {
+                Ok(request) => self.reading.push(Reading {
+                    retry,
+                    request,
+                    response,
+                    on_done,
+                }),
+                Err(_) => {
+                    self.forget(&retry.url);
+                    self.resend(retry, response, on_done);
+                }
+            }
+            return;
+        }
+
+        if response.is_success() {
+            if (entry.etag.is_some() || entry.last_modified.is_some()) && storable(&retry.url) {
+                if let Ok(write) = fs::write(&self.body_path(&retry.url), &response.data) {
+                    self.writes.push(write);
+                    self.index.insert(retry.url.clone(), entry);
+                    self.index_dirty = true;
+                }
+            } else {
+                self.forget(&retry.url);
+            }
+        }
+        on_done(response, CacheStatus::Miss);
+    }
+
+    fn load_index(&mut self) {
+        let Some(loading) = &self.loading else {
+            return;
+        };
+        if loading.is_done() {
+            let text = loading
+                .take_string()
+                .and_then(Result::ok)
+                .unwrap_or_default();
+            // Entries stored since `new` are newer than the file's.
+            for (url, entry) in parse_index(&text) {
+                self.index.entry(url).or_insert(entry);
+            }
+            self.loading = None;
+        } else if loading.has_error() {
+            // No index yet.
+            self.loading = None;
+        }
+    }
+
+    fn flush_index(&mut self) {
+        // Writing before the old index is read would lose it.
+        if !self.index_dirty || self.loading.is_some() {
+            return;
+        }
+        if self
+            .index_write
+            .as_ref()
+            .is_some_and(|w| !w.is_done() && !w.has_error())
+        {
+            return;
+        }
+        let path = format!("{}/{INDEX_FILE}", self.dir);
+        self.index_write = fs::write(&path, format_index(&self.index).as_bytes()).ok();
+        self.index_dirty = false;
+    }
+}
@@ -0,0 +1,256 @@
+//! Client for a local companion application (SimBridge-style) reached over HTTP.
+//!
+//! The client polls a health endpoint to track whether the companion is up,
+//! backs off exponentially while it is unreachable, and sends typed requests
+//! through [`Endpoint`] implementations.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::companion::{CompanionClient, Endpoint};
+//! use msfs::network::Method;
+//!
+//! struct Metar<'a>(&'a str);
+//!
+//! impl Endpoint for Metar<'_> {
+//!     type Response = String;
+//!     const METHOD: Method = Method::Get;
+//!
+//!     fn path(&self) -> String {
+//!         format!("/api/v1/metar/{}", self.0)
+//!     }
+//!
+//!     fn decode(data: &[u8]) -> Result<String, String> {
+//!         String::from_utf8(data.to_vec()).map_err(|e| e.to_string())
+//!     }
+//! }
+//!
+//! let mut client = CompanionClient::new("http://localhost:8380", "/health");
+//!
+//! // in update():
+//! # let dt = 1.0 / 30.0;
+//! client.update(dt);
+//! if client.is_connected() {
+//!     client.request(&Metar("EGLL"), |res| println!("{res:?}"))?;
+//! }
+//! # Ok::<(), msfs::network::companion::CompanionError>(())
+//! ```
+
+use super::*;
+use std::rc::Rc;
+
+const DEFAULT_HEARTBEAT_INTERVAL: f32 = 5.0;
+const DEFAULT_BACKOFF_INITIAL: f32 = 1.0;
+const DEFAULT_BACKOFF_MAX: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No health check has completed yet.
+    Unknown,
+    Connected,
+    Disconnected,
+}
+
+#[derive(Debug)]
+pub enum CompanionError {
+    /// The last health check failed; the request was not sent.
+    NotConnected,
+    Net(NetError),
+    /// The companion answered with a non-success status code.
+    Http(i32),
+    Decode(String),
+}
+
+impl From<NetError> for CompanionError {
+    fn from(value: NetError) -> Self {
+        CompanionError::Net(value)
+    }
+}
+
+/// A typed request against the companion app.
+pub trait Endpoint {
+    type Response;
+
+    const METHOD: Method;
+
+    /// Path appended to the client's base URL, starting with `/`.
+    fn path(&self) -> String;
+
+    fn headers(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn body(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn decode(data: &[u8]) -> Result<Self::Response, String>;
+}
+
+struct Shared {
+    state: ConnectionState,
+    health_in_flight: bool,
+    /// Set by callbacks; consumed by `update` to reschedule the next check.
+    outcome: Option<bool>,
+}
+
+pub struct CompanionClient {
+    base_url: String,
+    health_path: String,
+    heartbeat_interval: f32,
+    backoff_initial: f32,
+    backoff_max: f32,
+    backoff: f32,
+    until_next_check: f32,
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl CompanionClient {
+    pub fn new(base_url: &str, health_path: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            health_path: health_path.to_string(),
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            backoff_initial: DEFAULT_BACKOFF_INITIAL,
+            backoff_max: DEFAULT_BACKOFF_MAX,
+            backoff: DEFAULT_BACKOFF_INITIAL,
+            until_next_check: 0.0,
+            shared: Rc::new(RefCell::new(Shared {
+                state: ConnectionState::Unknown,
+                health_in_flight: false,
+                outcome: None,
+            })),
+        }
+    }
+
+    /// Seconds between health checks while connected. Defaults to 5s.
+    pub fn with_heartbeat_interval(mut self, seconds: f32) -> Self {
+        self.heartbeat_interval = seconds;
+        self
+    }
+
+    /// Reconnect delay bounds in seconds; the delay doubles after each failed check.
+    pub fn with_backoff(mut self, initial: f32, max: f32) -> Self {
+        self.backoff_initial = initial;
+        self.backoff_max = max.max(initial);
+        self.backoff = initial;
+        self
+    }
+
+    #[inline]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    #[inline]
+    pub fn state(&self) -> ConnectionState {
+        self.shared.borrow().state
+    }
+
+    #[inline]
+    pub fn is_connected(&self) -> bool {
+        self.state() == ConnectionState::Connected
+    }
+
+    /// Skip the remaining wait and run a health check on the next `update`.
+    pub fn reconnect_now(&mut self) {
+        self.backoff = self.backoff_initial;
+        self.until_next_check = 0.0;
+    }
+
+    /// Drive heartbeats and reconnection. Call once per update tick.
+    pub fn update(&mut self, dt: f32) {
+        let outcome = self.shared.borrow_mut().outcome.take();
+        match outcome {
+            Some(true) => {
+                self.backoff = self.backoff_initial;
+                self.until_next_check = self.heartbeat_interval;
+            }
+            Some(false) => {
+                self.until_next_check = self.backoff;
+                self.backoff = (self.backoff * 2.0).min(self.backoff_max);
+            }
+            None => {}
+        }
+
+        if self.shared.borrow().health_in_flight {
+            return;
+        }
+
+        self.until_next_check -= dt;
+        if self.until_next_check > 0.0 {
+            return;
+        }
+
+        let shared = Rc::clone(&self.shared);
+        let sent = http_request(
+            Method::Get,
+            &self.url(&self.health_path),
+            HttpParams::default(),
+            move |resp| {
+                let mut s = shared.borrow_mut();
+                s.health_in_flight = false;
+                s.state = if resp.is_success() {
+                    ConnectionState::Connected
+                } else {
+                    ConnectionState::Disconnected
+                };
+                s.outcome = Some(resp.is_success());
+            },
+        );
+
+        let mut s = self.shared.borrow_mut();
+        match sent {
+            Ok(_) => s.health_in_flight = true,
+            Err(_) => {
+                s.state = ConnectionState::Disconnected;
+                s.outcome = Some(false);
+            }
+        }
+    }
+
+    /// Send a typed request. Fails fast with [`CompanionError::NotConnected`] while
+    /// the companion is known to be down; a transport failure marks it down.
+    pub fn request<E: Endpoint>(
+        &self,
+        endpoint: &E,
+        on_done: impl FnOnce(Result<E::Response, CompanionError>) + 'static,
+    ) -> Result<RequestHandle, CompanionError> {
+        if self.state() == ConnectionState::Disconnected {
+            return Err(CompanionError::NotConnected);
+        }
+
+        let params = HttpParams {
+            headers: endpoint.headers(),
+            post_field: None,
+            body: endpoint.body(),
+            ..Default::default()
+        };
+
+        let shared = Rc::clone(&self.shared);
+        let handle = http_request(
+            E::METHOD,
+            &self.url(&endpoint.path()),
+            params,
+            move |resp| {
+                if resp.error_code < 0 {
+                    let mut s = shared.borrow_mut();
+                    s.state = ConnectionState::Disconnected;
+                    s.outcome = Some(false);
+                }
+
+                let result = if resp.is_success() {
+                    E::decode(&resp.data).map_err(CompanionError::Decode)
+                } else {
+                    Err(CompanionError::Http(resp.error_code))
+                };
+                on_done(result);
+            },
+        )?;
+
+        Ok(handle)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+}
@@ -0,0 +1,119 @@
+//! JSON requests with serde. Needs the `serde` feature.
+//!
+//! [`get_json`] and [`post_json`] set the JSON `Accept` / `Content-Type`
+//! headers, encode the body and decode the response, so the callback gets a
+//! typed value or a [`JsonError`].
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::json;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Metar {
+//!     icao: String,
+//!     raw: String,
+//! }
+//!
+//! json::get_json("http://localhost:8380/api/v1/metar/EGLL", |metar: Result<Metar, _>| {
+//!     match metar {
+//!         Ok(metar) => println!("{}: {}", metar.icao, metar.raw),
+//!         Err(e) => println!("no METAR: {e}"),
+//!     }
+//! })?;
+//! # Ok::<(), json::JsonError>(())
+//! ```
+
+use super::{request::Request, *};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+
+/// Why a JSON request failed.
+#[derive(Debug)]
+pub enum JsonError {
+    /// The request couldn't be sent, e.g. a NUL byte in the URL.
+    Net(NetError),
+    /// The body couldn't be encoded, e.g. a map with non-string keys.
+    Encode(serde_json::Error),
+    /// The response wasn't a success; carries its
+    /// [`error_code`](HttpResponse::error_code).
+    Http(i32),
+    /// The response body didn't decode as the expected type.
+    Decode(serde_json::Error),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Net(e) => write!(f, "could not send request: {e}"),
+            JsonError::Encode(e) => write!(f, "could not encode body: {e}"),
+            JsonError::Http(code) => write!(f, "request failed with code {code}"),
+            JsonError::Decode(e) => write!(f, "could not decode response: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JsonError::Net(e) => Some(e),
+            JsonError::Encode(e) | JsonError::Decode(e) => Some(e),
+            JsonError::Http(_) => None,
+        }
+    }
+}
+
+impl From<NetError> for JsonError {
+    fn from(e: NetError) -> Self {
+        JsonError::Net(e)
+    }
+}
+
+/// Decode a response body. An empty body (e.g. `204 No Content`) decodes as
+/// `null`, so `()` and `Option` accept it; trailing NULs are ignored.
+pub fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, serde_json::Error> {
+    let end = data.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    let data = &data[..end];
+    if data.iter().all(u8::is_ascii_whitespace) {
+        return serde_json::from_slice(b"null");
+    }
+    serde_json::from_slice(data)
+}
+
+fn respond<T: DeserializeOwned>(
+    on_done: impl FnOnce(Result<T, JsonError>) + 'static,
+) -> impl FnOnce(HttpResponse) + 'static {
+    move |resp| {
+        let result = if resp.is_success() {
+            decode(&resp.data).map_err(JsonError::Decode)
+        } else {
+            Err(JsonError::Http(resp.error_code))
+        };
+        on_done(result)
+    }
+}
+
+/// GET `url` and decode the response as `T`.
+pub fn get_json<T: DeserializeOwned + 'static>(
+    url: &str,
+    on_done: impl FnOnce(Result<T, JsonError>) + 'static,
+) -> Result<RequestHandle, JsonError> {
+    Ok(Request::get(url)
+        .header("Accept", "application/json")
+        .send(respond(on_done))?)
+}
+
+/// POST `body` as JSON to `url` and decode the response as `T`; use `()` to
+/// ignore it.
+pub fn post_json<B: Serialize + ?Sized, T: DeserializeOwned + 'static>(
+    url: &str,
+    body: &B,
+    on_done: impl FnOnce(Result<T, JsonError>) + 'static,
+) -> Result<RequestHandle, JsonError> {
+    let body = serde_json::to_vec(body).map_err(JsonError::Encode)?;
+    Ok(Request::post(url)
+        .header("Accept", "application/json")
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send(respond(on_done))?)
+}
@@ -1,20 +1,147 @@
+use crate::io::IoError;
 use crate::sys::*;
 use std::{
     cell::RefCell,
     collections::HashMap,
     ffi::CString,
+    future::Future,
     os::raw::{c_char, c_void},
+    pin::Pin,
+    rc::Rc,
+    task::{Context as TaskContext, Poll, Waker},
+    time::{Duration, Instant},
 };
 
+pub mod cache;
+pub mod companion;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod multipart;
+pub mod request;
+pub mod stream;
+pub mod upload;
+pub mod url;
+
+pub use upload::{UPLOAD_CHUNK_SIZE, UploadHandle, upload_file};
+
+/// What went wrong with a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetErrorKind {
+    /// The URL, a header or the post field contains a NUL byte.
+    Nul,
+    /// The sim refused to send the request, e.g. too many are pending.
+    Rejected,
+    /// The request hit its [`HttpParams::timeout`].
+    Timeout,
+    /// The file to [`upload_file`] couldn't be read.
+    File,
+    /// A negative code from the sim. The SDK doesn't document what they
+    /// mean, so they are passed on as is.
+    Other(i32),
+}
+
+impl NetErrorKind {
+    /// Decode a negative [`HttpResponse::error_code`]: our own
+    /// [`TIMEOUT_ERROR_CODE`], or [`Other`](Self::Other).
+    pub fn from_code(code: i32) -> Option<Self> {
+        if code == TIMEOUT_ERROR_CODE {
+            Some(NetErrorKind::Timeout)
+        } else if code < 0 {
+            Some(NetErrorKind::Other(code))
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for NetErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetErrorKind::Nul => f.write_str("NUL byte in request"),
+            NetErrorKind::Rejected => f.write_str("request rejected by the sim"),
+            NetErrorKind::Timeout => f.write_str("timed out"),
+            NetErrorKind::File => f.write_str("could not read file"),
+            NetErrorKind::Other(code) => write!(f, "network error {code}"),
+        }
+    }
+}
+
+/// A request that couldn't be sent or didn't get a response, with its URL.
 #[derive(Debug)]
-pub enum NetError {
+pub struct NetError {
+    kind: NetErrorKind,
+    url: String,
+    cause: Option<Cause>,
+}
+
+#[derive(Debug)]
+enum Cause {
     Nul(std::ffi::NulError),
-    Msfs(i32),
+    Io(IoError),
+}
+
+impl NetError {
+    pub fn new(kind: NetErrorKind, url: &str) -> Self {
+        Self {
+            kind,
+            url: url.to_string(),
+            cause: None,
+        }
+    }
+
+    fn io(e: IoError, url: &str) -> Self {
+        Self {
+            kind: NetErrorKind::File,
+            url: url.to_string(),
+            cause: Some(Cause::Io(e)),
+        }
+    }
+
+    pub fn kind(&self) -> NetErrorKind {
+        self.kind
+    }
+
+    /// The request URL; empty if unknown.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn with_url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+}
+
+impl std::fmt::Display for NetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(Cause::Io(e)) = &self.cause {
+            write!(f, ": {e}")?;
+        }
+        if !self.url.is_empty() {
+            write!(f, " ({})", self.url)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.cause {
+            Some(Cause::Nul(e)) => Some(e),
+            Some(Cause::Io(e)) => Some(e),
+            None => None,
+        }
+    }
 }
 
 impl From<std::ffi::NulError> for NetError {
     fn from(value: std::ffi::NulError) -> Self {
-        NetError::Nul(value)
+        Self {
+            kind: NetErrorKind::Nul,
+            url: String::new(),
+            cause: Some(Cause::Nul(value)),
+        }
     }
 }
 
@@ -23,11 +150,43 @@ pub type NetResult<T> = Result<T, NetError>;
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
     pub request_id: FsNetworkRequestId,
+    pub url: String,
     pub error_code: i32,
     pub data: Vec<u8>,
 }
 
+/// [`HttpResponse::error_code`] of a request that hit its
+/// [`HttpParams::timeout`]. Never a sim or HTTP status code.
+pub const TIMEOUT_ERROR_CODE: i32 = i32::MIN;
+
+impl HttpResponse {
+    /// `true` for a 2xx status, or `0` when the sim reports no error.
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.error_code == 0 || (200..300).contains(&self.error_code)
+    }
+
+    /// `true` if the request was canceled by its [`HttpParams::timeout`].
+    #[inline]
+    pub fn is_timeout(&self) -> bool {
+        self.error_code == TIMEOUT_ERROR_CODE
+    }
+
+    /// Why no response arrived, for a negative [`error_code`](Self::error_code).
+    /// HTTP error statuses such as 404 are responses, so `None`.
+    pub fn error(&self) -> Option<NetError> {
+        NetErrorKind::from_code(self.error_code).map(|kind| NetError::new(kind, &self.url))
+    }
+
+    /// A response header by name, e.g. `"ETag"`. Only available inside the
+    /// completion callback, while the sim still holds the response.
+    pub fn header(&self, name: &str) -> Option<String> {
+        header(self.request_id, name)
+    }
+}
+
 type Handler = Box<dyn FnOnce(HttpResponse) + 'static>;
+type ProgressHandler = Box<dyn FnMut(Progress) + 'static>;
 
 thread_local! {
     static HANDLERS: RefCell<HashMap<FsNetworkRequestId, Handler>> =
@@ -35,6 +194,12 @@ thread_local! {
 
     static PARAMS: RefCell<HashMap<FsNetworkRequestId, OwnedFfiParams>> =
         RefCell::new(HashMap::new());
+
+    static DEADLINES: RefCell<HashMap<FsNetworkRequestId, Instant>> =
+        RefCell::new(HashMap::new());
+
+    static PROGRESS: RefCell<HashMap<FsNetworkRequestId, (usize, ProgressHandler)>> =
+        RefCell::new(HashMap::new());
 }
 
 extern "C" fn http_trampoline(
@@ -54,11 +219,22 @@ extern "C" fn http_trampoline(
 
     let resp = HttpResponse {
         request_id,
+        url: take_url(request_id),
         error_code,
         data,
     };
 
-    drop_params(request_id);
+    DEADLINES.with(|m| m.borrow_mut().remove(&request_id));
+
+    let progress = PROGRESS.with(|m| m.borrow_mut().remove(&request_id));
+    if let Some((last, mut on_progress)) = progress
+        && resp.data.len() != last
+    {
+        on_progress(Progress {
+            received: resp.data.len(),
+            total: Some(resp.data.len()),
+        });
+    }
 
     let handler = HANDLERS.with(|m| m.borrow_mut().remove(&request_id));
     if let Some(h) = handler {
@@ -66,6 +242,165 @@ extern "C" fn http_trampoline(
     }
 }
 
+/// How much of a response has arrived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Body bytes received so far.
+    pub received: usize,
+    /// The body size from `Content-Length`, once the headers arrived; `None`
+    /// for chunked responses.
+    pub total: Option<usize>,
+}
+
+impl Progress {
+    /// `received / total` in `0.0..=1.0`, if the total is known.
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.received as f32 / total as f32).min(1.0)),
+            None => None,
+        }
+    }
+}
+
+fn header(id: FsNetworkRequestId, name: &str) -> Option<String> {
+    let section = CString::new(name.to_ascii_lowercase()).ok()?;
+    let value = unsafe {
+        let value = fsNetworkHttpRequestGetHeaderSection(id, section.as_ptr());
+        if value.is_null() {
+            return None;
+        }
+        std::ffi::CStr::from_ptr(value)
+            .to_string_lossy()
+            .into_owned()
+    };
+    // Either the bare value or the whole `Name: value` line.
+    let value = match value.split_once(':') {
+        Some((key, rest)) if key.trim().eq_ignore_ascii_case(name) => rest,
+        _ => &value,
+    };
+    Some(value.trim().to_string())
+}
+
+fn progress(id: FsNetworkRequestId) -> Progress {
+    let received = unsafe { fsNetworkHttpRequestGetDataSize(id) as usize };
+    let total = header(id, "content-length").and_then(|v| v.parse().ok());
+    Progress { received, total }
+}
+
+/// Number of requests still waiting for their completion callback.
+pub fn in_flight() -> usize {
+    HANDLERS.with(|m| m.borrow().len())
+}
+
+/// A request sent with [`http_request`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestHandle {
+    id: FsNetworkRequestId,
+}
+
+impl RequestHandle {
+    #[inline]
+    pub fn id(self) -> FsNetworkRequestId {
+        self.id
+    }
+
+    /// `true` until the callback has run or the request was canceled.
+    pub fn is_pending(self) -> bool {
+        HANDLERS.with(|m| m.borrow().contains_key(&self.id))
+    }
+
+    /// Abort the request and drop its callback without calling it. `false` if
+    /// it already completed or was canceled.
+    pub fn cancel(self) -> bool {
+        cancel(self.id)
+    }
+
+    /// How much of the response has arrived; `None` once the request is no
+    /// longer pending.
+    pub fn progress(self) -> Option<Progress> {
+        self.is_pending().then(|| progress(self.id))
+    }
+
+    /// Call `on_progress` from [`update`] whenever more of the response has
+    /// arrived, and once more with the full size before the completion
+    /// callback. `false` if the request is no longer pending.
+    pub fn on_progress(self, on_progress: impl FnMut(Progress) + 'static) -> bool {
+        if !self.is_pending() {
+            return false;
+        }
+        PROGRESS.with(|m| m.borrow_mut().insert(self.id, (0, Box::new(on_progress))));
+        true
+    }
+}
+
+fn cancel(id: FsNetworkRequestId) -> bool {
+    abort(id).is_some()
+}
+
+/// Cancel `id` in the sim and forget it, returning its callback and URL if it
+/// was still pending.
+fn abort(id: FsNetworkRequestId) -> Option<(Handler, String)> {
+    DEADLINES.with(|m| m.borrow_mut().remove(&id));
+    PROGRESS.with(|m| m.borrow_mut().remove(&id));
+    let handler = HANDLERS.with(|m| m.borrow_mut().remove(&id))?;
+    unsafe {
+        fsNetworkHttpCancelRequest(id);
+    }
+    Some((handler, take_url(id)))
+}
+
+/// Cancel every pending request, e.g. from `kill`. Returns how many were.
+pub fn cancel_all() -> usize {
+    let reading = upload::cancel_all();
+    let ids: Vec<_> = HANDLERS.with(|m| m.borrow().keys().copied().collect());
+    reading + ids.into_iter().filter(|&id| cancel(id)).count()
+}
+
+/// Cancel the requests past their [`HttpParams::timeout`] and run their
+/// callbacks with [`TIMEOUT_ERROR_CODE`], report progress and advance file
+/// uploads. Called by the export macros at the start of each update.
+pub fn update() {
+    let now = Instant::now();
+    let expired: Vec<_> = DEADLINES.with(|m| {
+        m.borrow()
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(id, _)| *id)
+            .collect()
+    });
+    for request_id in expired {
+        if let Some((h, url)) = abort(request_id) {
+            h(HttpResponse {
+                request_id,
+                url,
+                error_code: TIMEOUT_ERROR_CODE,
+                data: Vec::new(),
+            });
+        }
+    }
+
+    // Taken out while the callbacks run, which may start or cancel requests.
+    let mut watched = PROGRESS.with(|m| std::mem::take(&mut *m.borrow_mut()));
+    for (&id, (last, on_progress)) in watched.iter_mut() {
+        let now = progress(id);
+        if now.received != *last {
+            *last = now.received;
+            on_progress(now);
+        }
+    }
+    PROGRESS.with(|m| {
+        let mut m = m.borrow_mut();
+        for (id, entry) in watched {
+            if HANDLERS.with(|h| h.borrow().contains_key(&id)) {
+                m.entry(id).or_insert(entry);
+            }
+        }
+    });
+
+    upload::update();
+}
+
 struct OwnedFfiParams {
     url: CString,
     _post_field: Option<CString>,
@@ -139,8 +474,12 @@ fn keep_params_alive(id: FsNetworkRequestId, params: OwnedFfiParams) {
     PARAMS.with(|m| m.borrow_mut().insert(id, params));
 }
 
-fn drop_params(id: FsNetworkRequestId) {
-    PARAMS.with(|m| m.borrow_mut().remove(&id));
+/// Drop the params kept alive for `id`, returning its URL.
+fn take_url(id: FsNetworkRequestId) -> String {
+    PARAMS
+        .with(|m| m.borrow_mut().remove(&id))
+        .map(|p| p.url.to_string_lossy().into_owned())
+        .unwrap_or_default()
 }
 
 #[derive(Default)]
@@ -148,31 +487,73 @@ pub struct HttpParams {
     pub headers: Vec<String>,
     pub post_field: Option<String>,
     pub body: Vec<u8>,
+    /// Cancel the request if no response arrived this long after sending it,
+    /// and run the callback with [`TIMEOUT_ERROR_CODE`].
+    pub timeout: Option<Duration>,
 }
 
+/// Header carrying the real method of a [`Method::Delete`] or [`Method::Patch`]
+/// request, which the sim can only send as a POST.
+pub const METHOD_OVERRIDE_HEADER: &str = "X-HTTP-Method-Override";
+
+/// HTTP method of a request.
+///
+/// The sim only sends GET, POST and PUT. `Delete` and `Patch` go out as a POST
+/// with the real method in [`METHOD_OVERRIDE_HEADER`], which the server must
+/// honor (most frameworks can). `Head` goes out as a GET and the body is
+/// dropped before the callback sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Method {
     Get,
     Post,
     Put,
+    Delete,
+    Patch,
+    Head,
+}
+
+impl Method {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Patch => "PATCH",
+            Method::Head => "HEAD",
+        }
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 pub fn http_request(
     method: Method,
     url: &str,
-    params: HttpParams,
+    mut params: HttpParams,
     on_done: impl FnOnce(HttpResponse) + 'static,
-) -> NetResult<FsNetworkRequestId> {
-    let mut owned = OwnedFfiParams::new(url, params)?;
+) -> NetResult<RequestHandle> {
+    if matches!(method, Method::Delete | Method::Patch) {
+        params
+            .headers
+            .push(format!("{METHOD_OVERRIDE_HEADER}: {method}"));
+    }
+    let deadline = params.timeout.map(|timeout| Instant::now() + timeout);
+    let mut owned = OwnedFfiParams::new(url, params).map_err(|e| e.with_url(url))?;
 
     let id = unsafe {
         match method {
-            Method::Get => fsNetworkHttpRequestGet(
+            Method::Get | Method::Head => fsNetworkHttpRequestGet(
                 owned.url_ptr(),
                 owned.ffi_ptr(),
                 Some(http_trampoline),
                 std::ptr::null_mut(),
             ),
-            Method::Post => fsNetworkHttpRequestPost(
+            Method::Post | Method::Delete | Method::Patch => fsNetworkHttpRequestPost(
                 owned.url_ptr(),
                 owned.ffi_ptr(),
                 Some(http_trampoline),
@@ -187,8 +568,101 @@ pub fn http_request(
         }
     };
 
+    if id == 0 {
+        return Err(NetError::new(NetErrorKind::Rejected, url));
+    }
+
+    let handler: Handler = if method == Method::Head {
+        Box::new(move |mut resp: HttpResponse| {
+            resp.data.clear();
+            on_done(resp)
+        })
+    } else {
+        Box::new(on_done)
+    };
+
     keep_params_alive(id, owned);
-    HANDLERS.with(|m| m.borrow_mut().insert(id, Box::new(on_done)));
+    HANDLERS.with(|m| m.borrow_mut().insert(id, handler));
+    if let Some(deadline) = deadline {
+        DEADLINES.with(|m| m.borrow_mut().insert(id, deadline));
+    }
 
-    Ok(id)
+    Ok(RequestHandle { id })
+}
+
+/// [`http_request`] as a future, e.g. for a task on the
+/// [`executor`](crate::executor). The request is sent right away; dropping the
+/// future cancels it.
+pub fn http_request_async(method: Method, url: &str, params: HttpParams) -> HttpFuture {
+    let shared = Rc::new(RefCell::new(FutureState::default()));
+    let slot = Rc::clone(&shared);
+    let sent = http_request(method, url, params, move |resp| {
+        let mut s = slot.borrow_mut();
+        s.response = Some(resp);
+        if let Some(waker) = s.waker.take() {
+            waker.wake();
+        }
+    });
+    match sent {
+        Ok(handle) => HttpFuture {
+            handle: Some(handle),
+            error: None,
+            shared,
+        },
+        Err(e) => HttpFuture {
+            handle: None,
+            error: Some(e),
+            shared,
+        },
+    }
+}
+
+#[derive(Default)]
+struct FutureState {
+    response: Option<HttpResponse>,
+    waker: Option<Waker>,
+}
+
+/// Future returned by [`http_request_async`].
+pub struct HttpFuture {
+    handle: Option<RequestHandle>,
+    error: Option<NetError>,
+    shared: Rc<RefCell<FutureState>>,
+}
+
+impl HttpFuture {
+    /// The request, unless it couldn't be sent.
+    pub fn handle(&self) -> Option<RequestHandle> {
+        self.handle
+    }
+}
+
+impl Future for HttpFuture {
+    type Output = NetResult<HttpResponse>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        if let Some(e) = self.error.take() {
+            return Poll::Ready(Err(e));
+        }
+        let mut s = self.shared.borrow_mut();
+        match s.response.take() {
+            Some(resp) => {
+                drop(s);
+                self.handle = None;
+                Poll::Ready(Ok(resp))
+            }
+            None => {
+                s.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for HttpFuture {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle {
+            handle.cancel();
+        }
+    }
 }
@@ -0,0 +1,202 @@
+//! `multipart/form-data` bodies, as web forms upload files.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::{multipart::Multipart, request::Request};
+//!
+//! let log = b"12:01:07 takeoff EGLL\n".to_vec();
+//! let form = Multipart::new()
+//!     .text("aircraft", "A32NX")
+//!     .file("log", "flight.log", "text/plain", log);
+//!
+//! Request::post("https://example.com/api/logs")
+//!     .multipart(form)
+//!     .send(|resp| println!("upload: {}", resp.error_code))?;
+//! # Ok::<(), msfs::network::NetError>(())
+//! ```
+
+use std::{
+    cell::Cell,
+    hash::{DefaultHasher, Hash, Hasher},
+    time::SystemTime,
+};
+
+thread_local! {
+    static COUNTER: Cell<u64> = const { Cell::new(0) };
+}
+
+// Unpredictable enough that no payload contains it by accident; `finish`
+// checks anyway.
+fn new_boundary() -> String {
+    let n = COUNTER.with(|c| {
+        c.set(c.get() + 1);
+        c.get()
+    });
+    let mut hasher = DefaultHasher::new();
+    (SystemTime::now(), n).hash(&mut hasher);
+    format!("----infinity-rs-{:016x}{n:04x}", hasher.finish())
+}
+
+// Per the HTML spec: quotes and line breaks in names are percent-encoded.
+fn quote(s: &str) -> String {
+    s.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+struct Part {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    data: Vec<u8>,
+}
+
+/// A `multipart/form-data` body being built.
+pub struct Multipart {
+    boundary: String,
+    parts: Vec<Part>,
+}
+
+impl Default for Multipart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Multipart {
+    pub fn new() -> Self {
+        Self {
+            boundary: new_boundary(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Add a plain form field.
+    pub fn text(mut self, name: &str, value: &str) -> Self {
+        self.parts.push(Part {
+            name: name.to_string(),
+            filename: None,
+            content_type: None,
+            data: value.as_bytes().to_vec(),
+        });
+        self
+    }
+
+    /// Add a file field, e.g. `content_type` `application/json` or
+    /// `application/octet-stream`.
+    pub fn file(
+        mut self,
+        name: &str,
+        filename: &str,
+        content_type: &str,
+        data: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.parts.push(Part {
+            name: name.to_string(),
+            filename: Some(filename.to_string()),
+            content_type: Some(content_type.to_string()),
+            data: data.into(),
+        });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// The `Content-Type` header value, with the boundary.
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// The `Content-Type` header value and the body.
+    pub fn finish(mut self) -> (String, Vec<u8>) {
+        // A boundary must not occur in any part; a fresh one fixes that.
+        let occurs =
+            |boundary: &[u8], data: &[u8]| data.windows(boundary.len()).any(|w| w == boundary);
+        while self
+            .parts
+            .iter()
+            .any(|p| occurs(self.boundary.as_bytes(), &p.data))
+        {
+            self.boundary = new_boundary();
+        }
+
+        let mut body = Vec::new();
+        for part in &self.parts {
+            body.extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+            let mut disposition = format!("form-data; name=\"{}\"", quote(&part.name));
+            if let Some(filename) = &part.filename {
+                disposition.push_str(&format!("; filename=\"{}\"", quote(filename)));
+            }
+            body.extend_from_slice(format!("Content-Disposition: {disposition}\r\n").as_bytes());
+            if let Some(content_type) = &part.content_type {
+                let content_type = content_type.replace(['\r', '\n'], "");
+                body.extend_from_slice(format!("Content-Type: {content_type}\r\n").as_bytes());
+            }
+            body.extend_from_slice(b"\r\n");
+            body.extend_from_slice(&part.data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        (self.content_type(), body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn body_layout() {
+        let mut form = Multipart::new().text("aircraft", "A32NX").file(
+            "log",
+            "flight.log",
+            "text/plain",
+            b"line\n".to_vec(),
+        );
+        assert_eq!(form.len(), 2);
+        form.boundary = "XYZ".to_string();
+
+        let (content_type, body) = form.finish();
+        assert_eq!(content_type, "multipart/form-data; boundary=XYZ");
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--XYZ\r\n\
+             Content-Disposition: form-data; name=\"aircraft\"\r\n\
+             \r\n\
+             A32NX\r\n\
+             --XYZ\r\n\
+             Content-Disposition: form-data; name=\"log\"; filename=\"flight.log\"\r\n\
+             Content-Type: text/plain\r\n\
+             \r\n\
+             line\n\r\n\
+             --XYZ--\r\n"
+        );
+    }
+
+    #[test]
+    fn escapes_names_and_headers() {
+        let mut form = Multipart::new().file("a\"b", "x\r\ny.txt", "text/plain\r\nX-Evil: 1", "");
+        form.boundary = "B".to_string();
+        let body = String::from_utf8(form.finish().1).unwrap();
+        assert!(body.contains("name=\"a%22b\"; filename=\"x%0D%0Ay.txt\"\r\n"));
+        assert!(body.contains("Content-Type: text/plainX-Evil: 1\r\n"));
+    }
+
+    #[test]
+    fn boundary_avoids_payload() {
+        let mut form = Multipart::new().text("a", "before --collide after");
+        form.boundary = "collide".to_string();
+        let (content_type, body) = form.finish();
+        let boundary = content_type.rsplit_once('=').unwrap().1;
+        assert_ne!(boundary, "collide");
+        assert!(body.starts_with(format!("--{boundary}\r\n").as_bytes()));
+        assert!(Multipart::new().is_empty());
+        assert_ne!(Multipart::new().boundary, Multipart::new().boundary);
+    }
+}
@@ -0,0 +1,144 @@
+//! Fluent builder for [`http_request`].
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::request::Request;
+//! use std::time::Duration;
+//!
+//! Request::get("https://example.com/api/airports")
+//!     .header("Accept", "application/json")
+//!     .query("icao", "EGLL")
+//!     .timeout(Duration::from_secs(10))
+//!     .send(|resp| println!("{}", String::from_utf8_lossy(&resp.data)))?;
+//! # Ok::<(), msfs::network::NetError>(())
+//! ```
+
+use super::{multipart::Multipart, url::Url, *};
+
+/// An HTTP request being built; [`send`](Request::send) it or hand its parts
+/// to another API with [`build`](Request::build).
+pub struct Request {
+    method: Method,
+    url: Url,
+    params: HttpParams,
+    on_progress: Option<ProgressHandler>,
+}
+
+impl Request {
+    /// A request to `url`, a string or a [`Url`].
+    pub fn new(method: Method, url: impl Into<Url>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            params: HttpParams::default(),
+            on_progress: None,
+        }
+    }
+
+    pub fn get(url: impl Into<Url>) -> Self {
+        Self::new(Method::Get, url)
+    }
+
+    pub fn post(url: impl Into<Url>) -> Self {
+        Self::new(Method::Post, url)
+    }
+
+    pub fn put(url: impl Into<Url>) -> Self {
+        Self::new(Method::Put, url)
+    }
+
+    pub fn delete(url: impl Into<Url>) -> Self {
+        Self::new(Method::Delete, url)
+    }
+
+    pub fn patch(url: impl Into<Url>) -> Self {
+        Self::new(Method::Patch, url)
+    }
+
+    pub fn head(url: impl Into<Url>) -> Self {
+        Self::new(Method::Head, url)
+    }
+
+    /// Add a `name: value` header. Line breaks are removed so a value can't
+    /// inject further headers.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let clean = |s: &str| s.replace(['\r', '\n'], "").trim().to_string();
+        self.params
+            .headers
+            .push(format!("{}: {}", clean(name), clean(value)));
+        self
+    }
+
+    /// Append `key=value` to the query string, percent-encoded.
+    pub fn query(mut self, key: &str, value: impl std::fmt::Display) -> Self {
+        self.url = self.url.query(key, value);
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.params.body = body.into();
+        self
+    }
+
+    /// Send `form` as a `multipart/form-data` body, setting `Content-Type`.
+    pub fn multipart(self, form: Multipart) -> Self {
+        let (content_type, body) = form.finish();
+        self.header("Content-Type", &content_type).body(body)
+    }
+
+    /// Send `field` as the sim's POST field (form data) instead of a body.
+    pub fn post_field(mut self, field: &str) -> Self {
+        self.params.post_field = Some(field.to_string());
+        self
+    }
+
+    /// See [`HttpParams::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.params.timeout = Some(timeout);
+        self
+    }
+
+    /// See [`RequestHandle::on_progress`].
+    pub fn on_progress(mut self, on_progress: impl FnMut(Progress) + 'static) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// The URL with the query string.
+    pub fn url(&self) -> String {
+        self.url.to_string()
+    }
+
+    /// The method, final URL and parameters, e.g. for
+    /// [`StreamingRequest::start`](super::stream::StreamingRequest::start).
+    /// Drops any [`on_progress`](Self::on_progress) callback.
+    pub fn build(self) -> (Method, String, HttpParams) {
+        let url = self.url();
+        (self.method(), url, self.params)
+    }
+
+    /// Send the request; see [`http_request`].
+    pub fn send(
+        mut self,
+        on_done: impl FnOnce(HttpResponse) + 'static,
+    ) -> NetResult<RequestHandle> {
+        let on_progress = self.on_progress.take();
+        let (method, url, params) = self.build();
+        let handle = http_request(method, &url, params, on_done)?;
+        if let Some(on_progress) = on_progress {
+            handle.on_progress(on_progress);
+        }
+        Ok(handle)
+    }
+
+    /// Send the request; see [`http_request_async`]. Drops any
+    /// [`on_progress`](Self::on_progress) callback.
+    pub fn send_async(self) -> HttpFuture {
+        let (method, url, params) = self.build();
+        http_request_async(method, &url, params)
+    }
+}
@@ -0,0 +1,359 @@
+//! Incremental parsers for long-lived streaming responses.
+//!
+//! Companion services often push data as `text/event-stream` (server-sent events)
+//! or newline-delimited JSON over a chunked response that never really ends.
+//! [`StreamingRequest`] polls the bytes received so far each tick and feeds only the
+//! new ones through a [`StreamParser`], so messages surface as they arrive rather
+//! than when the connection closes.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::{HttpParams, Method};
+//! use msfs::network::stream::{SseParser, StreamingRequest};
+//!
+//! let mut feed = StreamingRequest::start(
+//!     Method::Get,
+//!     "http://localhost:8380/api/v1/events",
+//!     HttpParams::default(),
+//!     SseParser::new(),
+//! )?;
+//!
+//! // in update():
+//! feed.poll(|ev| println!("{:?}: {}", ev.event, ev.data));
+//! # Ok::<(), msfs::network::NetError>(())
+//! ```
+
+use super::*;
+use std::rc::Rc;
+
+/// Splits a byte stream into discrete messages. Partial input is buffered until
+/// the rest of the message arrives.
+pub trait StreamParser {
+    type Item;
+
+    fn feed(&mut self, bytes: &[u8]) -> Vec<Self::Item>;
+
+    /// Flush anything still buffered once the stream has closed.
+    fn finish(&mut self) -> Vec<Self::Item>;
+}
+
+/// Buffers bytes and yields complete lines with the trailing `\r` stripped.
+#[derive(Debug, Default)]
+struct LineBuffer {
+    buf: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buf.extend_from_slice(bytes);
+        let mut lines = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = self.buf.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+        lines
+    }
+
+    fn take_rest(&mut self) -> Option<String> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let rest = std::mem::take(&mut self.buf);
+        Some(
+            String::from_utf8_lossy(&rest)
+                .trim_end_matches('\r')
+                .to_string(),
+        )
+    }
+}
+
+/// One dispatched server-sent event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// `event:` field; `None` means the default `message` type.
+    pub event: Option<String>,
+    /// `data:` lines joined with `\n`.
+    pub data: String,
+    /// Last `id:` seen on the stream, carried over between events as the spec requires.
+    pub id: Option<String>,
+    /// `retry:` reconnection delay in milliseconds.
+    pub retry: Option<u32>,
+}
+
+/// `text/event-stream` parser.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    lines: LineBuffer,
+    event: Option<String>,
+    data: Vec<String>,
+    last_id: Option<String>,
+    retry: Option<u32>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Last event id received, for a `Last-Event-ID` header when reconnecting.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_id.as_deref()
+    }
+
+    fn line(&mut self, line: &str, out: &mut Vec<SseEvent>) {
+        if line.is_empty() {
+            self.dispatch(out);
+            return;
+        }
+        if line.starts_with(':') {
+            return;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((f, v)) => (f, v.strip_prefix(' ').unwrap_or(v)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" if !value.contains('\0') => self.last_id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(ms);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self, out: &mut Vec<SseEvent>) {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return;
+        }
+        out.push(SseEvent {
+            event,
+            data: std::mem::take(&mut self.data).join("\n"),
+            id: self.last_id.clone(),
+            retry: self.retry,
+        });
+    }
+}
+
+impl StreamParser for SseParser {
+    type Item = SseEvent;
+
+    fn feed(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        let mut out = Vec::new();
+        for line in self.lines.push(bytes) {
+            self.line(&line, &mut out);
+        }
+        out
+    }
+
+    fn finish(&mut self) -> Vec<SseEvent> {
+        let mut out = Vec::new();
+        if let Some(rest) = self.lines.take_rest() {
+            self.line(&rest, &mut out);
+        }
+        self.dispatch(&mut out);
+        out
+    }
+}
+
+/// Newline-delimited JSON parser. Yields each non-blank line as raw JSON text;
+/// decoding is left to the caller.
+#[derive(Debug, Default)]
+pub struct NdjsonParser {
+    lines: LineBuffer,
+}
+
+impl NdjsonParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StreamParser for NdjsonParser {
+    type Item = String;
+
+    fn feed(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.lines
+            .push(bytes)
+            .into_iter()
+            .filter(|l| !l.trim().is_empty())
+            .collect()
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        self.lines
+            .take_rest()
+            .filter(|l| !l.trim().is_empty())
+            .into_iter()
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamState {
+    Open,
+    /// The connection finished with this error code.
+    Closed(i32),
+    /// [`StreamingRequest::cancel`] closed the connection.
+    Canceled,
+}
+
+/// A request whose response body is parsed as it arrives.
+pub struct StreamingRequest<P: StreamParser> {
+    handle: RequestHandle,
+    parser: P,
+    offset: usize,
+    done: Rc<RefCell<Option<HttpResponse>>>,
+    state: StreamState,
+}
+
+impl<P: StreamParser> StreamingRequest<P> {
+    pub fn start(method: Method, url: &str, params: HttpParams, parser: P) -> NetResult<Self> {
+        let done = Rc::new(RefCell::new(None));
+        let slot = Rc::clone(&done);
+        let handle = http_request(method, url, params, move |resp| {
+            *slot.borrow_mut() = Some(resp);
+        })?;
+
+        Ok(Self {
+            handle,
+            parser,
+            offset: 0,
+            done,
+            state: StreamState::Open,
+        })
+    }
+
+    #[inline]
+    pub fn request_id(&self) -> FsNetworkRequestId {
+        self.handle.id()
+    }
+
+    /// Close the connection. The parser keeps whatever partial message it
+    /// had buffered.
+    pub fn cancel(&mut self) -> bool {
+        if self.state != StreamState::Open {
+            return false;
+        }
+        self.state = StreamState::Canceled;
+        self.handle.cancel()
+    }
+
+    #[inline]
+    pub fn state(&self) -> StreamState {
+        self.state
+    }
+
+    #[inline]
+    pub fn parser(&self) -> &P {
+        &self.parser
+    }
+
+    /// Parse newly received bytes and hand each complete message to `on_item`.
+    /// Call once per update tick.
+    pub fn poll(&mut self, mut on_item: impl FnMut(P::Item)) -> StreamState {
+        if self.state != StreamState::Open {
+            return self.state;
+        }
+
+        if let Some(resp) = self.done.borrow_mut().take() {
+            let rest = resp.data.get(self.offset..).unwrap_or_default();
+            self.offset = resp.data.len();
+            self.parser.feed(rest).into_iter().for_each(&mut on_item);
+            self.parser.finish().into_iter().for_each(&mut on_item);
+            self.state = StreamState::Closed(resp.error_code);
+            return self.state;
+        }
+
+        // The sim grows the response buffer as chunks arrive.
+        let chunk = unsafe {
+            let len = fsNetworkHttpRequestGetDataSize(self.handle.id()) as usize;
+            let ptr = fsNetworkHttpRequestGetData(self.handle.id());
+            if ptr.is_null() || len <= self.offset {
+                return self.state;
+            }
+            std::slice::from_raw_parts(ptr as *const u8, len)[self.offset..].to_vec()
+        };
+        self.offset += chunk.len();
+        self.parser.feed(&chunk).into_iter().for_each(on_item);
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_bytewise<P: StreamParser>(parser: &mut P, input: &[u8]) -> Vec<P::Item> {
+        let mut out = Vec::new();
+        for b in input {
+            out.extend(parser.feed(std::slice::from_ref(b)));
+        }
+        out.extend(parser.finish());
+        out
+    }
+
+    #[test]
+    fn sse_fields() {
+        let mut p = SseParser::new();
+        let events = p.feed(
+            b": comment\r\nevent: update\r\nid: 7\r\nretry: 500\r\ndata: a\r\ndata:b\r\n\r\n",
+        );
+        assert_eq!(
+            events,
+            vec![SseEvent {
+                event: Some("update".into()),
+                data: "a\nb".into(),
+                id: Some("7".into()),
+                retry: Some(500),
+            }]
+        );
+        assert_eq!(p.last_event_id(), Some("7"));
+
+        // The id carries over; event and data don't.
+        let events = p.feed(b"data: c\n\n");
+        assert_eq!(events[0].event, None);
+        assert_eq!(events[0].data, "c");
+        assert_eq!(events[0].id.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn sse_split_across_chunks() {
+        let input = "event: é\ndata: 1\n\ndata\n\n: no data\nevent: x\n\ndata: tail".as_bytes();
+        let events = feed_bytewise(&mut SseParser::new(), input);
+        let data: Vec<_> = events.iter().map(|e| e.data.as_str()).collect();
+        assert_eq!(data, ["1", "", "tail"]);
+        assert_eq!(events[0].event.as_deref(), Some("é"));
+        // `event: x` had no data, so it was dropped rather than leaking into `tail`.
+        assert_eq!(events[2].event, None);
+    }
+
+    #[test]
+    fn sse_ignores_bad_id_and_retry() {
+        let mut p = SseParser::new();
+        let events = p.feed(b"id: a\0b\nretry: soon\ndata: x\n\n");
+        assert_eq!(events[0].id, None);
+        assert_eq!(events[0].retry, None);
+    }
+
+    #[test]
+    fn ndjson_lines() {
+        let input = b"{\"a\":1}\r\n\n  \n{\"b\":[2,3]}\n{\"c\":3}";
+        let lines = feed_bytewise(&mut NdjsonParser::new(), input);
+        assert_eq!(lines, ["{\"a\":1}", "{\"b\":[2,3]}", "{\"c\":3}"]);
+
+        let mut p = NdjsonParser::new();
+        assert!(p.feed(b"{\"partial\"").is_empty());
+        assert_eq!(p.feed(b":1}\n"), ["{\"partial\":1}"]);
+        assert!(p.finish().is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+//! Uploading a `\work` file as a request body.
+//!
+//! fsNetwork can't stream a request body: it takes the whole body as one
+//! buffer. [`upload_file`] therefore reads the entire file into memory before
+//! sending, in [`UPLOAD_CHUNK_SIZE`] pieces, one per update, so no single read
+//! stalls the update on a large flight log. The pieces go straight into the
+//! body buffer, which is handed to the request without a copy.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::{HttpParams, Method, upload_file};
+//!
+//! let params = HttpParams {
+//!     headers: vec!["Content-Type: text/plain".to_string()],
+//!     ..Default::default()
+//! };
+//! upload_file("https://example.com/api/logs", "\\work/flight.log", Method::Post, params, |resp| {
+//!     match resp {
+//!         Ok(resp) => println!("upload: {}", resp.error_code),
+//!         Err(e) => println!("upload failed: {e}"),
+//!     }
+//! })?;
+//! # Ok::<(), msfs::network::NetError>(())
+//! ```
+
+use super::*;
+use crate::io::{self, File, OpenFlags};
+use std::cell::Cell;
+
+/// Bytes read from the file per update.
+pub const UPLOAD_CHUNK_SIZE: usize = 256 * 1024;
+
+type UploadHandler = Box<dyn FnOnce(NetResult<HttpResponse>) + 'static>;
+
+struct Upload {
+    url: String,
+    method: Method,
+    params: HttpParams,
+    file: Option<File>,
+    opened: Rc<Cell<bool>>,
+    // Shared with the read in flight, which the sim fills asynchronously.
+    body: Rc<RefCell<Vec<u8>>>,
+    size: Option<usize>,
+    read: usize,
+    reading: Option<Rc<Cell<Option<usize>>>>,
+    request: Option<RequestHandle>,
+    on_done: Option<UploadHandler>,
+}
+
+enum Step {
+    Wait,
+    Send(Vec<u8>),
+    Fail(IoError),
+}
+
+thread_local! {
+    static UPLOADS: RefCell<HashMap<u64, Upload>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<u64> = const { Cell::new(1) };
+}
+
+/// An upload started with [`upload_file`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UploadHandle {
+    id: u64,
+}
+
+impl UploadHandle {
+    /// `true` until the callback ran or the upload was canceled.
+    pub fn is_pending(self) -> bool {
+        UPLOADS.with(|m| m.borrow().contains_key(&self.id))
+    }
+
+    /// The request, once the file is read and it was sent.
+    pub fn request(self) -> Option<RequestHandle> {
+        UPLOADS.with(|m| m.borrow().get(&self.id).and_then(|u| u.request))
+    }
+
+    /// Stop reading or cancel the request, and drop the callback without
+    /// running it. `false` if the upload was no longer pending.
+    pub fn cancel(self) -> bool {
+        let Some(upload) = UPLOADS.with(|m| m.borrow_mut().remove(&self.id)) else {
+            return false;
+        };
+        if let Some(request) = upload.request {
+            request.cancel();
+        }
+        true
+    }
+}
+
+/// Send the file at `path`, e.g. `\work/flight.log`, as the body of a request
+/// to `url`, replacing `params.body`. `on_done` gets the response, or an error
+/// of kind [`NetErrorKind::File`] if the file couldn't be read.
+pub fn upload_file(
+    url: &str,
+    path: &str,
+    method: Method,
+    params: HttpParams,
+    on_done: impl FnOnce(NetResult<HttpResponse>) + 'static,
+) -> NetResult<UploadHandle> {
+    let opened = Rc::new(Cell::new(false));
+    let flag = Rc::clone(&opened);
+    let file = io::open(path, OpenFlags::RDONLY, move |file| {
+        flag.set(true);
+        // Another handle to the file kept in the upload; dropping it would
+        // close the file.
+        std::mem::forget(file);
+    })
+    .map_err(|e| NetError::io(e, url))?;
+
+    let id = NEXT_ID.with(|n| {
+        let id = n.get();
+        n.set(id + 1);
+        id
+    });
+    let upload = Upload {
+        url: url.to_string(),
+        method,
+        params,
+        file: Some(file),
+        opened,
+        body: Rc::new(RefCell::new(Vec::new())),
+        size: None,
+        read: 0,
+        reading: None,
+        request: None,
+        on_done: Some(Box::new(on_done)),
+    };
+    UPLOADS.with(|m| m.borrow_mut().insert(id, upload));
+    Ok(UploadHandle { id })
+}
+
+/// Drop every upload still reading its file. Returns how many were.
+pub(super) fn cancel_all() -> usize {
+    let uploads = UPLOADS.with(|m| std::mem::take(&mut *m.borrow_mut()));
+    uploads.values().filter(|u| u.request.is_none()).count()
+}
+
+/// Read the next chunk of every upload and send the finished ones.
+pub(super) fn update() {
+    let ids: Vec<_> = UPLOADS.with(|m| m.borrow().keys().copied().collect());
+    for id in ids {
+        // Out of the map while it runs, so callbacks can start or cancel
+        // uploads.
+        let Some(mut upload) = UPLOADS.with(|m| m.borrow_mut().remove(&id)) else {
+            continue;
+        };
+        match advance(&mut upload) {
+            Step::Wait => {
+                UPLOADS.with(|m| m.borrow_mut().insert(id, upload));
+            }
+            Step::Send(body) => send(id, upload, body),
+            Step::Fail(e) => {
+                if let Some(on_done) = upload.on_done.take() {
+                    on_done(Err(NetError::io(e, &upload.url)));
+                }
+            }
+        }
+    }
+}
+
+fn advance(upload: &mut Upload) -> Step {
+    let Some(file) = &upload.file else {
+        // Sent; waiting for the response.
+        return Step::Wait;
+    };
+    if file.has_error() {
+        return Step::Fail(file.last_error().unwrap_or(IoError::OperationImpossible));
+    }
+    if !upload.opened.get() {
+        return Step::Wait;
+    }
+
+    if let Some(reading) = &upload.reading {
+        match reading.get() {
+            None => return Step::Wait,
+            // The file shrank while it was read.
+            Some(0) => return Step::Fail(IoError::PartialReadImpossible),
+            Some(n) => {
+                upload.read += n;
+                upload.reading = None;
+            }
+        }
+    }
+
+    let size = match upload.size {
+        Some(size) => size,
+        None => {
+            let size = file.file_size();
+            if size > i32::MAX as u64 {
+                return Step::Fail(IoError::OperationImpossible);
+            }
+            upload.body.borrow_mut().resize(size as usize, 0);
+            *upload.size.insert(size as usize)
+        }
+    };
+    if upload.read >= size {
+        // The last read's callback has dropped its handle to the buffer by
+        // now; if not, wait for it rather than copy the body.
+        let body = std::mem::take(&mut upload.body);
+        return match Rc::try_unwrap(body) {
+            Ok(body) => Step::Send(body.into_inner()),
+            Err(body) => {
+                upload.body = body;
+                Step::Wait
+            }
+        };
+    }
+
+    let len = (size - upload.read).min(UPLOAD_CHUNK_SIZE);
+    let done = Rc::new(Cell::new(None));
+    let slot = Rc::clone(&done);
+    // Keeps the buffer alive until the sim wrote into it, even if the upload
+    // is canceled first.
+    let keep = Rc::clone(&upload.body);
+    let start = upload.read;
+    let sent = file.read(
+        &mut upload.body.borrow_mut()[start..start + len],
+        start as i32,
+        len as i32,
+        move |data, _offset| {
+            drop(keep);
+            slot.set(Some(data.len()));
+        },
+    );
+    match sent {
+        Ok(()) => {
+            upload.reading = Some(done);
+            Step::Wait
+        }
+        Err(e) => Step::Fail(e),
+    }
+}
+
+fn send(id: u64, mut upload: Upload, body: Vec<u8>) {
+    if let Some(file) = upload.file.take() {
+        let _ = file.close();
+    }
+    upload.params.body = body;
+    let params = std::mem::take(&mut upload.params);
+
+    // Moved into the request, but still needed if it can't be sent.
+    let on_done = Rc::new(RefCell::new(upload.on_done.take()));
+    let slot = Rc::clone(&on_done);
+    let sent = http_request(upload.method, &upload.url, params, move |resp| {
+        UPLOADS.with(|m| m.borrow_mut().remove(&id));
+        if let Some(on_done) = slot.borrow_mut().take() {
+            on_done(Ok(resp));
+        }
+    });
+    match sent {
+        Ok(request) => {
+            upload.request = Some(request);
+            UPLOADS.with(|m| m.borrow_mut().insert(id, upload));
+        }
+        Err(e) => {
+            if let Some(on_done) = on_done.borrow_mut().take() {
+                on_done(Err(e));
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! URL building with percent-encoding.
+//!
+//! Hand-concatenated URLs break on anything outside plain ASCII letters: a
+//! space or `+` in a route string, a `/` in a name. [`Url`] encodes every
+//! path segment and query parameter added to it.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::network::url::Url;
+//!
+//! let url = Url::new("https://example.com/api")
+//!     .segment("routes")
+//!     .segment("EGLL/KJFK")
+//!     .query("route", "DVR UL9 KONAN+1");
+//! assert_eq!(
+//!     url.to_string(),
+//!     "https://example.com/api/routes/EGLL%2FKJFK?route=DVR%20UL9%20KONAN%2B1",
+//! );
+//! ```
+
+use std::fmt;
+
+/// Percent-encode everything but RFC 3986 unreserved characters, so the
+/// result is safe in a path segment, query or fragment.
+pub fn encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+/// Undo [`encode`], also reading `+` as a space as forms do. `None` if the
+/// result isn't UTF-8.
+pub fn decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            // `from_str_radix` would also take a sign, as in `%+1`.
+            .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// A URL being built. The base passed to [`new`](Url::new) is kept as is;
+/// everything added afterwards is encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    path: String,
+    raw_query: String,
+    query: Vec<(String, String)>,
+    fragment: Option<String>,
+}
+
+impl Url {
+    /// Start from `base`, e.g. `https://example.com/api`, which may already
+    /// have a query string or fragment.
+    pub fn new(base: &str) -> Self {
+        let (rest, fragment) = match base.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment.to_string())),
+            None => (base, None),
+        };
+        let (path, raw_query) = rest.split_once('?').unwrap_or((rest, ""));
+        Self {
+            path: path.to_string(),
+            raw_query: raw_query.trim_matches('&').to_string(),
+            query: Vec::new(),
+            fragment,
+        }
+    }
+
+    /// Append `/segment` to the path; a `/` inside it is encoded too.
+    pub fn segment(mut self, segment: &str) -> Self {
+        if !self.path.ends_with('/') {
+            self.path.push('/');
+        }
+        self.path.push_str(&encode(segment));
+        self
+    }
+
+    /// Append each of `segments`.
+    pub fn segments<S: AsRef<str>>(self, segments: impl IntoIterator<Item = S>) -> Self {
+        segments
+            .into_iter()
+            .fold(self, |url, s| url.segment(s.as_ref()))
+    }
+
+    /// Append `key=value` to the query string.
+    pub fn query(mut self, key: &str, value: impl fmt::Display) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Append `key=value` if `value` is `Some`.
+    pub fn query_opt(self, key: &str, value: Option<impl fmt::Display>) -> Self {
+        match value {
+            Some(value) => self.query(key, value),
+            None => self,
+        }
+    }
+
+    /// Replace the `#fragment`.
+    pub fn fragment(mut self, fragment: &str) -> Self {
+        self.fragment = Some(encode(fragment));
+        self
+    }
+
+    /// The added query parameters, not encoded.
+    pub fn query_pairs(&self) -> &[(String, String)] {
+        &self.query
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)?;
+        let mut sep = '?';
+        if !self.raw_query.is_empty() {
+            write!(f, "?{}", self.raw_query)?;
+            sep = '&';
+        }
+        for (key, value) in &self.query {
+            write!(f, "{sep}{}={}", encode(key), encode(value))?;
+            sep = '&';
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{fragment}")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Url {
+    fn from(base: &str) -> Self {
+        Url::new(base)
+    }
+}
+
+impl From<String> for Url {
+    fn from(base: String) -> Self {
+        Url::new(&base)
+    }
+}
+
+impl From<&String> for Url {
+    fn from(base: &String) -> Self {
+        Url::new(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_reserved_and_unicode() {
+        assert_eq!(encode("AZaz09-._~"), "AZaz09-._~");
+        assert_eq!(
+            encode("a b+c/d?e&f=g#h%"),
+            "a%20b%2Bc%2Fd%3Fe%26f%3Dg%23h%25"
+        );
+        assert_eq!(encode("é"), "%C3%A9");
+        assert_eq!(encode(""), "");
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        for s in ["a b+c/d", "é ü", "100%", "%%"] {
+            assert_eq!(decode(&encode(s)).as_deref(), Some(s));
+        }
+        assert_eq!(decode("a+b%2b").as_deref(), Some("a b+"));
+        assert_eq!(decode("%c3%a9").as_deref(), Some("é"));
+        // Malformed escapes pass through.
+        assert_eq!(decode("50%").as_deref(), Some("50%"));
+        assert_eq!(decode("%zz%+1%-1").as_deref(), Some("%zz% 1%-1"));
+        assert_eq!(decode("%C3"), None);
+    }
+
+    #[test]
+    fn builds_urls() {
+        let url = Url::new("https://example.com/api/?v=1&#top")
+            .segments(["a b", "c/d"])
+            .query("q", "x&y")
+            .query_opt("skip", None::<u32>)
+            .query_opt("n", Some(2));
+        assert_eq!(
+            url.to_string(),
+            "https://example.com/api/a%20b/c%2Fd?v=1&q=x%26y&n=2#top"
+        );
+        assert_eq!(url.query_pairs()[0], ("q".to_string(), "x&y".to_string()));
+
+        let url = Url::new("http://host").fragment("a b");
+        assert_eq!(url.to_string(), "http://host#a%20b");
+    }
+}
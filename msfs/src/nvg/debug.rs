@@ -0,0 +1,171 @@
+use super::{Align, Color, NvgContext};
+use crate::vars::{LVar, Var, VarKind, VarResult};
+use std::collections::VecDeque;
+
+const HISTORY: usize = 120;
+const WIDTH: f32 = 260.0;
+const LINE: f32 = 16.0;
+const GRAPH_HEIGHT: f32 = 48.0;
+/// Graph ceiling in ms; 33ms (30 fps) sits at two thirds of the height.
+const GRAPH_MAX_MS: f32 = 50.0;
+
+type Watch = (String, Box<dyn Fn() -> Option<f64>>);
+
+/// Debug HUD drawn on top of a gauge: FPS, frame-time graph, memory and
+/// in-flight IO/network counts, plus a list of watched values.
+///
+/// Hidden until the toggle LVar is non-zero, so it can be flipped from the
+/// dev-mode watch window without a rebuild.
+///
+/// ```ignore
+/// let mut overlay = DebugOverlay::new("L:INFINITY_PFD_DEBUG")?.with_font(font);
+/// overlay.watch_var("IAS", AVar::new("A:AIRSPEED INDICATED", "knots")?);
+///
+/// // in update():
+/// overlay.frame(dt);
+///
+/// // at the end of the nvg frame:
+/// overlay.draw(nvg, 8.0, 8.0);
+/// ```
+pub struct DebugOverlay {
+    toggle: LVar,
+    font: Option<i32>,
+    frame_ms: VecDeque<f32>,
+    watches: Vec<Watch>,
+}
+
+impl DebugOverlay {
+    pub fn new(toggle_lvar: &str) -> VarResult<Self> {
+        Ok(Self {
+            toggle: LVar::new(toggle_lvar, "Bool")?,
+            font: None,
+            frame_ms: VecDeque::with_capacity(HISTORY),
+            watches: Vec::new(),
+        })
+    }
+
+    /// Font used for the text rows. Without one only the graph is drawn.
+    pub fn with_font(mut self, font: Option<i32>) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Watch an arbitrary value; `None` renders as `--`.
+    pub fn watch(&mut self, label: &str, read: impl Fn() -> Option<f64> + 'static) {
+        self.watches.push((label.to_string(), Box::new(read)));
+    }
+
+    pub fn watch_var<K: VarKind + 'static>(&mut self, label: &str, var: Var<K>) {
+        self.watch(label, move || var.get().ok());
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.toggle.get().unwrap_or(0.0) != 0.0
+    }
+
+    /// Record a frame of `dt` seconds. Call once per tick.
+    pub fn frame(&mut self, dt: f32) {
+        if self.frame_ms.len() == HISTORY {
+            self.frame_ms.pop_front();
+        }
+        self.frame_ms.push_back(dt * 1000.0);
+    }
+
+    /// Draw at `(x, y)` in the current frame. Does nothing while disabled.
+    pub fn draw(&self, nvg: &NvgContext, x: f32, y: f32) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let rows = self.rows();
+        let text_height = if self.font.is_some() {
+            rows.len() as f32 * LINE
+        } else {
+            0.0
+        };
+        let height = 8.0 + GRAPH_HEIGHT + 8.0 + text_height;
+
+        nvg.scoped(|nvg| {
+            nvg.reset_scissor();
+            nvg.begin_path();
+            nvg.rounded_rect(x, y, WIDTH, height, 4.0);
+            nvg.fill_color(Color::rgba(0, 0, 0, 180));
+            nvg.fill();
+
+            self.draw_graph(nvg, x + 8.0, y + 8.0, WIDTH - 16.0, GRAPH_HEIGHT);
+
+            if let Some(font) = self.font {
+                nvg.font_face_id(font);
+                nvg.font_size(14.0);
+                nvg.text_align(Align::LEFT | Align::TOP);
+                nvg.fill_color(Color::WHITE);
+                let mut ty = y + 8.0 + GRAPH_HEIGHT + 8.0;
+                for row in &rows {
+                    nvg.text(x + 8.0, ty, row);
+                    ty += LINE;
+                }
+            }
+        });
+    }
+
+    fn rows(&self) -> Vec<String> {
+        let avg = if self.frame_ms.is_empty() {
+            0.0
+        } else {
+            self.frame_ms.iter().sum::<f32>() / self.frame_ms.len() as f32
+        };
+        let max = self.frame_ms.iter().copied().fold(0.0, f32::max);
+        let fps = if avg > 0.0 { 1000.0 / avg } else { 0.0 };
+
+        let mut rows = vec![
+            format!("{fps:5.1} fps  {avg:5.2} ms  (max {max:5.2})"),
+            format!(
+                "mem {} KB  io {}  net {}",
+                crate::metrics::memory_bytes() / 1024,
+                crate::io::in_flight(),
+                crate::network::in_flight()
+            ),
+        ];
+        rows.extend(self.watches.iter().map(|(label, read)| match read() {
+            Some(v) => format!("{label}: {v:.3}"),
+            None => format!("{label}: --"),
+        }));
+        rows
+    }
+
+    fn draw_graph(&self, nvg: &NvgContext, x: f32, y: f32, w: f32, h: f32) {
+        nvg.begin_path();
+        nvg.rect(x, y, w, h);
+        nvg.fill_color(Color::rgba(255, 255, 255, 24));
+        nvg.fill();
+
+        // 30 fps reference line.
+        let ref_y = y + h - h * (33.3 / GRAPH_MAX_MS);
+        nvg.begin_path();
+        nvg.move_to(x, ref_y);
+        nvg.line_to(x + w, ref_y);
+        nvg.stroke_color(Color::rgba(255, 80, 80, 120));
+        nvg.stroke_width(1.0);
+        nvg.stroke();
+
+        if self.frame_ms.len() < 2 {
+            return;
+        }
+
+        let step = w / (HISTORY - 1) as f32;
+        let start = x + w - step * (self.frame_ms.len() - 1) as f32;
+        nvg.begin_path();
+        for (i, ms) in self.frame_ms.iter().enumerate() {
+            let px = start + step * i as f32;
+            let py = y + h - h * (ms / GRAPH_MAX_MS).min(1.0);
+            if i == 0 {
+                nvg.move_to(px, py);
+            } else {
+                nvg.line_to(px, py);
+            }
+        }
+        nvg.stroke_color(Color::GREEN);
+        nvg.stroke_width(1.5);
+        nvg.stroke();
+    }
+}
@@ -1,5 +1,6 @@
 ﻿mod color;
 mod context;
+mod debug;
 mod enums;
 mod paint;
 mod path;
@@ -10,6 +11,7 @@ mod transform;
 
 pub use color::Color;
 pub use context::NvgContext;
+pub use debug::DebugOverlay;
 pub use enums::*;
 pub use paint::{FillStyle, Gradient, ImagePattern};
 pub use path::PathBuilder;
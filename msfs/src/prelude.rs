@@ -1,9 +1,15 @@
 pub use crate::context::Context;
-pub use crate::modules::{Gauge, System};
+pub use crate::modules::{Gauge, PanelService, System};
 
-pub use crate::comm_bus::{BroadcastFlags, Subscription, call as commbus_call};
+#[allow(deprecated)]
+pub use crate::comm_bus::call as commbus_call;
+pub use crate::comm_bus::{
+    BroadcastFlags, Subscription, SubscriptionSet, try_call as try_commbus_call,
+};
 pub use crate::io::*;
-pub use crate::network::{HttpParams, Method, http_request};
+pub use crate::network::{HttpParams, Method, RequestHandle, http_request};
 pub use crate::types::{GaugeDraw, GaugeInstall, SystemInstall};
 pub use crate::vars::a_var::AVar;
+pub use crate::vars::e_var::EVar;
 pub use crate::vars::l_var::LVar;
+pub use crate::vars::o_var::OVar;
@@ -0,0 +1,298 @@
+//! SimConnect plumbing shared by the features that talk to the sim through a
+//! client.
+//!
+//! Features that only need a short-lived client of their own use [`open`]
+//! and [`dispatch`]. Long-lived features share the module's [`Client`]
+//! instead of opening one each: ids come from one counter so no two features
+//! use the same group, event, definition or request id. Each [`Inbox`]
+//! [claims](Inbox::claim) the ids it allocated, and every message the client
+//! receives is queued in the inboxes that claimed its group, event or request
+//! id.
+
+use crate::sys::{
+    DWORD, HANDLE, SIMCONNECT_RECV, SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT, SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_EX1,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE, SimConnect_CallDispatch,
+    SimConnect_Open,
+};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    ffi::CString,
+    os::raw::c_void,
+    rc::{Rc, Weak},
+};
+
+pub(crate) fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+pub(crate) fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+pub(crate) fn read_f64(bytes: &[u8], at: usize) -> Option<f64> {
+    Some(f64::from_le_bytes(bytes.get(at..at + 8)?.try_into().ok()?))
+}
+
+/// The `dwID` of a message, i.e. its `SIMCONNECT_RECV_ID`.
+pub(crate) fn recv_id(bytes: &[u8]) -> Option<u32> {
+    read_u32(bytes, 8)
+}
+
+/// Open a client named `name`. `None` if SimConnect is unavailable.
+pub(crate) fn open(name: &str) -> Option<HANDLE> {
+    let name = CString::new(name).ok()?;
+    let mut handle: HANDLE = core::ptr::null_mut();
+    let opened = unsafe {
+        SimConnect_Open(
+            &mut handle,
+            name.as_ptr(),
+            core::ptr::null_mut(),
+            0,
+            core::ptr::null_mut(),
+            0,
+        ) >= 0
+    };
+    opened.then_some(handle)
+}
+
+/// Hand every pending message on `handle` to `f`.
+pub(crate) fn dispatch(handle: HANDLE, mut f: impl FnMut(&[u8])) {
+    let mut f: &mut dyn FnMut(&[u8]) = &mut f;
+    unsafe {
+        SimConnect_CallDispatch(
+            handle,
+            Some(trampoline),
+            &mut f as *mut &mut dyn FnMut(&[u8]) as *mut c_void,
+        );
+    }
+}
+
+unsafe extern "C" fn trampoline(data: *mut SIMCONNECT_RECV, size: DWORD, ctx: *mut c_void) {
+    if data.is_null() || ctx.is_null() {
+        return;
+    }
+    let f = unsafe { &mut *(ctx as *mut &mut dyn FnMut(&[u8])) };
+    let bytes = unsafe { std::slice::from_raw_parts(data as *const u8, size as usize) };
+    f(bytes);
+}
+
+/// Messages kept per inbox whose owner stopped receiving; older ones are
+/// dropped.
+pub(crate) const INBOX_LIMIT: usize = 256;
+
+/// The group, event or request ids `bytes` belongs to. Event messages carry
+/// uGroupID and uEventID at 12 and 16, data messages dwRequestID at 12.
+fn owner_ids(bytes: &[u8]) -> [Option<u32>; 2] {
+    let Some(id) = recv_id(bytes) else {
+        return [None; 2];
+    };
+    let id = id as i32;
+    if id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as i32
+        || id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_EX1 as i32
+        || id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT_FILENAME as i32
+    {
+        [read_u32(bytes, 12), read_u32(bytes, 16)]
+    } else if id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA as i32
+        || id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA as i32
+        || id == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE as i32
+    {
+        [read_u32(bytes, 12), None]
+    } else {
+        [None; 2]
+    }
+}
+
+#[derive(Default)]
+struct Route {
+    // Group, event and request ids.
+    ids: Vec<u32>,
+    // `SIMCONNECT_RECV_ID`s of messages without an owner id, e.g. the
+    // controller list.
+    kinds: Vec<u32>,
+    queue: VecDeque<Rc<[u8]>>,
+}
+
+impl Route {
+    fn wants(&self, bytes: &[u8]) -> bool {
+        owner_ids(bytes)
+            .into_iter()
+            .flatten()
+            .any(|id| self.ids.contains(&id))
+            || recv_id(bytes).is_some_and(|kind| self.kinds.contains(&kind))
+    }
+
+    fn push(&mut self, bytes: &Rc<[u8]>) {
+        if self.wants(bytes) {
+            if self.queue.len() == INBOX_LIMIT {
+                self.queue.pop_front();
+            }
+            self.queue.push_back(Rc::clone(bytes));
+        }
+    }
+}
+
+/// The module's shared client.
+pub(crate) struct Client {
+    handle: HANDLE,
+    next_id: DWORD,
+    inboxes: Vec<Weak<RefCell<Route>>>,
+}
+
+impl Client {
+    pub(crate) fn handle(&self) -> HANDLE {
+        self.handle
+    }
+
+    /// A group, client event, data definition, client data or request id no
+    /// other feature uses.
+    pub(crate) fn next_id(&mut self) -> DWORD {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+thread_local! {
+    static CLIENT: RefCell<Option<Client>> = const { RefCell::new(None) };
+}
+
+/// Run `f` on the shared client, opening it on first use. `None` if
+/// SimConnect is unavailable.
+pub(crate) fn with_client<R>(f: impl FnOnce(&mut Client) -> R) -> Option<R> {
+    CLIENT.with(|c| {
+        let mut c = c.borrow_mut();
+        if c.is_none() {
+            *c = Some(Client {
+                handle: open("infinity-rs")?,
+                next_id: 1,
+                inboxes: Vec::new(),
+            });
+        }
+        c.as_mut().map(f)
+    })
+}
+
+/// Like [`with_client`], but `None` instead of opening the client, and while
+/// the client is in use. For `Drop` impls.
+pub(crate) fn try_with_client<R>(f: impl FnOnce(&mut Client) -> R) -> Option<R> {
+    CLIENT
+        .try_with(|c| c.try_borrow_mut().ok()?.as_mut().map(f))
+        .ok()
+        .flatten()
+}
+
+/// Receive pending messages on the shared client into the inboxes that
+/// claimed them. Does nothing until the client is open.
+pub(crate) fn pump() {
+    let (messages, inboxes) = CLIENT.with(|c| {
+        let mut c = c.borrow_mut();
+        let Some(client) = c.as_mut() else {
+            return (Vec::new(), Vec::new());
+        };
+        let mut messages: Vec<Rc<[u8]>> = Vec::new();
+        dispatch(client.handle, |bytes| messages.push(bytes.into()));
+        client.inboxes.retain(|i| i.strong_count() > 0);
+        let inboxes: Vec<_> = client.inboxes.iter().filter_map(Weak::upgrade).collect();
+        (messages, inboxes)
+    });
+    if messages.is_empty() {
+        return;
+    }
+    for inbox in inboxes {
+        let mut inbox = inbox.borrow_mut();
+        for bytes in &messages {
+            inbox.push(bytes);
+        }
+    }
+}
+
+/// A feature's copy of the messages received on the shared client for the
+/// ids it claimed. At most [`INBOX_LIMIT`] are kept between receives.
+pub(crate) struct Inbox {
+    route: Rc<RefCell<Route>>,
+}
+
+impl Inbox {
+    /// Start collecting messages. `None` if SimConnect is unavailable.
+    pub(crate) fn new() -> Option<Self> {
+        let route = Rc::new(RefCell::new(Route::default()));
+        with_client(|client| client.inboxes.push(Rc::downgrade(&route)))?;
+        Some(Self { route })
+    }
+
+    /// Receive messages for the group, event or request `id`.
+    pub(crate) fn claim(&self, id: DWORD) {
+        self.route.borrow_mut().ids.push(id as u32);
+    }
+
+    /// Stop receiving messages for `id`.
+    pub(crate) fn release(&self, id: DWORD) {
+        self.route.borrow_mut().ids.retain(|&i| i != id as u32);
+    }
+
+    /// Receive every message of `kind`, a `SIMCONNECT_RECV_ID`, for messages
+    /// that carry no group, event or request id.
+    pub(crate) fn claim_kind(&self, kind: u32) {
+        self.route.borrow_mut().kinds.push(kind);
+    }
+
+    /// [`pump`], then take everything received since the last call.
+    pub(crate) fn receive(&self) -> Vec<Rc<[u8]>> {
+        pump();
+        self.route.borrow_mut().queue.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION;
+
+    fn message(kind: u32, at_12: u32, at_16: u32) -> Rc<[u8]> {
+        let mut bytes = vec![0; 24];
+        bytes[8..12].copy_from_slice(&kind.to_le_bytes());
+        bytes[12..16].copy_from_slice(&at_12.to_le_bytes());
+        bytes[16..20].copy_from_slice(&at_16.to_le_bytes());
+        bytes.into()
+    }
+
+    #[test]
+    fn routes_by_owner_id() {
+        let event = SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as u32;
+        let data = SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_CLIENT_DATA as u32;
+        let exception = SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION as u32;
+        let mut route = Route {
+            ids: vec![3, 7],
+            ..Route::default()
+        };
+        // Group 3, event 7, request 7.
+        assert!(route.wants(&message(event, 3, 99)));
+        assert!(route.wants(&message(event, 99, 7)));
+        assert!(route.wants(&message(data, 7, 0)));
+        // A data message's offset 16 isn't an id.
+        assert!(!route.wants(&message(data, 99, 3)));
+        assert!(!route.wants(&message(exception, 3, 7)));
+
+        route.kinds.push(exception);
+        assert!(route.wants(&message(exception, 0, 0)));
+    }
+
+    #[test]
+    fn queue_is_capped() {
+        let event = SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EVENT as u32;
+        let mut route = Route {
+            ids: vec![1],
+            ..Route::default()
+        };
+        for n in 0..INBOX_LIMIT as u32 + 10 {
+            route.push(&message(event, 1, n));
+        }
+        route.push(&message(event, 2, 0));
+        assert_eq!(route.queue.len(), INBOX_LIMIT);
+        assert_eq!(read_u32(&route.queue[0], 16), Some(10));
+    }
+}
@@ -0,0 +1,99 @@
+//! Sound triggering from WASM.
+//!
+//! WASM modules have no direct Wwise API; sounds are driven through LVars that the
+//! aircraft's `sound.xml` binds to Wwise events and RTPCs. This module wraps the three
+//! usual patterns:
+//!
+//! - [`SoundTrigger`]: one-shot sounds (clicks, chimes). The LVar pulses to 1 for one
+//!   tick, so bind the event to the LVar reaching 1.
+//! - [`SoundLoop`]: looping sounds (aural warnings) bound to a boolean LVar.
+//! - [`Rtpc`]: a numeric LVar used as an RTPC (pitch, volume, blend).
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::sound::{SoundLoop, SoundTrigger};
+//!
+//! let mut click = SoundTrigger::new("L:INFINITY_SND_CLICK")?;
+//! let stall = SoundLoop::new("L:INFINITY_SND_STALL_HORN")?;
+//!
+//! // in update():
+//! # let (button_pressed, aoa, critical_aoa) = (true, 12.0, 15.0);
+//! click.update();
+//! if button_pressed { click.fire(); }
+//! stall.set_playing(aoa > critical_aoa);
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use crate::vars::{LVar, VarResult};
+
+pub struct SoundTrigger {
+    var: LVar,
+    armed: bool,
+}
+
+impl SoundTrigger {
+    pub fn new(lvar: &str) -> VarResult<Self> {
+        let var = LVar::new(lvar, "Number")?;
+        var.set(0.0)?;
+        Ok(Self { var, armed: false })
+    }
+
+    /// Pulse the LVar to 1. It is reset to 0 by the next [`update`](Self::update).
+    pub fn fire(&mut self) {
+        if self.var.set(1.0).is_ok() {
+            self.armed = true;
+        }
+    }
+
+    /// Call once per tick so consecutive `fire` calls produce separate pulses.
+    pub fn update(&mut self) {
+        if self.armed {
+            self.armed = false;
+            let _ = self.var.set(0.0);
+        }
+    }
+}
+
+pub struct SoundLoop {
+    var: LVar,
+}
+
+impl SoundLoop {
+    pub fn new(lvar: &str) -> VarResult<Self> {
+        Ok(Self {
+            var: LVar::new(lvar, "Bool")?,
+        })
+    }
+
+    pub fn set_playing(&self, playing: bool) {
+        let _ = self.var.set(if playing { 1.0 } else { 0.0 });
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.var.get().unwrap_or(0.0) >= 0.5
+    }
+}
+
+pub struct Rtpc {
+    var: LVar,
+    last: Option<f64>,
+}
+
+impl Rtpc {
+    pub fn new(lvar: &str) -> VarResult<Self> {
+        Ok(Self {
+            var: LVar::new(lvar, "Number")?,
+            last: None,
+        })
+    }
+
+    /// Write the RTPC value. Unchanged values are skipped.
+    pub fn set(&mut self, value: f64) {
+        if self.last == Some(value) {
+            return;
+        }
+        if self.var.set(value).is_ok() {
+            self.last = Some(value);
+        }
+    }
+}
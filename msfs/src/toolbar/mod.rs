@@ -0,0 +1,193 @@
+//! Companion protocol for in-game toolbar panels (EFB-style add-ons).
+//!
+//! Every event lives under a namespace, e.g. `infinity.efb`:
+//!
+//! | Event | Direction | Payload |
+//! |---|---|---|
+//! | `<ns>/ready` | panel → wasm | panel protocol version (text), sent when the panel opens |
+//! | `<ns>/hello` | wasm → panel | `"<PROTOCOL_VERSION> <app version>"`, reply to `ready` |
+//! | `<ns>/closed` | panel → wasm | empty, sent when the panel closes |
+//! | `<ns>/state` | wasm → panel | the state struct, encoded by [`CommBusMessage::encode`] |
+//! | `<ns>/cmd` | panel → wasm | `"<command> <args>"` (args optional) |
+//!
+//! The state is a struct deriving `CommBusMessage`, so the panel can use its
+//! generated TypeScript interface.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::toolbar::ToolbarPanel;
+//! use msfs_derive::CommBusMessage;
+//!
+//! #[derive(CommBusMessage)]
+//! #[comm_bus(event = "infinity.efb/state")]
+//! struct EfbState {
+//!     alt: f64,
+//!     ap_engaged: bool,
+//! }
+//!
+//! let mut panel = ToolbarPanel::<EfbState>::new("infinity.efb", "1.0.0")?;
+//! panel.on_command("set_ap", |args| println!("ap -> {args}"));
+//!
+//! // in update():
+//! # let (dt, alt, ap_engaged) = (1.0 / 30.0, 12000.0, true);
+//! panel.update(dt, &EfbState { alt, ap_engaged });
+//! # Ok::<(), msfs::comm_bus::CommBusError>(())
+//! ```
+
+use crate::comm_bus::{BroadcastFlags, CommBusMessage, CommBusResult, Subscription, try_call};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    marker::PhantomData,
+    rc::Rc,
+};
+
+/// Version of the event layout above. Bumped when the wire format changes.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+const DEFAULT_SYNC_INTERVAL: f32 = 0.5;
+
+#[derive(Default)]
+struct Inbox {
+    ready: Option<String>,
+    closed: bool,
+    commands: VecDeque<String>,
+}
+
+type CommandHandler = Box<dyn FnMut(&str) + 'static>;
+
+/// The module side of a toolbar panel whose state is a `T`.
+pub struct ToolbarPanel<T: CommBusMessage> {
+    namespace: String,
+    app_version: String,
+    inbox: Rc<RefCell<Inbox>>,
+    handlers: HashMap<String, CommandHandler>,
+    panel_version: Option<String>,
+    sync_interval: f32,
+    accum: f32,
+    last_state: Option<Vec<u8>>,
+    _subs: [Subscription; 3],
+    _state: PhantomData<fn(&T)>,
+}
+
+impl<T: CommBusMessage> ToolbarPanel<T> {
+    pub fn new(namespace: &str, app_version: &str) -> CommBusResult<Self> {
+        let inbox = Rc::new(RefCell::new(Inbox::default()));
+
+        let ready_inbox = Rc::clone(&inbox);
+        let ready = Subscription::try_subscribe(&format!("{namespace}/ready"), move |bytes| {
+            ready_inbox.borrow_mut().ready = Some(String::from_utf8_lossy(bytes).into_owned());
+        })?;
+
+        let closed_inbox = Rc::clone(&inbox);
+        let closed = Subscription::try_subscribe(&format!("{namespace}/closed"), move |_| {
+            closed_inbox.borrow_mut().closed = true;
+        })?;
+
+        let cmd_inbox = Rc::clone(&inbox);
+        let cmd = Subscription::try_subscribe(&format!("{namespace}/cmd"), move |bytes| {
+            cmd_inbox
+                .borrow_mut()
+                .commands
+                .push_back(String::from_utf8_lossy(bytes).into_owned());
+        })?;
+
+        Ok(Self {
+            namespace: namespace.to_string(),
+            app_version: app_version.to_string(),
+            inbox,
+            handlers: HashMap::new(),
+            panel_version: None,
+            sync_interval: DEFAULT_SYNC_INTERVAL,
+            accum: 0.0,
+            last_state: None,
+            _subs: [ready, closed, cmd],
+            _state: PhantomData,
+        })
+    }
+
+    /// Seconds between state pushes while the panel is open. Defaults to 0.5s.
+    pub fn with_sync_interval(mut self, seconds: f32) -> Self {
+        self.sync_interval = seconds.max(0.0);
+        self
+    }
+
+    /// Register a handler for `<ns>/cmd` messages whose first word is `command`.
+    ///
+    /// Handlers run from [`update`](Self::update), never from inside the comm bus callback.
+    pub fn on_command(&mut self, command: &str, handler: impl FnMut(&str) + 'static) {
+        self.handlers.insert(command.to_string(), Box::new(handler));
+    }
+
+    /// Whether the panel has completed the handshake and not closed since.
+    #[inline]
+    pub fn is_connected(&self) -> bool {
+        self.panel_version.is_some()
+    }
+
+    /// Protocol version the panel reported in its `ready` message.
+    #[inline]
+    pub fn panel_version(&self) -> Option<&str> {
+        self.panel_version.as_deref()
+    }
+
+    /// Send the current state on the next [`update`](Self::update), even if unchanged.
+    pub fn force_sync(&mut self) {
+        self.last_state = None;
+        self.accum = self.sync_interval;
+    }
+
+    /// Pump the protocol: answer handshakes, dispatch queued commands and push state.
+    ///
+    /// `state` is only encoded when a push is due, so encoding cost is paid at the sync rate,
+    /// and only sent when it changed.
+    pub fn update(&mut self, dt: f32, state: &T) {
+        let (ready, closed, commands) = {
+            let mut inbox = self.inbox.borrow_mut();
+            (
+                inbox.ready.take(),
+                std::mem::take(&mut inbox.closed),
+                std::mem::take(&mut inbox.commands),
+            )
+        };
+
+        if closed {
+            self.panel_version = None;
+        }
+
+        if let Some(version) = ready {
+            self.panel_version = Some(version);
+            let hello = format!("{PROTOCOL_VERSION} {}", self.app_version);
+            let _ = try_call(&self.event("hello"), hello.as_bytes(), BroadcastFlags::JS);
+            self.force_sync();
+        }
+
+        for msg in commands {
+            let (command, args) = msg.split_once(' ').unwrap_or((msg.as_str(), ""));
+            if let Some(handler) = self.handlers.get_mut(command) {
+                handler(args);
+            }
+        }
+
+        if !self.is_connected() {
+            return;
+        }
+
+        self.accum += dt;
+        if self.accum < self.sync_interval {
+            return;
+        }
+        self.accum = 0.0;
+
+        let bytes = state.encode();
+        if self.last_state.as_deref() == Some(bytes.as_slice()) {
+            return;
+        }
+        let _ = try_call(&self.event("state"), &bytes, BroadcastFlags::JS);
+        self.last_state = Some(bytes);
+    }
+
+    fn event(&self, suffix: &str) -> String {
+        format!("{}/{suffix}", self.namespace)
+    }
+}
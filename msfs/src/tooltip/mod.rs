@@ -0,0 +1,113 @@
+//! Gauge tooltips.
+//!
+//! WASM gauges render into a texture, so the sim has no idea which pixels are
+//! buttons. Gauges answer [`Gauge::tooltip`](crate::modules::Gauge::tooltip)
+//! with a tooltip id for the cursor position instead; the export layer calls
+//! it on every mouse event and [`publish`]es changes to the L-var
+//! `<gauge>_TOOLTIP_ID` (see [`var_name`]), `0` while nothing is hovered.
+//!
+//! The sim shows the tooltip itself, from the mouse rect over the gauge's
+//! screen in the model behaviors, the same way stock cockpits do. Its text
+//! picks the string for the id, so it stays localizable:
+//!
+//! ```xml
+//! <MouseRect>
+//!     <TooltipText>%((L:my_gauge_TOOLTIP_ID))%{case}%{:1}Autopilot master%{:2}Flight director%{end}</TooltipText>
+//! </MouseRect>
+//! ```
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::tooltip::TooltipAreas;
+//!
+//! let mut tips = TooltipAreas::new();
+//! tips.add(10.0, 10.0, 80.0, 30.0, 1); // Autopilot master
+//! tips.add(10.0, 50.0, 80.0, 30.0, 2); // Flight director
+//!
+//! // in Gauge::tooltip:
+//! # let (x, y) = (40.0, 20.0);
+//! let id = tips.hit(x, y);
+//! ```
+
+use crate::vars::l_var::LVar;
+use std::{cell::RefCell, collections::HashMap};
+
+struct Published {
+    var: LVar,
+    id: u32,
+}
+
+thread_local! {
+    static CURRENT: RefCell<HashMap<String, Published>> = RefCell::new(HashMap::new());
+}
+
+/// The L-var `gauge`'s tooltip id is written to.
+pub fn var_name(gauge: &str) -> String {
+    format!("{gauge}_TOOLTIP_ID")
+}
+
+/// Show tooltip `id` (or hide with `None` or `Some(0)`) for `gauge`. Repeated
+/// identical calls are dropped.
+pub fn publish(gauge: &str, id: Option<u32>) {
+    let id = id.unwrap_or(0);
+    CURRENT.with(|m| {
+        let mut m = m.borrow_mut();
+        if let Some(published) = m.get_mut(gauge) {
+            if published.id != id && published.var.set(id as f64).is_ok() {
+                published.id = id;
+            }
+            return;
+        }
+        let Ok(var) = LVar::new(&var_name(gauge), "number") else {
+            return;
+        };
+        if var.set(id as f64).is_ok() {
+            m.insert(gauge.to_string(), Published { var, id });
+        }
+    });
+}
+
+/// Tooltip id currently shown for `gauge`, if any.
+pub fn current(gauge: &str) -> Option<u32> {
+    CURRENT.with(|m| m.borrow().get(gauge).map(|p| p.id).filter(|&id| id != 0))
+}
+
+#[derive(Debug, Clone)]
+struct Area {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    id: u32,
+}
+
+/// Rectangles with tooltip ids, in gauge pixel coordinates.
+/// Later areas win where they overlap.
+#[derive(Debug, Clone, Default)]
+pub struct TooltipAreas {
+    areas: Vec<Area>,
+}
+
+impl TooltipAreas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `id` is what the mouse rect's tooltip text switches on; `0` hides the
+    /// tooltip.
+    pub fn add(&mut self, x: f32, y: f32, w: f32, h: f32, id: u32) {
+        self.areas.push(Area { x, y, w, h, id });
+    }
+
+    pub fn clear(&mut self) {
+        self.areas.clear();
+    }
+
+    pub fn hit(&self, x: f32, y: f32) -> Option<u32> {
+        self.areas
+            .iter()
+            .rev()
+            .find(|a| x >= a.x && x < a.x + a.w && y >= a.y && y < a.y + a.h)
+            .map(|a| a.id)
+    }
+}
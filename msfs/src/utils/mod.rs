@@ -4,7 +4,10 @@
     eFsVarParamType_FsVarParamTypeInteger, eFsVarParamType_FsVarParamTypeString,
 };
 use core::{ffi::c_char, ptr, slice};
-use std::mem;
+use std::{
+    ffi::{CString, NulError},
+    mem,
+};
 
 #[derive(Debug, Copy, Clone)]
 pub enum FsParamArg {
@@ -123,3 +126,109 @@ pub fn fs_create_param_array(
 
     Ok(FsVarParamArrayOwned { raw })
 }
+
+/// Owned counterpart of [`FsParamArg`]; strings are copied, so no raw pointers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Crc(FsCRC),
+    Str(String),
+    Index(u32),
+    Double(f64),
+}
+
+impl From<u32> for ParamValue {
+    fn from(v: u32) -> Self {
+        ParamValue::Index(v)
+    }
+}
+
+impl From<f64> for ParamValue {
+    fn from(v: f64) -> Self {
+        ParamValue::Double(v)
+    }
+}
+
+impl From<&str> for ParamValue {
+    fn from(v: &str) -> Self {
+        ParamValue::Str(v.to_owned())
+    }
+}
+
+impl From<String> for ParamValue {
+    fn from(v: String) -> Self {
+        ParamValue::Str(v)
+    }
+}
+
+/// A param array that owns its strings, so the pointers handed to the sim stay
+/// valid for as long as the array lives.
+///
+/// ```no_run
+/// use msfs::utils::{ParamArray, ParamValue};
+/// use msfs::vars::AVar;
+///
+/// let var = AVar::new("SOME STRING PARAM VAR", "Number")?;
+/// let mut params = ParamArray::new([ParamValue::Index(1), "left".into()])?;
+/// let v = var.get_with(params.as_raw_mut(), Default::default())?;
+/// # Ok::<(), msfs::vars::VarError>(())
+/// ```
+pub struct ParamArray {
+    variants: Vec<FsVarParamVariant>,
+    // Pointed to by `variants`; the heap buffers don't move with the Vec.
+    _strings: Vec<CString>,
+}
+
+impl ParamArray {
+    /// Fails if a string contains a NUL byte.
+    pub fn new(values: impl IntoIterator<Item = ParamValue>) -> Result<Self, NulError> {
+        let mut variants = Vec::new();
+        let mut strings = Vec::new();
+        for value in values {
+            let arg = match value {
+                ParamValue::Crc(x) => FsParamArg::Crc(x),
+                ParamValue::Index(x) => FsParamArg::Index(x),
+                ParamValue::Double(x) => FsParamArg::Double(x),
+                ParamValue::Str(x) => {
+                    let c = CString::new(x)?;
+                    let arg = FsParamArg::Str(c.as_ptr());
+                    strings.push(c);
+                    arg
+                }
+            };
+            let ch = match arg {
+                FsParamArg::Crc(_) => 'c',
+                FsParamArg::Str(_) => 's',
+                FsParamArg::Index(_) => 'i',
+                FsParamArg::Double(_) => 'f',
+            };
+            variants.push(make_variant(ch, arg).expect("format char matches the argument"));
+        }
+        Ok(Self {
+            variants,
+            _strings: strings,
+        })
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.variants.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    /// Valid while `self` is alive.
+    #[inline]
+    pub fn as_raw_mut(&mut self) -> FsVarParamArray {
+        FsVarParamArray {
+            size: self.variants.len() as _,
+            array: if self.variants.is_empty() {
+                ptr::null_mut()
+            } else {
+                self.variants.as_mut_ptr()
+            },
+        }
+    }
+}
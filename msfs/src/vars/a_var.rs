@@ -1,16 +1,40 @@
 use crate::{
+    sys::{
+        FS_OBJECT_ID_USER_AIRCRAFT, FS_OBJECT_ID_USER_CURRENT, FsObjectId, FsVarError,
+        FsVarError_FS_VAR_ERROR_FAIL, FsVarError_FS_VAR_ERROR_NONE,
+        FsVarError_FS_VAR_ERROR_NOT_SUPPORTED, FsVarParamArray,
+        eFsVarParamType_FsVarParamTypeInteger,
+    },
     sys::{FsAVarId, fsVarsAVarSet, fsVarsGetAVarId},
-    vars::{Var, VarKind},
+    vars::{Var, VarError, VarKind, VarResult, calc},
+};
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    sync::{Mutex, OnceLock},
 };
 
 pub struct AVarKind;
 
+/// Names of registered A-vars, so string reads (which need the name) work from an id.
+fn names() -> &'static Mutex<HashMap<FsAVarId, CString>> {
+    static NAMES: OnceLock<Mutex<HashMap<FsAVarId, CString>>> = OnceLock::new();
+    NAMES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
 impl VarKind for AVarKind {
     type Id = FsAVarId;
 
     #[inline]
     fn register(name: *const std::os::raw::c_char) -> Self::Id {
-        unsafe { fsVarsGetAVarId(name) }
+        let id = unsafe { fsVarsGetAVarId(name) };
+        let name = unsafe { CStr::from_ptr(name) };
+        names()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(id)
+            .or_insert_with(|| name.to_owned());
+        id
     }
 
     #[inline]
@@ -34,6 +58,94 @@ impl VarKind for AVarKind {
     ) -> crate::sys::FsVarError {
         unsafe { fsVarsAVarSet(id, unit, param, value, target) }
     }
+
+    fn get_string(
+        id: Self::Id,
+        param: FsVarParamArray,
+        target: FsObjectId,
+        out: &mut Vec<u8>,
+    ) -> FsVarError {
+        let names = names().lock().unwrap_or_else(|e| e.into_inner());
+        let Some(name) = names.get(&id) else {
+            return FsVarError_FS_VAR_ERROR_FAIL;
+        };
+        calculator_string(&name.to_string_lossy(), param_index(&param), target, out)
+    }
+
+    fn is_settable(name: &str) -> bool {
+        is_settable(name)
+    }
+}
+
+/// Whether the A-var `name` accepts writes, per the SDK's settable column for
+/// the vars in [`simvars`](super::simvars). Vars missing from it are assumed
+/// settable and left for the sim to reject. The `A:` prefix, `:index` suffix
+/// and case are ignored.
+pub fn is_settable(name: &str) -> bool {
+    super::simvars::is_settable(name).unwrap_or(true)
+}
+
+/// `name` without its `A:` prefix and `:index` suffix, for table lookups.
+pub(crate) fn base_name(name: &str) -> &str {
+    let name = name.trim();
+    let name = name
+        .get(..2)
+        .filter(|p| p.eq_ignore_ascii_case("A:"))
+        .map_or(name, |_| &name[2..]);
+    let name = match name.rsplit_once(':') {
+        Some((base, index)) if index.bytes().all(|b| b.is_ascii_digit()) => base,
+        _ => name,
+    };
+    name.trim()
 }
 
 pub type AVar = Var<AVarKind>;
+
+/// The index of a single integer parameter, as built by `VarParamArray1::index`.
+fn param_index(param: &FsVarParamArray) -> Option<u32> {
+    if param.size == 0 || param.array.is_null() {
+        return None;
+    }
+    let v = unsafe { &*param.array };
+    if v.type_ == eFsVarParamType_FsVarParamTypeInteger {
+        Some(unsafe { v.__bindgen_anon_1.intValue })
+    } else {
+        None
+    }
+}
+
+// fsVars only returns numbers, so strings go through the gauge calculator, which
+// reads the user aircraft.
+fn calculator_string(
+    name: &str,
+    index: Option<u32>,
+    target: FsObjectId,
+    out: &mut Vec<u8>,
+) -> FsVarError {
+    if target != FS_OBJECT_ID_USER_AIRCRAFT && target != FS_OBJECT_ID_USER_CURRENT {
+        return FsVarError_FS_VAR_ERROR_NOT_SUPPORTED;
+    }
+    let name = name
+        .get(..2)
+        .filter(|p| p.eq_ignore_ascii_case("A:"))
+        .map_or(name, |_| &name[2..]);
+    let code = match index {
+        Some(i) => format!("(A:{name}:{i}, string)"),
+        None => format!("(A:{name}, string)"),
+    };
+    calc::string_code(code, out)
+}
+
+/// Read a string-valued A-var such as `ATC ID` or `TITLE` by name, without
+/// registering it.
+///
+/// Only the user aircraft can be read; other targets fail with
+/// `FS_VAR_ERROR_NOT_SUPPORTED`. A leading `A:` on `name` is optional.
+pub fn get_string(name: &str, index: Option<u32>, target: FsObjectId) -> VarResult<String> {
+    let mut buf = Vec::new();
+    let err = calculator_string(name, index, target, &mut buf);
+    if err != FsVarError_FS_VAR_ERROR_NONE {
+        return Err(VarError::Fs(err));
+    }
+    String::from_utf8(buf).map_err(|e| VarError::Utf8(e.utf8_error()))
+}
@@ -0,0 +1,193 @@
+//! Reading many vars per frame into one slice.
+//!
+//! The sim has no batch read, so [`VarBatch::read`] still makes one FFI call
+//! per var. What it saves is the rest: names, units and param arrays are
+//! resolved when a var is added, and vars are grouped by kind so each read is
+//! a direct call with the stored id and unit.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::vars::{AVar, LVar, VarBatch};
+//!
+//! let mut batch = VarBatch::new();
+//! let alt = batch.add(AVar::new("PLANE ALTITUDE", "Feet")?);
+//! let n1 = batch.add_indexed(AVar::new("TURB ENG N1", "Percent")?, 1);
+//! let mode = batch.add(LVar::new("L:MY_MODE", "Number")?);
+//!
+//! let mut values = vec![0.0; batch.len()];
+//! // in update():
+//! batch.read(&mut values);
+//! println!("{} ft, N1 {}%, mode {}", values[alt], values[n1], values[mode]);
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{Var, VarKind, VarParamArray1, empty_param_array};
+use crate::sys::{FsObjectId, FsUnitId, FsVarError, FsVarError_FS_VAR_ERROR_NONE};
+use std::any::{Any, TypeId};
+
+struct Entry<K: VarKind> {
+    id: K::Id,
+    unit: FsUnitId,
+    // Built once in `add_*`; `read` only hands out a pointer to it.
+    param: Option<VarParamArray1>,
+    target: FsObjectId,
+    slot: usize,
+}
+
+/// The vars of one kind.
+struct Group<K: VarKind> {
+    entries: Vec<Entry<K>>,
+}
+
+trait ReadGroup {
+    /// Read every entry into `out[entry.slot]`. Returns the number of failed
+    /// reads.
+    fn read(&mut self, out: &mut [f64], errors: &mut [Option<FsVarError>]) -> usize;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<K: VarKind> ReadGroup for Group<K> {
+    fn read(&mut self, out: &mut [f64], errors: &mut [Option<FsVarError>]) -> usize {
+        let mut failed = 0;
+        for entry in &mut self.entries {
+            let param = match &mut entry.param {
+                Some(p) => p.as_raw_mut(),
+                None => empty_param_array(),
+            };
+            let mut value = out[entry.slot];
+            let err = K::get(entry.id, entry.unit, param, &mut value, entry.target);
+            if err == FsVarError_FS_VAR_ERROR_NONE {
+                out[entry.slot] = value;
+                errors[entry.slot] = None;
+            } else {
+                errors[entry.slot] = Some(err);
+                failed += 1;
+            }
+        }
+        failed
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A fixed list of vars read into a slice, one slot per var in the order added.
+///
+/// Param arrays are built when a var is added, so [`VarBatch::read`] does no
+/// allocation or string work, only the sim calls.
+#[derive(Default)]
+pub struct VarBatch {
+    groups: Vec<(TypeId, Box<dyn ReadGroup>)>,
+    errors: Vec<Option<FsVarError>>,
+}
+
+impl VarBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            groups: Vec::new(),
+            errors: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Add `var` read from its kind's default target. Returns its slot.
+    pub fn add<K: VarKind + 'static>(&mut self, var: Var<K>) -> usize {
+        self.push(var, None, K::default_target())
+    }
+
+    /// Add one index of an indexed var, e.g. engine 2.
+    pub fn add_indexed<K: VarKind + 'static>(&mut self, var: Var<K>, index: u32) -> usize {
+        self.push(var, Some(index), K::default_target())
+    }
+
+    /// Add `var` read from `target`, optionally indexed.
+    pub fn add_with<K: VarKind + 'static>(
+        &mut self,
+        var: Var<K>,
+        index: Option<u32>,
+        target: FsObjectId,
+    ) -> usize {
+        self.push(var, index, target)
+    }
+
+    fn push<K: VarKind + 'static>(
+        &mut self,
+        var: Var<K>,
+        index: Option<u32>,
+        target: FsObjectId,
+    ) -> usize {
+        let slot = self.errors.len();
+        let entry = Entry::<K> {
+            id: var.raw_id(),
+            unit: var.unit().0,
+            param: index.map(VarParamArray1::index),
+            target,
+            slot,
+        };
+        let kind = TypeId::of::<K>();
+        let group = match self.groups.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, group)) => group,
+            None => {
+                self.groups.push((
+                    kind,
+                    Box::new(Group::<K> {
+                        entries: Vec::new(),
+                    }),
+                ));
+                &mut self.groups.last_mut().expect("just pushed").1
+            }
+        };
+        group
+            .as_any_mut()
+            .downcast_mut::<Group<K>>()
+            .expect("groups are keyed by kind")
+            .entries
+            .push(entry);
+        self.errors.push(None);
+        slot
+    }
+
+    /// Read every var into `out[slot]`. A var whose read fails keeps its
+    /// previous value in `out`; see [`VarBatch::error`]. Returns the number of
+    /// failed reads.
+    ///
+    /// # Panics
+    /// If `out` is shorter than [`VarBatch::len`].
+    pub fn read(&mut self, out: &mut [f64]) -> usize {
+        assert!(
+            out.len() >= self.len(),
+            "VarBatch::read: {} slots for {} vars",
+            out.len(),
+            self.len()
+        );
+        self.groups
+            .iter_mut()
+            .map(|(_, group)| group.read(out, &mut self.errors))
+            .sum()
+    }
+
+    /// Read into a new `Vec`; failed slots are `NaN`.
+    pub fn read_vec(&mut self) -> Vec<f64> {
+        let mut out = vec![f64::NAN; self.len()];
+        self.read(&mut out);
+        out
+    }
+
+    /// The error from the last [`VarBatch::read`] for `slot`, if it failed.
+    pub fn error(&self, slot: usize) -> Option<FsVarError> {
+        self.errors.get(slot).copied().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
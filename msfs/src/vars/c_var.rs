@@ -0,0 +1,172 @@
+use crate::{
+    sys::{
+        FsObjectId, FsUnitId, FsVarError, FsVarError_FS_VAR_ERROR_FAIL, FsVarParamArray,
+        eFsVarParamType_FsVarParamTypeString,
+    },
+    vars::{Var, VarKind, VarParamArray1, VarResult, calc, intern_name},
+};
+use std::{ffi::CStr, os::raw::c_char};
+
+/// Component (`C:`) variables, such as the legacy GPS module's
+/// `C:fs9gps:WaypointAirportIdent`.
+///
+/// The component is passed as a string parameter; without one, the registered
+/// name must already include it (`fs9gps:WaypointAirportIdent`). Reads and
+/// writes go through the gauge calculator. Prefer [`CVar`], which builds the
+/// parameter for you.
+pub struct CVarKind;
+
+/// The component from a string parameter built by [`VarParamArray1::string`].
+fn param_component(param: &FsVarParamArray) -> Option<&CStr> {
+    if param.size == 0 || param.array.is_null() {
+        return None;
+    }
+    let v = unsafe { &*param.array };
+    if v.type_ != eFsVarParamType_FsVarParamTypeString {
+        return None;
+    }
+    let ptr = unsafe { v.__bindgen_anon_1.stringValue };
+    (!ptr.is_null()).then(|| unsafe { CStr::from_ptr(ptr) })
+}
+
+/// `component:name`, or just `name` when no component parameter was given.
+fn qualified(id: &CStr, param: &FsVarParamArray) -> String {
+    let name = id.to_string_lossy();
+    match param_component(param) {
+        Some(component) => format!("{}:{name}", component.to_string_lossy()),
+        None => name.into_owned(),
+    }
+}
+
+impl VarKind for CVarKind {
+    type Id = &'static CStr;
+
+    #[inline]
+    fn register(name: *const c_char) -> Self::Id {
+        intern_name(unsafe { CStr::from_ptr(name) }, b"C:")
+    }
+
+    fn get(
+        id: Self::Id,
+        unit: FsUnitId,
+        param: FsVarParamArray,
+        out: *mut f64,
+        _target: FsObjectId,
+    ) -> FsVarError {
+        let name = qualified(id, &param);
+        calc::number_code(format!("(C:{name}, {})", calc::unit_name(unit)), out)
+    }
+
+    fn set(
+        id: Self::Id,
+        unit: FsUnitId,
+        param: FsVarParamArray,
+        value: f64,
+        _target: FsObjectId,
+    ) -> FsVarError {
+        // The calculator can't parse `NaN`/`inf`.
+        if !value.is_finite() {
+            return FsVarError_FS_VAR_ERROR_FAIL;
+        }
+        let name = qualified(id, &param);
+        calc::number_code(
+            format!("{value} (>C:{name}, {})", calc::unit_name(unit)),
+            core::ptr::null_mut(),
+        )
+    }
+
+    fn get_string(
+        id: Self::Id,
+        param: FsVarParamArray,
+        _target: FsObjectId,
+        out: &mut Vec<u8>,
+    ) -> FsVarError {
+        let name = qualified(id, &param);
+        calc::string_code(format!("(C:{name}, string)"), out)
+    }
+}
+
+/// A component variable with its component bound.
+///
+/// ```no_run
+/// use msfs::vars::CVar;
+///
+/// let ident = CVar::with_component("fs9gps", "WaypointAirportIdent", "Number")?;
+/// let icao = ident.get_string()?;
+///
+/// // same thing, component in the name
+/// let ident = CVar::new("C:fs9gps:WaypointAirportIdent", "Number")?;
+/// # Ok::<(), msfs::vars::VarError>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CVar {
+    var: Var<CVarKind>,
+    component: Option<&'static CStr>,
+}
+
+impl CVar {
+    /// `name` is `C:component:var`; the `C:` is optional.
+    pub fn new(name: &str, unit: &str) -> VarResult<Self> {
+        Ok(Self {
+            var: Var::new(name, unit)?,
+            component: None,
+        })
+    }
+
+    pub fn with_component(component: &str, name: &str, unit: &str) -> VarResult<Self> {
+        let component = std::ffi::CString::new(component)?;
+        Ok(Self {
+            var: Var::new(name, unit)?,
+            component: Some(intern_name(&component, b"C:")),
+        })
+    }
+
+    fn param(&self) -> Option<VarParamArray1> {
+        self.component.map(VarParamArray1::string)
+    }
+
+    pub fn get(&self) -> VarResult<f64> {
+        match self.param() {
+            Some(mut p) => self
+                .var
+                .get_with(p.as_raw_mut(), CVarKind::default_target()),
+            None => self.var.get(),
+        }
+    }
+
+    pub fn set(&self, value: f64) -> VarResult<()> {
+        match self.param() {
+            Some(mut p) => self
+                .var
+                .set_with(p.as_raw_mut(), value, CVarKind::default_target()),
+            None => self.var.set(value),
+        }
+    }
+
+    pub fn get_string(&self) -> VarResult<String> {
+        let mut out = String::new();
+        self.get_string_into(&mut out)?;
+        Ok(out)
+    }
+
+    /// `get_string`, reusing `out`'s allocation for per-frame polling.
+    pub fn get_string_into(&self, out: &mut String) -> VarResult<()> {
+        match self.param() {
+            Some(mut p) => {
+                self.var
+                    .get_string_with(p.as_raw_mut(), CVarKind::default_target(), out)
+            }
+            None => self.var.get_string_into(out),
+        }
+    }
+
+    pub fn component(&self) -> Option<&'static CStr> {
+        self.component
+    }
+
+    /// The underlying var, for use with the raw `*_with` methods.
+    #[inline]
+    pub fn var(&self) -> Var<CVarKind> {
+        self.var
+    }
+}
@@ -0,0 +1,92 @@
+//! A var that is only read from the sim once per interval.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::vars::{AVar, CachedVar};
+//! use std::time::{Duration, Instant};
+//!
+//! let mut fuel = CachedVar::new(
+//!     AVar::new("FUEL TOTAL QUANTITY", "Gallons")?,
+//!     Duration::from_millis(500),
+//! );
+//!
+//! // in update(): at most two sim reads per second
+//! let gallons = fuel.get_cached(Instant::now())?;
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{Var, VarKind, VarResult};
+use std::time::{Duration, Instant};
+
+/// Wraps a [`Var`] and reuses its last value until it is older than `max_age`.
+///
+/// Failed reads are not cached, so the next call tries again.
+pub struct CachedVar<K: VarKind> {
+    var: Var<K>,
+    max_age: Duration,
+    last: Option<(Instant, f64)>,
+}
+
+impl<K: VarKind> CachedVar<K> {
+    pub fn new(var: Var<K>, max_age: Duration) -> Self {
+        Self {
+            var,
+            max_age,
+            last: None,
+        }
+    }
+
+    /// The cached value if it was read within `max_age` of `now`, otherwise a
+    /// fresh read. Passing `now` in lets one `Instant::now()` serve a whole frame.
+    pub fn get_cached(&mut self, now: Instant) -> VarResult<f64> {
+        if let Some((at, value)) = self.last
+            && now.saturating_duration_since(at) < self.max_age
+        {
+            return Ok(value);
+        }
+        let value = self.var.get()?;
+        self.last = Some((now, value));
+        Ok(value)
+    }
+
+    /// Always read from the sim, refreshing the cache.
+    pub fn get(&mut self) -> VarResult<f64> {
+        let value = self.var.get()?;
+        self.last = Some((Instant::now(), value));
+        Ok(value)
+    }
+
+    /// Write through to the sim; the written value is cached on success.
+    pub fn set(&mut self, value: f64) -> VarResult<()> {
+        self.var.set(value)?;
+        self.last = Some((Instant::now(), value));
+        Ok(())
+    }
+
+    /// The last value read, however old, without touching the sim.
+    #[inline]
+    pub fn last(&self) -> Option<f64> {
+        self.last.map(|(_, v)| v)
+    }
+
+    /// Force the next [`CachedVar::get_cached`] to read.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    #[inline]
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    #[inline]
+    pub fn set_max_age(&mut self, max_age: Duration) {
+        self.max_age = max_age;
+    }
+
+    #[inline]
+    pub fn var(&self) -> Var<K> {
+        self.var
+    }
+}
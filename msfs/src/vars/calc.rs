@@ -0,0 +1,158 @@
+//! The gauge calculator: run RPN code the way XML gauges do.
+//!
+//! Useful for porting gauges built on RPN snippets, and for vars and events that
+//! have no fsVars entry point.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::vars::calc;
+//!
+//! let alt = calc::number("(A:PLANE ALTITUDE, feet) 100 /")?;
+//! let ident = calc::string("(A:GPS WP NEXT ID, string)")?;
+//! calc::run("1 (>L:MY_FLAG)");
+//!
+//! let out = calc::execute("(A:AUTOPILOT MASTER, bool)")?;
+//! println!("{} {}", out.number, out.integer);
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{UnitId, VarError, VarResult};
+use crate::sys::{
+    FsUnitId, FsVarError, FsVarError_FS_VAR_ERROR_FAIL, FsVarError_FS_VAR_ERROR_NONE,
+    execute_calculator_code,
+};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+};
+
+/// Everything the calculator returned for one evaluation.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CalcOutput {
+    /// The top of the stack as a number.
+    pub number: f64,
+    /// The top of the stack truncated to an integer.
+    pub integer: i32,
+    /// The top of the stack as a string, if the calculator produced one.
+    pub string: Option<String>,
+}
+
+/// `Err(VarError::Fs(FS_VAR_ERROR_FAIL))` when the sim rejects the code.
+pub type CalcResult = VarResult<CalcOutput>;
+
+fn call(code: &CStr, number: *mut f64, integer: *mut c_int, string: *mut *const c_char) -> bool {
+    unsafe { execute_calculator_code(code.as_ptr(), number, integer, string) != 0 }
+}
+
+fn status(ok: bool) -> FsVarError {
+    if ok {
+        FsVarError_FS_VAR_ERROR_NONE
+    } else {
+        FsVarError_FS_VAR_ERROR_FAIL
+    }
+}
+
+/// Evaluate `code` and return all of its results.
+pub fn execute(code: &str) -> CalcResult {
+    execute_cstr(&CString::new(code)?)
+}
+
+/// [`execute`] for code that is already a C string, e.g. built once and reused.
+pub fn execute_cstr(code: &CStr) -> CalcResult {
+    let mut number = 0.0;
+    let mut integer: c_int = 0;
+    let mut string: *const c_char = core::ptr::null();
+    if !call(code, &mut number, &mut integer, &mut string) {
+        return Err(VarError::Fs(FsVarError_FS_VAR_ERROR_FAIL));
+    }
+    // The calculator owns the buffer until its next call; copy it out now.
+    let string = (!string.is_null()).then(|| {
+        unsafe { CStr::from_ptr(string) }
+            .to_string_lossy()
+            .into_owned()
+    });
+    Ok(CalcOutput {
+        number,
+        integer: integer as i32,
+        string,
+    })
+}
+
+/// Evaluate `code` for its numeric result.
+pub fn number(code: &str) -> VarResult<f64> {
+    let mut out = 0.0;
+    let err = number_code(code.to_owned(), &mut out);
+    if err != FsVarError_FS_VAR_ERROR_NONE {
+        return Err(VarError::Fs(err));
+    }
+    Ok(out)
+}
+
+/// Evaluate `code` for its string result.
+pub fn string(code: &str) -> VarResult<String> {
+    let mut buf = Vec::new();
+    let err = string_code(code.to_owned(), &mut buf);
+    if err != FsVarError_FS_VAR_ERROR_NONE {
+        return Err(VarError::Fs(err));
+    }
+    String::from_utf8(buf).map_err(|e| VarError::Utf8(e.utf8_error()))
+}
+
+/// Evaluate `code` for its side effects (`(>K:...)`, `(>L:...)`). `false` if the
+/// sim rejected it or it contains a NUL byte.
+pub fn run(code: &str) -> bool {
+    CString::new(code).is_ok_and(|code| run_cstr(&code))
+}
+
+pub(crate) fn run_cstr(code: &CStr) -> bool {
+    call(
+        code,
+        core::ptr::null_mut(),
+        core::ptr::null_mut(),
+        core::ptr::null_mut(),
+    )
+}
+
+/// Store the numeric result of `code` in `out`, which may be null for writes.
+pub(crate) fn number_cstr(code: &CStr, out: *mut f64) -> FsVarError {
+    status(call(
+        code,
+        out,
+        core::ptr::null_mut(),
+        core::ptr::null_mut(),
+    ))
+}
+
+pub(crate) fn number_code(code: String, out: *mut f64) -> FsVarError {
+    match CString::new(code) {
+        Ok(code) => number_cstr(&code, out),
+        Err(_) => FsVarError_FS_VAR_ERROR_FAIL,
+    }
+}
+
+/// Copy the string result of `code` into `out`.
+pub(crate) fn string_code(code: String, out: &mut Vec<u8>) -> FsVarError {
+    let Ok(code) = CString::new(code) else {
+        return FsVarError_FS_VAR_ERROR_FAIL;
+    };
+    let mut value: *const c_char = core::ptr::null();
+    if !call(
+        &code,
+        core::ptr::null_mut(),
+        core::ptr::null_mut(),
+        &mut value,
+    ) || value.is_null()
+    {
+        return FsVarError_FS_VAR_ERROR_FAIL;
+    }
+    // The calculator owns the buffer until its next call; copy it out now.
+    out.clear();
+    out.extend_from_slice(unsafe { CStr::from_ptr(value) }.to_bytes());
+    FsVarError_FS_VAR_ERROR_NONE
+}
+
+/// Unit name for calculator code; units outside the bundled table fall back to
+/// the raw number.
+pub(crate) fn unit_name(unit: FsUnitId) -> &'static str {
+    UnitId(unit).name().unwrap_or("number")
+}
@@ -0,0 +1,59 @@
+use crate::{
+    sys::{FsObjectId, FsUnitId, FsVarError, FsVarError_FS_VAR_ERROR_FAIL, FsVarParamArray},
+    vars::{Var, VarKind, calc, intern_name},
+};
+use std::{ffi::CStr, os::raw::c_char};
+
+/// Environment (`E:`) variables such as `ZULU TIME` or `SIMULATION RATE`.
+///
+/// The fsVars API has no E-var entry points, so reads go through the gauge
+/// calculator. E-vars are read-only.
+pub struct EVarKind;
+
+impl VarKind for EVarKind {
+    type Id = &'static CStr;
+
+    #[inline]
+    fn register(name: *const c_char) -> Self::Id {
+        intern_name(unsafe { CStr::from_ptr(name) }, b"E:")
+    }
+
+    fn get(
+        id: Self::Id,
+        unit: FsUnitId,
+        _param: FsVarParamArray,
+        out: *mut f64,
+        _target: FsObjectId,
+    ) -> FsVarError {
+        let name = id.to_string_lossy();
+        calc::number_code(format!("(E:{name}, {})", calc::unit_name(unit)), out)
+    }
+
+    #[inline]
+    fn set(
+        _id: Self::Id,
+        _unit: FsUnitId,
+        _param: FsVarParamArray,
+        _value: f64,
+        _target: FsObjectId,
+    ) -> FsVarError {
+        FsVarError_FS_VAR_ERROR_FAIL
+    }
+
+    #[inline]
+    fn can_set() -> bool {
+        false
+    }
+}
+
+/// An environment variable, read through the same API as [`AVar`](super::AVar).
+///
+/// ```no_run
+/// use msfs::vars::EVar;
+///
+/// let zulu = EVar::new("E:ZULU TIME", "Seconds")?;
+/// let rate = EVar::new("SIMULATION RATE", "Number")?;
+/// let (t, r) = (zulu.get()?, rate.get()?);
+/// # Ok::<(), msfs::vars::VarError>(())
+/// ```
+pub type EVar = Var<EVarKind>;
@@ -0,0 +1,143 @@
+//! Per-frame read de-duplication.
+//!
+//! When several components read the same var in one `update()`/`draw()` call,
+//! only the first read goes through FFI; the rest get the same value back. The
+//! export macros call [`begin`] and [`end`] around every module callback, so
+//! all that's needed is to opt in once:
+//!
+//! ```no_run
+//! // in System::init / Gauge::init:
+//! msfs::vars::frame::set_enabled(true);
+//! ```
+//!
+//! Reads are keyed by (var, unit, index, target); reads with other params
+//! (strings, doubles, several params) always go through. A write through
+//! [`Var`](super::Var) drops that var's entries so the next read sees it, but
+//! changes made by other means (key events, calculator code, other modules)
+//! are not seen until the next frame. Call [`clear`] after such a change if a
+//! later read in the same frame depends on it.
+
+use crate::sys::{FsObjectId, FsUnitId, FsVarParamArray, eFsVarParamType_FsVarParamTypeInteger};
+use crate::vars::{VarKind, VarResult};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+};
+
+type Key<Id> = (Id, FsUnitId, Option<u32>, FsObjectId);
+
+#[derive(Default)]
+struct Frame {
+    enabled: bool,
+    depth: u32,
+    // One `HashMap<Key<K::Id>, f64>` per var kind.
+    kinds: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Frame {
+    fn active(&self) -> bool {
+        self.enabled && self.depth > 0
+    }
+
+    fn reads<K: VarKind>(&mut self) -> &mut HashMap<Key<K::Id>, f64> {
+        self.kinds
+            .entry(TypeId::of::<K>())
+            .or_insert_with(|| Box::new(HashMap::<Key<K::Id>, f64>::new()))
+            .downcast_mut()
+            .expect("frame cache entry keyed by its kind")
+    }
+}
+
+thread_local! {
+    static FRAME: RefCell<Frame> = RefCell::new(Frame::default());
+}
+
+/// Turn the frame cache on or off. Off by default.
+pub fn set_enabled(enabled: bool) {
+    FRAME.with(|f| {
+        let mut f = f.borrow_mut();
+        f.enabled = enabled;
+        f.kinds.clear();
+    });
+}
+
+pub fn is_enabled() -> bool {
+    FRAME.with(|f| f.borrow().enabled)
+}
+
+/// Start a frame. Calls nest; the cache is emptied when the outermost frame
+/// [`end`]s. Called by the export macros.
+pub fn begin() {
+    FRAME.with(|f| f.borrow_mut().depth += 1);
+}
+
+/// End a frame started with [`begin`].
+pub fn end() {
+    FRAME.with(|f| {
+        let mut f = f.borrow_mut();
+        f.depth = f.depth.saturating_sub(1);
+        if f.depth == 0 {
+            f.kinds.clear();
+        }
+    });
+}
+
+/// Drop every cached read, so the rest of the frame reads from the sim again.
+pub fn clear() {
+    FRAME.with(|f| f.borrow_mut().kinds.clear());
+}
+
+/// `None` for an empty param array, `Some(i)` for a single index; anything
+/// else is not cached.
+fn cache_index(param: &FsVarParamArray) -> Option<Option<u32>> {
+    if param.size == 0 {
+        return Some(None);
+    }
+    if param.size != 1 || param.array.is_null() {
+        return None;
+    }
+    let v = unsafe { &*param.array };
+    (v.type_ == eFsVarParamType_FsVarParamTypeInteger)
+        .then_some(Some(unsafe { v.__bindgen_anon_1.intValue }))
+}
+
+/// `read()`, or the value it returned earlier in this frame. Errors are not cached.
+pub(crate) fn read<K: VarKind>(
+    id: K::Id,
+    unit: FsUnitId,
+    param: &FsVarParamArray,
+    target: FsObjectId,
+    read: impl FnOnce() -> VarResult<f64>,
+) -> VarResult<f64> {
+    let key = FRAME
+        .with(|f| f.borrow().active())
+        .then(|| cache_index(param))
+        .flatten()
+        .map(|index| (id, unit, index, target));
+    let Some(key) = key else {
+        return read();
+    };
+    if let Some(value) = FRAME.with(|f| f.borrow_mut().reads::<K>().get(&key).copied()) {
+        return Ok(value);
+    }
+    // Not borrowed across the FFI call.
+    let value = read()?;
+    FRAME.with(|f| {
+        let mut f = f.borrow_mut();
+        if f.active() {
+            f.reads::<K>().insert(key, value);
+        }
+    });
+    Ok(value)
+}
+
+/// Forget every cached read of `id` (all units, indices and targets) after a write.
+pub(crate) fn invalidate<K: VarKind>(id: K::Id) {
+    FRAME.with(|f| {
+        let mut f = f.borrow_mut();
+        if f.active() {
+            f.reads::<K>().retain(|key, _| key.0 != id);
+        }
+    });
+}
@@ -0,0 +1,122 @@
+//! Typed access to the GPS / flight plan vars.
+//!
+//! The active leg comes from the `GPS WP ...` A-vars; individual flight plan
+//! waypoints are read from the legacy `fs9gps` component by selecting an index
+//! and reading it back.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::vars::gps::{ActiveWaypoint, FlightPlan};
+//!
+//! let wp = ActiveWaypoint::get()?;
+//! println!("{} {:.1} nm, XTK {:.2} nm", wp.ident, wp.distance_nm, wp.cross_track_nm);
+//!
+//! let plan = FlightPlan::get()?;
+//! for i in 0..plan.waypoint_count {
+//!     println!("{i}: {}", msfs::vars::gps::waypoint(i)?.ident);
+//! }
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{CVar, VarResult};
+use msfs_derive::VarStruct;
+use std::sync::OnceLock;
+
+/// The leg the GPS is currently flying.
+#[derive(Debug, Clone, PartialEq, VarStruct)]
+pub struct ActiveWaypoint {
+    #[var(name = "A:GPS WP NEXT ID")]
+    pub ident: String,
+    #[var(name = "A:GPS WP PREV ID")]
+    pub prev_ident: String,
+    #[var(name = "A:GPS WP DISTANCE", unit = "Nautical miles")]
+    pub distance_nm: f64,
+    #[var(name = "A:GPS WP ETE", unit = "Seconds")]
+    pub ete_s: f64,
+    #[var(name = "A:GPS WP BEARING", unit = "Degrees")]
+    pub bearing_deg: f64,
+    #[var(name = "A:GPS WP DESIRED TRACK", unit = "Degrees")]
+    pub desired_track_deg: f64,
+    #[var(name = "A:GPS WP CROSS TRK", unit = "Nautical miles")]
+    pub cross_track_nm: f64,
+    #[var(name = "A:GPS WP NEXT ALT", unit = "Feet")]
+    pub altitude_ft: f64,
+}
+
+/// Flight plan progress.
+#[derive(Debug, Clone, Copy, PartialEq, VarStruct)]
+pub struct FlightPlan {
+    #[var(name = "A:GPS FLIGHT PLAN WP COUNT", unit = "Number")]
+    pub waypoint_count: u32,
+    /// Index of the waypoint being flown to.
+    #[var(name = "A:GPS FLIGHT PLAN WP INDEX", unit = "Number")]
+    pub active_index: u32,
+    /// Time to the destination.
+    #[var(name = "A:GPS ETE", unit = "Seconds")]
+    pub ete_s: f64,
+    #[var(name = "A:GPS GROUND SPEED", unit = "Knots")]
+    pub ground_speed_kt: f64,
+}
+
+/// A flight plan waypoint read with [`waypoint`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waypoint {
+    pub index: u32,
+    pub ident: String,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub altitude_ft: f64,
+    /// Length of the leg ending at this waypoint.
+    pub leg_distance_nm: f64,
+}
+
+struct Fs9Gps {
+    index: CVar,
+    ident: CVar,
+    latitude: CVar,
+    longitude: CVar,
+    altitude: CVar,
+    distance: CVar,
+}
+
+fn fs9gps() -> VarResult<&'static Fs9Gps> {
+    static VARS: OnceLock<Fs9Gps> = OnceLock::new();
+    if let Some(vars) = VARS.get() {
+        return Ok(vars);
+    }
+    let var = |name, unit| CVar::with_component("fs9gps", name, unit);
+    let vars = Fs9Gps {
+        index: var("FlightPlanWaypointIndex", "Number")?,
+        // Read with `get_string`, which ignores the unit.
+        ident: var("FlightPlanWaypointIdent", "Number")?,
+        latitude: var("FlightPlanWaypointLatitude", "Degrees")?,
+        longitude: var("FlightPlanWaypointLongitude", "Degrees")?,
+        altitude: var("FlightPlanWaypointAltitude", "Feet")?,
+        distance: var("FlightPlanWaypointDistance", "Nautical miles")?,
+    };
+    Ok(VARS.get_or_init(|| vars))
+}
+
+/// Read flight plan waypoint `index` (0-based, up to
+/// [`FlightPlan::waypoint_count`]).
+///
+/// This selects the waypoint on the calling gauge's `fs9gps` instance, so it
+/// must not be interleaved with other code that drives the same component.
+pub fn waypoint(index: u32) -> VarResult<Waypoint> {
+    let gps = fs9gps()?;
+    gps.index.set(index as f64)?;
+    Ok(Waypoint {
+        index,
+        ident: gps.ident.get_string()?,
+        latitude_deg: gps.latitude.get()?,
+        longitude_deg: gps.longitude.get()?,
+        altitude_ft: gps.altitude.get()?,
+        leg_distance_nm: gps.distance.get()?,
+    })
+}
+
+/// Every waypoint of the active flight plan, in order.
+pub fn waypoints() -> VarResult<Vec<Waypoint>> {
+    let count = FlightPlan::get()?.waypoint_count;
+    (0..count).map(waypoint).collect()
+}
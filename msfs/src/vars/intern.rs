@@ -0,0 +1,62 @@
+//! Process-wide table of registered vars.
+//!
+//! [`Var::new`] looks vars up here by (kind, name, unit) and only registers
+//! with the sim on the first call, so creating the same var from several
+//! places, or on every read as the `VarStruct` derive does, costs one hash
+//! lookup instead of a registration and a static per field.
+
+use crate::vars::{UnitId, Var, VarError, VarKind, VarResult};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+type Key = (TypeId, &'static str, &'static str);
+
+fn table() -> &'static Mutex<HashMap<Key, Box<dyn Any + Send>>> {
+    static VARS: OnceLock<Mutex<HashMap<Key, Box<dyn Any + Send>>>> = OnceLock::new();
+    VARS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The var registered for `(K, name, unit)`, registering it on first use.
+/// Failed registrations are not remembered; their errors are
+/// [`VarError::Named`] with `name`.
+pub fn var<K: VarKind>(name: &str, unit: &str) -> VarResult<Var<K>> {
+    let kind = TypeId::of::<K>();
+    let mut vars = table().lock().unwrap_or_else(|e| e.into_inner());
+    // Shorten the key lifetime so borrowed names can be looked up without leaking.
+    let lookup: &HashMap<(TypeId, &str, &str), Box<dyn Any + Send>> = &vars;
+    if let Some(var) = lookup.get(&(kind, name, unit)) {
+        return Ok(*var.downcast_ref().expect("interned var keyed by its kind"));
+    }
+    let var = register::<K>(name, unit).map_err(|e| e.named(name))?;
+    #[cfg(feature = "unit-check")]
+    if kind == TypeId::of::<crate::vars::a_var::AVarKind>()
+        && let Err(m) = crate::vars::simvars::check_unit(name, unit)
+    {
+        eprintln!(
+            "[msfs] unit \"{unit}\" ({:?}) for A-var \"{name}\", expected {:?} such as \"{}\"",
+            m.found,
+            m.expected.info().family,
+            m.expected.name()
+        );
+    }
+    let name: &'static str = Box::leak(name.into());
+    let unit: &'static str = Box::leak(unit.into());
+    vars.insert((kind, name, unit), Box::new(var));
+    Ok(var)
+}
+
+fn register<K: VarKind>(name: &str, unit: &str) -> VarResult<Var<K>> {
+    let unit_id = UnitId::from_str(unit)?;
+    if !unit_id.is_valid() {
+        return Err(VarError::UnknownUnit(unit.to_string()));
+    }
+    Var::with_unit_id(name, unit_id)
+}
+
+/// Number of distinct vars registered through [`var`].
+pub fn len() -> usize {
+    table().lock().unwrap_or_else(|e| e.into_inner()).len()
+}
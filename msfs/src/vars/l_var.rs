@@ -1,7 +1,11 @@
 use crate::{
-    sys::{FsLVarId, fsVarsLVarSet, fsVarsRegisterLVar},
-    vars::{Var, VarKind},
+    sys::{
+        FsLVarId, ID, check_named_variable, fsVarsLVarSet, fsVarsRegisterLVar,
+        get_name_of_named_variable,
+    },
+    vars::{Var, VarKind, VarResult},
 };
+use std::ffi::{CStr, CString};
 
 pub struct LVarKind;
 
@@ -37,3 +41,67 @@ impl VarKind for LVarKind {
 }
 
 pub type LVar = Var<LVarKind>;
+
+impl LVar {
+    /// The name this L-var was registered under (without the `L:` prefix).
+    pub fn name(&self) -> Option<String> {
+        name_of(self.raw_id())
+    }
+
+    /// Whether any gauge, module or JS instrument has already registered `name`.
+    /// Does not register it.
+    pub fn exists(name: &str) -> bool {
+        let stripped = name
+            .get(..2)
+            .filter(|p| p.eq_ignore_ascii_case("L:"))
+            .map_or(name, |_| &name[2..]);
+        [name, stripped].into_iter().any(|n| {
+            CString::new(n).is_ok_and(|c| unsafe { check_named_variable(c.as_ptr()) } >= 0)
+        })
+    }
+
+    /// Register `name`, writing `default` only if nobody had registered it yet,
+    /// so a module initialising after the var's owner doesn't stomp its value.
+    pub fn get_or_create(name: &str, unit: &str, default: f64) -> VarResult<Self> {
+        let existed = Self::exists(name);
+        let var = Self::new(name, unit)?;
+        if !existed {
+            var.set(default)?;
+        }
+        Ok(var)
+    }
+}
+
+/// An L-var known to the sim, as listed by [`enumerate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LVarInfo {
+    pub id: FsLVarId,
+    /// Without the `L:` prefix.
+    pub name: String,
+}
+
+/// Name of the L-var with this id, or `None` past the end of the table.
+pub fn name_of(id: FsLVarId) -> Option<String> {
+    let ptr = unsafe { get_name_of_named_variable(id as ID) };
+    if ptr.is_null() {
+        return None;
+    }
+    Some(
+        unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Every L-var currently registered by any gauge, module or JS instrument.
+///
+/// Ids are allocated densely from 0, so this walks them until the sim reports
+/// no name. The list only grows during a flight; call again to pick up new ones.
+pub fn iter() -> impl Iterator<Item = LVarInfo> {
+    (0..).map_while(|id| name_of(id).map(|name| LVarInfo { id, name }))
+}
+
+/// [`iter`], collected.
+pub fn enumerate() -> Vec<LVarInfo> {
+    iter().collect()
+}
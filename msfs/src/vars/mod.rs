@@ -1,17 +1,145 @@
-﻿pub mod a_var;
+pub mod a_var;
+pub mod batch;
+pub mod c_var;
+pub mod cached;
+pub mod calc;
+pub mod e_var;
+pub mod frame;
+pub mod gps;
+pub mod intern;
 pub mod l_var;
+pub mod o_var;
+pub mod objects;
+pub mod registry;
+pub mod simvars;
+pub mod subscription;
+pub mod units;
+pub mod value;
+pub mod watcher;
 
 pub use a_var::AVar;
+pub use batch::VarBatch;
+pub use c_var::CVar;
+pub use cached::CachedVar;
+pub use e_var::EVar;
 pub use l_var::LVar;
+pub use o_var::OVar;
+pub use registry::{AnyVar, VarRegistry};
+pub use units::{Unit, convert, try_convert};
+pub use value::VarValue;
+pub use watcher::VarWatcher;
 
 use crate::sys::*;
-
-use std::{ffi::CString, marker::PhantomData, mem::MaybeUninit, os::raw::c_char};
+use crate::utils::{ParamArray, ParamValue};
+
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    os::raw::c_char,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum VarError {
+    /// Raw error code from the sim; [`VarError::fs_error`] decodes it.
     Fs(FsVarError),
     Nul(std::ffi::NulError),
+    /// The (rounded) value has no matching variant in the field's enum.
+    Unmapped(i32),
+    /// A string var's value is not valid UTF-8.
+    Utf8(std::str::Utf8Error),
+    /// The var is known to be read-only; `set()` was not attempted.
+    ReadOnly,
+    /// The sim doesn't know this unit name.
+    UnknownUnit(String),
+    /// `error`, raised while creating the var `name`.
+    Named {
+        name: String,
+        error: Box<VarError>,
+    },
+}
+
+/// Decoded [`FsVarError`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FsError {
+    /// The sim rejected the access: unknown var, bad index or param, or a
+    /// target that doesn't have the var.
+    Fail,
+    /// The operation doesn't exist for this var, e.g. writing an E-var.
+    NotSupported,
+    Unknown(FsVarError),
+}
+
+impl FsError {
+    pub fn from_raw(code: FsVarError) -> Self {
+        if code == FsVarError_FS_VAR_ERROR_FAIL {
+            FsError::Fail
+        } else if code == FsVarError_FS_VAR_ERROR_NOT_SUPPORTED {
+            FsError::NotSupported
+        } else {
+            FsError::Unknown(code)
+        }
+    }
+}
+
+impl std::fmt::Display for FsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FsError::Fail => write!(f, "access failed (unknown var, bad index or target)"),
+            FsError::NotSupported => write!(f, "operation not supported for this var"),
+            FsError::Unknown(c) => write!(f, "unknown var error ({c})"),
+        }
+    }
+}
+
+impl VarError {
+    /// The decoded sim error, looking through [`VarError::Named`].
+    pub fn fs_error(&self) -> Option<FsError> {
+        match self {
+            VarError::Fs(code) => Some(FsError::from_raw(*code)),
+            VarError::Named { error, .. } => error.fs_error(),
+            _ => None,
+        }
+    }
+
+    /// Attach the var name, unless the error already has one.
+    pub fn named(self, name: &str) -> Self {
+        match self {
+            VarError::Named { .. } => self,
+            error => VarError::Named {
+                name: name.to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for VarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarError::Fs(code) => FsError::from_raw(*code).fmt(f),
+            VarError::Nul(e) => write!(f, "null byte in string: {e}"),
+            VarError::Unmapped(v) => write!(f, "value {v} has no matching enum variant"),
+            VarError::Utf8(e) => write!(f, "string value is not UTF-8: {e}"),
+            VarError::ReadOnly => write!(f, "var is read-only"),
+            VarError::UnknownUnit(unit) => write!(f, "unknown unit \"{unit}\""),
+            VarError::Named { name, error } => write!(f, "{name}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for VarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VarError::Nul(e) => Some(e),
+            VarError::Utf8(e) => Some(e),
+            VarError::Named { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 impl From<std::ffi::NulError> for VarError {
@@ -34,8 +162,28 @@ impl UnitId {
     }
 }
 
-pub trait VarKind {
-    type Id: Copy;
+/// Intern `name` without its `prefix` (`E:`, `O:`, ...), so calculator-backed
+/// kinds get a `Copy` id that can live in statics.
+pub(crate) fn intern_name(name: &CStr, prefix: &[u8; 2]) -> &'static CStr {
+    static NAMES: OnceLock<Mutex<HashMap<CString, &'static CStr>>> = OnceLock::new();
+    let bytes = name.to_bytes_with_nul();
+    let name = match bytes.get(..2) {
+        Some(p) if p.eq_ignore_ascii_case(prefix) => {
+            CStr::from_bytes_with_nul(&bytes[2..]).expect("suffix of a C string")
+        }
+        _ => name,
+    };
+    let mut names = NAMES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    names
+        .entry(name.to_owned())
+        .or_insert_with(|| Box::leak(name.to_owned().into_boxed_c_str()))
+}
+
+pub trait VarKind: Send + 'static {
+    type Id: Copy + Eq + std::hash::Hash + Send + 'static;
 
     fn register(name: *const c_char) -> Self::Id;
 
@@ -55,6 +203,17 @@ pub trait VarKind {
         target: FsObjectId,
     ) -> FsVarError;
 
+    /// Read a string-valued var into `out`, replacing its contents. Kinds
+    /// without string values return `FS_VAR_ERROR_NOT_SUPPORTED`.
+    fn get_string(
+        _id: Self::Id,
+        _param: FsVarParamArray,
+        _target: FsObjectId,
+        _out: &mut Vec<u8>,
+    ) -> FsVarError {
+        FsVarError_FS_VAR_ERROR_NOT_SUPPORTED
+    }
+
     fn default_target() -> FsObjectId {
         FS_OBJECT_ID_USER_AIRCRAFT
     }
@@ -62,6 +221,12 @@ pub trait VarKind {
     fn can_set() -> bool {
         true
     }
+
+    /// Whether the var `name` accepts writes, for kinds where only some do.
+    /// Asked once when the var is registered.
+    fn is_settable(_name: &str) -> bool {
+        Self::can_set()
+    }
 }
 
 #[inline]
@@ -86,6 +251,18 @@ impl VarParamArray1 {
         Self { variant: v }
     }
 
+    /// A string parameter, such as the component of a [`CVar`]. `'static` so the
+    /// pointer stays valid however long the array is kept.
+    #[inline]
+    pub fn string(value: &'static CStr) -> Self {
+        let mut v: FsVarParamVariant = unsafe { core::mem::zeroed() };
+        v.type_ = eFsVarParamType_FsVarParamTypeString;
+        v.__bindgen_anon_1 = FsVarParamVariant__bindgen_ty_1 {
+            stringValue: value.as_ptr(),
+        };
+        Self { variant: v }
+    }
+
     #[inline]
     pub fn as_raw_mut(&mut self) -> FsVarParamArray {
         FsVarParamArray {
@@ -95,21 +272,166 @@ impl VarParamArray1 {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Up to `N` mixed parameters (index, double, CRC, string), on the stack.
+///
+/// String parameters borrow their `CStr` for `'a`, so the array can't outlive
+/// the strings it points to. The builder methods panic when adding more than
+/// `N` params.
+///
+/// ```no_run
+/// use msfs::vars::{AVar, VarParamArrayN};
+///
+/// let var = AVar::new("SOME INDEXED VAR", "Number")?;
+/// let mut params = VarParamArrayN::<2>::new().index(1).string(c"left");
+/// let v = var.get_params(&mut params)?;
+/// # Ok::<(), msfs::vars::VarError>(())
+/// ```
+#[derive(Copy, Clone)]
+pub struct VarParamArrayN<'a, const N: usize> {
+    variants: [FsVarParamVariant; N],
+    len: usize,
+    _strings: PhantomData<&'a CStr>,
+}
+
+impl<'a, const N: usize> VarParamArrayN<'a, N> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            variants: unsafe { core::mem::zeroed() },
+            len: 0,
+            _strings: PhantomData,
+        }
+    }
+
+    fn push(mut self, type_: eFsVarParamType, value: FsVarParamVariant__bindgen_ty_1) -> Self {
+        assert!(self.len < N, "VarParamArrayN<{N}> is full");
+        let v = &mut self.variants[self.len];
+        v.type_ = type_;
+        v.__bindgen_anon_1 = value;
+        self.len += 1;
+        self
+    }
+
+    #[inline]
+    pub fn index(self, index: u32) -> Self {
+        self.push(
+            eFsVarParamType_FsVarParamTypeInteger,
+            FsVarParamVariant__bindgen_ty_1 { intValue: index },
+        )
+    }
+
+    #[inline]
+    pub fn double(self, value: f64) -> Self {
+        self.push(
+            eFsVarParamType_FsVarParamTypeDouble,
+            FsVarParamVariant__bindgen_ty_1 { doubleValue: value },
+        )
+    }
+
+    #[inline]
+    pub fn crc(self, crc: FsCRC) -> Self {
+        self.push(
+            eFsVarParamType_FsVarParamTypeCRC,
+            FsVarParamVariant__bindgen_ty_1 { CRCValue: crc },
+        )
+    }
+
+    #[inline]
+    pub fn string(self, value: &'a CStr) -> Self {
+        self.push(
+            eFsVarParamType_FsVarParamTypeString,
+            FsVarParamVariant__bindgen_ty_1 {
+                stringValue: value.as_ptr(),
+            },
+        )
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Valid while `self` is neither moved nor dropped.
+    #[inline]
+    pub fn as_raw_mut(&mut self) -> FsVarParamArray {
+        FsVarParamArray {
+            size: self.len as u32,
+            array: if self.len == 0 {
+                core::ptr::null_mut()
+            } else {
+                self.variants.as_mut_ptr()
+            },
+        }
+    }
+}
+
+impl<const N: usize> Default for VarParamArrayN<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct Var<K: VarKind> {
     id: K::Id,
     unit: UnitId,
+    settable: bool,
     _k: PhantomData<K>,
 }
 
+// Manual impls: deriving would require the `K` marker itself to be Copy/Debug.
+impl<K: VarKind> Clone for Var<K> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: VarKind> Copy for Var<K> {}
+
+impl<K: VarKind> std::fmt::Debug for Var<K>
+where
+    K::Id: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Var")
+            .field("id", &self.id)
+            .field("unit", &self.unit)
+            .finish()
+    }
+}
+
 impl<K: VarKind> Var<K> {
+    /// Look up or register `name`. Registrations are shared process-wide (see
+    /// [`intern`]), so calling this repeatedly for the same var is cheap.
     pub fn new(name: &str, unit: &str) -> VarResult<Self> {
+        intern::var(name, unit)
+    }
+
+    /// Like [`Var::new`], with the unit checked at compile time.
+    ///
+    /// ```no_run
+    /// use msfs::vars::{AVar, units::Unit};
+    ///
+    /// let ias = AVar::new_typed("AIRSPEED INDICATED", Unit::Knots)?;
+    /// # Ok::<(), msfs::vars::VarError>(())
+    /// ```
+    pub fn new_typed(name: &str, unit: units::Unit) -> VarResult<Self> {
+        intern::var(name, unit.name())
+    }
+
+    /// Register `name` directly, bypassing the shared [`intern`] table.
+    pub fn with_unit_id(name: &str, unit: UnitId) -> VarResult<Self> {
         let name_c = CString::new(name)?;
-        let unit = UnitId::from_str(unit)?;
         let id = K::register(name_c.as_ptr() as *const c_char);
         Ok(Self {
             id,
             unit,
+            settable: K::is_settable(name),
             _k: PhantomData,
         })
     }
@@ -126,13 +448,43 @@ impl<K: VarKind> Var<K> {
 
     #[inline]
     pub fn get_with(&self, param: FsVarParamArray, target: FsObjectId) -> VarResult<f64> {
-        let mut out = MaybeUninit::<f64>::uninit();
-        let err = K::get(self.id, self.unit.0, param, out.as_mut_ptr(), target);
-        if err == FsVarError_FS_VAR_ERROR_NONE {
-            Ok(unsafe { out.assume_init() })
-        } else {
-            Err(VarError::Fs(err))
-        }
+        frame::read::<K>(self.id, self.unit.0, &param, target, || {
+            let mut out = MaybeUninit::<f64>::uninit();
+            let err = K::get(self.id, self.unit.0, param, out.as_mut_ptr(), target);
+            if err == FsVarError_FS_VAR_ERROR_NONE {
+                Ok(unsafe { out.assume_init() })
+            } else {
+                Err(VarError::Fs(err))
+            }
+        })
+    }
+
+    /// Read and convert, e.g. `get_as::<bool>()` or `get_as::<i32>()` with
+    /// rounding instead of `>= 0.5` / `as i32` at the call site.
+    #[inline]
+    pub fn get_as<T: VarValue>(&self) -> VarResult<T> {
+        self.get().map(T::from_f64)
+    }
+
+    #[inline]
+    pub fn get_indexed_as<T: VarValue>(&self, index: u32) -> VarResult<T> {
+        self.get_indexed(index).map(T::from_f64)
+    }
+
+    /// Read with the params in `params`, from the default target.
+    #[inline]
+    pub fn get_params<const N: usize>(&self, params: &mut VarParamArrayN<'_, N>) -> VarResult<f64> {
+        self.get_with(params.as_raw_mut(), K::default_target())
+    }
+
+    /// Read with owned params (strings included), from `target`.
+    pub fn get_with_values(
+        &self,
+        params: impl IntoIterator<Item = ParamValue>,
+        target: FsObjectId,
+    ) -> VarResult<f64> {
+        let mut params = ParamArray::new(params)?;
+        self.get_with(params.as_raw_mut(), target)
     }
 
     #[inline]
@@ -146,11 +498,98 @@ impl<K: VarKind> Var<K> {
         self.get_with(param.as_raw_mut(), target)
     }
 
+    /// Read a string-valued var (`ATC ID`, `TITLE`, ...). Only A-vars have them.
+    #[inline]
+    pub fn get_string(&self) -> VarResult<String> {
+        let mut out = String::new();
+        self.get_string_into(&mut out)?;
+        Ok(out)
+    }
+
+    #[inline]
+    pub fn get_string_indexed(&self, index: u32) -> VarResult<String> {
+        let mut out = String::new();
+        let mut param = VarParamArray1::index(index);
+        self.get_string_with(param.as_raw_mut(), K::default_target(), &mut out)?;
+        Ok(out)
+    }
+
+    /// `get_string`, reusing `out`'s allocation for per-frame polling.
+    #[inline]
+    pub fn get_string_into(&self, out: &mut String) -> VarResult<()> {
+        self.get_string_with(empty_param_array(), K::default_target(), out)
+    }
+
+    /// Read into `out`. On error `out` is left empty.
+    pub fn get_string_with(
+        &self,
+        param: FsVarParamArray,
+        target: FsObjectId,
+        out: &mut String,
+    ) -> VarResult<()> {
+        let mut buf = std::mem::take(out).into_bytes();
+        let err = K::get_string(self.id, param, target, &mut buf);
+        if err != FsVarError_FS_VAR_ERROR_NONE {
+            return Err(VarError::Fs(err));
+        }
+        match String::from_utf8(buf) {
+            Ok(s) => {
+                *out = s;
+                Ok(())
+            }
+            Err(e) => Err(VarError::Utf8(e.utf8_error())),
+        }
+    }
+
     #[inline]
     pub fn set(&self, value: f64) -> VarResult<()> {
         self.set_with(empty_param_array(), value, K::default_target())
     }
 
+    /// Write `value`, read it back, and return whether the sim kept it (within
+    /// `epsilon`). `Ok(false)` means the write was silently ignored or clamped,
+    /// as happens with read-only A-vars.
+    pub fn set_verified(&self, value: f64, epsilon: f64) -> VarResult<bool> {
+        self.set(value)?;
+        Ok((self.get()? - value).abs() <= epsilon)
+    }
+
+    /// [`Var::set_verified`] for one index of an indexed var.
+    pub fn set_indexed_verified(&self, index: u32, value: f64, epsilon: f64) -> VarResult<bool> {
+        self.set_indexed(index, value)?;
+        Ok((self.get_indexed(index)? - value).abs() <= epsilon)
+    }
+
+    /// Convert and write, e.g. `set_from(true)` or `set_from(3u8)`.
+    #[inline]
+    pub fn set_from<T: VarValue>(&self, value: T) -> VarResult<()> {
+        self.set(value.to_f64())
+    }
+
+    #[inline]
+    pub fn set_indexed_from<T: VarValue>(&self, index: u32, value: T) -> VarResult<()> {
+        self.set_indexed(index, value.to_f64())
+    }
+
+    #[inline]
+    pub fn set_params<const N: usize>(
+        &self,
+        params: &mut VarParamArrayN<'_, N>,
+        value: f64,
+    ) -> VarResult<()> {
+        self.set_with(params.as_raw_mut(), value, K::default_target())
+    }
+
+    pub fn set_with_values(
+        &self,
+        params: impl IntoIterator<Item = ParamValue>,
+        value: f64,
+        target: FsObjectId,
+    ) -> VarResult<()> {
+        let mut params = ParamArray::new(params)?;
+        self.set_with(params.as_raw_mut(), value, target)
+    }
+
     #[inline]
     pub fn set_target(&self, target: FsObjectId, value: f64) -> VarResult<()> {
         self.set_with(empty_param_array(), value, target)
@@ -166,7 +605,11 @@ impl<K: VarKind> Var<K> {
         if !K::can_set() {
             return Err(VarError::Fs(FsVarError_FS_VAR_ERROR_NOT_SUPPORTED));
         }
+        if !self.settable {
+            return Err(VarError::ReadOnly);
+        }
         let err = K::set(self.id, self.unit.0, param, value, target);
+        frame::invalidate::<K>(self.id);
         if err == FsVarError_FS_VAR_ERROR_NONE {
             Ok(())
         } else {
@@ -185,6 +628,15 @@ impl<K: VarKind> Var<K> {
         self.set_with(param.as_raw_mut(), value, target)
     }
 
+    /// `false` if writes are known to fail, in which case `set()` returns
+    /// [`VarError::ReadOnly`] without calling into the sim. A-vars are checked
+    /// against the SDK's settable column in [`simvars`] when registered; vars
+    /// missing from it are assumed settable.
+    #[inline]
+    pub fn is_settable(&self) -> bool {
+        K::can_set() && self.settable
+    }
+
     #[inline]
     pub fn unit(&self) -> UnitId {
         self.unit
@@ -195,3 +647,48 @@ impl<K: VarKind> Var<K> {
         self.id
     }
 }
+
+/// Last successful read of a `#[var(max_age_ms = ...)]` field, used by the
+/// `VarStruct` derive. Keyed by target so `get_for` doesn't return another
+/// object's value; errors are never cached.
+#[doc(hidden)]
+pub struct __MaxAgeCache<T> {
+    slot: Mutex<Option<(Option<FsObjectId>, Instant, T)>>,
+}
+
+impl<T: Clone> __MaxAgeCache<T> {
+    pub const fn new() -> Self {
+        Self {
+            slot: Mutex::new(None),
+        }
+    }
+
+    pub fn get_or_read(
+        &self,
+        target: Option<FsObjectId>,
+        max_age: Duration,
+        read: impl FnOnce() -> VarResult<T>,
+    ) -> VarResult<T> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((t, at, value)) = slot.as_ref()
+            && *t == target
+            && at.elapsed() <= max_age
+        {
+            return Ok(value.clone());
+        }
+        let value = read()?;
+        *slot = Some((target, Instant::now(), value.clone()));
+        Ok(value)
+    }
+
+    /// Force the next read through, e.g. after the field was written.
+    pub fn invalidate(&self) {
+        *self.slot.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+impl<T: Clone> Default for __MaxAgeCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    sys::{FsObjectId, FsUnitId, FsVarError, FsVarError_FS_VAR_ERROR_FAIL, FsVarParamArray},
+    vars::{Var, VarKind, calc, intern_name},
+};
+use std::{ffi::CStr, os::raw::c_char};
+
+/// Instrument-scoped (`O:`) variables.
+///
+/// Unlike L-vars, an O-var is private to the instrument that evaluates it, so
+/// two gauges can both keep an `O:PAGE` without clashing. There are no fsVars
+/// entry points for them; reads and writes go through the gauge calculator of
+/// the calling instrument.
+pub struct OVarKind;
+
+impl VarKind for OVarKind {
+    type Id = &'static CStr;
+
+    #[inline]
+    fn register(name: *const c_char) -> Self::Id {
+        intern_name(unsafe { CStr::from_ptr(name) }, b"O:")
+    }
+
+    fn get(
+        id: Self::Id,
+        unit: FsUnitId,
+        _param: FsVarParamArray,
+        out: *mut f64,
+        _target: FsObjectId,
+    ) -> FsVarError {
+        let name = id.to_string_lossy();
+        calc::number_code(format!("(O:{name}, {})", calc::unit_name(unit)), out)
+    }
+
+    fn set(
+        id: Self::Id,
+        unit: FsUnitId,
+        _param: FsVarParamArray,
+        value: f64,
+        _target: FsObjectId,
+    ) -> FsVarError {
+        // The calculator can't parse `NaN`/`inf`.
+        if !value.is_finite() {
+            return FsVarError_FS_VAR_ERROR_FAIL;
+        }
+        let name = id.to_string_lossy();
+        calc::number_code(
+            format!("{value} (>O:{name}, {})", calc::unit_name(unit)),
+            core::ptr::null_mut(),
+        )
+    }
+}
+
+/// An instrument-scoped variable, read and written like an [`LVar`](super::LVar).
+///
+/// ```no_run
+/// use msfs::vars::OVar;
+///
+/// let page = OVar::new("O:MFD_PAGE", "Number")?;
+/// page.set(2.0)?;
+/// # Ok::<(), msfs::vars::VarError>(())
+/// ```
+pub type OVar = Var<OVarKind>;
@@ -0,0 +1,264 @@
+//! Object ids to target var reads at, e.g. AI or multiplayer traffic.
+//!
+//! The user's own objects have fixed ids. Other objects are found with a
+//! SimConnect request, see [`SimObjectEnumeration`]; their ids can be passed to
+//! [`Var::get_target`](super::Var::get_target) and friends.
+
+use crate::simconnect::{self, read_f64, read_u32};
+use crate::sys::{
+    DWORD, FS_OBJECT_ID_USER_AIRCRAFT, FS_OBJECT_ID_USER_AVATAR, FS_OBJECT_ID_USER_CURRENT,
+    FsObjectId, HANDLE, SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64,
+    SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE, SIMCONNECT_SIMOBJECT_TYPE,
+    SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT,
+    SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_ALL,
+    SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_BOAT,
+    SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND,
+    SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_HELICOPTER,
+    SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_USER, SimConnect_AddToDataDefinition,
+    SimConnect_Close, SimConnect_RequestDataOnSimObjectType,
+};
+
+/// The aircraft the user is flying.
+#[inline]
+pub fn user_aircraft() -> FsObjectId {
+    FS_OBJECT_ID_USER_AIRCRAFT
+}
+
+/// The user's on-foot avatar.
+#[inline]
+pub fn user_avatar() -> FsObjectId {
+    FS_OBJECT_ID_USER_AVATAR
+}
+
+/// Whichever of the two the user currently controls.
+#[inline]
+pub fn user_current() -> FsObjectId {
+    FS_OBJECT_ID_USER_CURRENT
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimObjectKind {
+    User,
+    All,
+    Aircraft,
+    Helicopter,
+    Boat,
+    Ground,
+}
+
+impl SimObjectKind {
+    fn raw(self) -> SIMCONNECT_SIMOBJECT_TYPE {
+        match self {
+            SimObjectKind::User => SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_USER,
+            SimObjectKind::All => SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_ALL,
+            SimObjectKind::Aircraft => SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_AIRCRAFT,
+            SimObjectKind::Helicopter => {
+                SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_HELICOPTER
+            }
+            SimObjectKind::Boat => SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_BOAT,
+            SimObjectKind::Ground => SIMCONNECT_SIMOBJECT_TYPE_SIMCONNECT_SIMOBJECT_TYPE_GROUND,
+        }
+    }
+}
+
+/// One object found by [`SimObjectEnumeration`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimObject {
+    /// Usable as a var target.
+    pub id: FsObjectId,
+    /// The object's `TITLE`.
+    pub title: String,
+    pub latitude_deg: f64,
+    pub longitude_deg: f64,
+    pub altitude_ft: f64,
+}
+
+/// Lists sim objects within a radius of the user.
+///
+/// Opens a SimConnect client, so start it once and call
+/// [`SimObjectEnumeration::poll`] from `update` until it returns the list;
+/// [`SimObjectEnumeration::refresh`] asks again, e.g. every few seconds for a
+/// traffic display.
+///
+/// ```no_run
+/// use msfs::vars::{AVar, objects::{SimObjectEnumeration, SimObjectKind}};
+///
+/// let mut traffic = SimObjectEnumeration::start(SimObjectKind::Aircraft, 20_000)
+///     .expect("SimConnect unavailable");
+/// let ias = AVar::new("AIRSPEED INDICATED", "Knots")?;
+///
+/// // in update():
+/// if let Some(objects) = traffic.poll() {
+///     for o in objects {
+///         println!("{} {}: {:?} kt", o.id, o.title, ias.get_target(o.id));
+///     }
+/// }
+/// # Ok::<(), msfs::vars::VarError>(())
+/// ```
+pub struct SimObjectEnumeration {
+    handle: HANDLE,
+    kind: SimObjectKind,
+    radius_m: u32,
+    objects: Vec<SimObject>,
+    pending: Vec<SimObject>,
+    done: bool,
+}
+
+const DEFINITION_ID: DWORD = 1;
+const REQUEST_ID: DWORD = 1;
+/// SimConnect's limit for `RequestDataOnSimObjectType`.
+pub const MAX_RADIUS_M: u32 = 200_000;
+
+// `SIMCONNECT_RECV_SIMOBJECT_DATA_BYTYPE` is packed: dwSize dwVersion dwID
+// dwRequestID dwObjectID dwDefineID dwFlags dwentrynumber dwoutof dwDefineCount,
+// then the data in definition order.
+const DATA: usize = 40;
+const TITLE_LEN: usize = 256;
+
+impl SimObjectEnumeration {
+    /// Open a SimConnect client and request objects of `kind` within `radius_m`
+    /// meters (capped at [`MAX_RADIUS_M`]). `None` if SimConnect is unavailable.
+    pub fn start(kind: SimObjectKind, radius_m: u32) -> Option<Self> {
+        let handle = simconnect::open("infinity-rs objects")?;
+        let fields = [
+            (
+                c"PLANE LATITUDE",
+                c"degrees",
+                SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64,
+            ),
+            (
+                c"PLANE LONGITUDE",
+                c"degrees",
+                SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64,
+            ),
+            (
+                c"PLANE ALTITUDE",
+                c"feet",
+                SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64,
+            ),
+            (
+                c"TITLE",
+                c"",
+                SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_STRING256,
+            ),
+        ];
+        for (var, unit, ty) in fields {
+            let ok = unsafe {
+                SimConnect_AddToDataDefinition(
+                    handle,
+                    DEFINITION_ID,
+                    var.as_ptr(),
+                    if unit.is_empty() {
+                        core::ptr::null()
+                    } else {
+                        unit.as_ptr()
+                    },
+                    ty,
+                    0.0,
+                    u32::MAX as DWORD,
+                ) >= 0
+            };
+            if !ok {
+                unsafe { SimConnect_Close(handle) };
+                return None;
+            }
+        }
+        let mut this = Self {
+            handle,
+            kind,
+            radius_m: radius_m.min(MAX_RADIUS_M),
+            objects: Vec::new(),
+            pending: Vec::new(),
+            done: false,
+        };
+        this.refresh().then_some(this)
+    }
+
+    /// Request a fresh list. The previous one stays available from
+    /// [`SimObjectEnumeration::objects`] until the new one is complete.
+    pub fn refresh(&mut self) -> bool {
+        self.pending.clear();
+        self.done = false;
+        unsafe {
+            SimConnect_RequestDataOnSimObjectType(
+                self.handle,
+                REQUEST_ID,
+                DEFINITION_ID,
+                self.radius_m as DWORD,
+                self.kind.raw(),
+            ) >= 0
+        }
+    }
+
+    /// Process pending SimConnect messages. Returns the list once complete.
+    pub fn poll(&mut self) -> Option<&[SimObject]> {
+        if !self.done {
+            let handle = self.handle;
+            simconnect::dispatch(handle, |bytes| self.receive(bytes));
+        }
+        self.done.then_some(self.objects.as_slice())
+    }
+
+    /// The last complete list (empty before the first one arrives).
+    pub fn objects(&self) -> &[SimObject] {
+        &self.objects
+    }
+
+    fn receive(&mut self, bytes: &[u8]) {
+        let Some(id) = simconnect::recv_id(bytes) else {
+            return;
+        };
+        if id as i32 == SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_EXCEPTION as i32 {
+            self.finish();
+            return;
+        }
+        if id as i32 != SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA_BYTYPE as i32
+            || read_u32(bytes, 12) != Some(REQUEST_ID as u32)
+        {
+            return;
+        }
+        let (Some(object_id), Some(entry), Some(out_of)) = (
+            read_u32(bytes, 16),
+            read_u32(bytes, 28),
+            read_u32(bytes, 32),
+        ) else {
+            return;
+        };
+        // No objects in range: a single record with `out_of == 0`.
+        if out_of > 0
+            && let (Some(lat), Some(lon), Some(alt), Some(title)) = (
+                read_f64(bytes, DATA),
+                read_f64(bytes, DATA + 8),
+                read_f64(bytes, DATA + 16),
+                bytes.get(DATA + 24..DATA + 24 + TITLE_LEN),
+            )
+        {
+            let title = &title[..title.iter().position(|&b| b == 0).unwrap_or(TITLE_LEN)];
+            self.pending.push(SimObject {
+                id: object_id as FsObjectId,
+                title: String::from_utf8_lossy(title).into_owned(),
+                latitude_deg: lat,
+                longitude_deg: lon,
+                altitude_ft: alt,
+            });
+        }
+        if entry + 1 >= out_of {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.objects = std::mem::take(&mut self.pending);
+        self.done = true;
+    }
+}
+
+impl Drop for SimObjectEnumeration {
+    fn drop(&mut self) {
+        unsafe {
+            SimConnect_Close(self.handle);
+        }
+    }
+}
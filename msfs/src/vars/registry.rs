@@ -0,0 +1,150 @@
+//! Vars looked up by name at runtime, for gauges whose var lists come from
+//! config files.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::vars::VarRegistry;
+//!
+//! let mut registry = VarRegistry::new();
+//! for (name, unit) in [("A:PLANE ALTITUDE", "Feet"), ("A:GENERAL ENG RPM:1", "RPM")] {
+//!     registry.register(name, unit)?;
+//! }
+//!
+//! // later, per frame:
+//! if let Some(var) = registry.get("A:PLANE ALTITUDE") {
+//!     let alt = var.get()?;
+//! }
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{AVar, CVar, EVar, LVar, OVar, VarError, VarResult};
+use crate::sys::FsVarError_FS_VAR_ERROR_NOT_SUPPORTED;
+use std::collections::HashMap;
+
+/// A var of any kind, chosen from its name prefix.
+#[derive(Debug, Clone, Copy)]
+pub enum AnyVar {
+    /// An A-var, with the index from a trailing `:N` (`A:GENERAL ENG RPM:1`).
+    A(AVar, Option<u32>),
+    L(LVar),
+    E(EVar),
+    O(OVar),
+    /// A component var, `C:component:name`.
+    C(CVar),
+}
+
+impl AnyVar {
+    /// Register `name` with `unit`. `L:`, `E:`, `O:` and `C:` prefixes select those kinds;
+    /// anything else is an A-var, with or without `A:`.
+    pub fn new(name: &str, unit: &str) -> VarResult<Self> {
+        let name = name.trim();
+        match name.get(..2) {
+            Some(p) if p.eq_ignore_ascii_case("L:") => Ok(AnyVar::L(LVar::new(name, unit)?)),
+            Some(p) if p.eq_ignore_ascii_case("E:") => Ok(AnyVar::E(EVar::new(name, unit)?)),
+            Some(p) if p.eq_ignore_ascii_case("O:") => Ok(AnyVar::O(OVar::new(name, unit)?)),
+            Some(p) if p.eq_ignore_ascii_case("C:") => Ok(AnyVar::C(CVar::new(name, unit)?)),
+            _ => {
+                let (name, index) = split_index(name);
+                Ok(AnyVar::A(AVar::new(name, unit)?, index))
+            }
+        }
+    }
+
+    pub fn get(&self) -> VarResult<f64> {
+        match self {
+            AnyVar::A(v, Some(i)) => v.get_indexed(*i),
+            AnyVar::A(v, None) => v.get(),
+            AnyVar::L(v) => v.get(),
+            AnyVar::E(v) => v.get(),
+            AnyVar::O(v) => v.get(),
+            AnyVar::C(v) => v.get(),
+        }
+    }
+
+    /// E-vars are read-only and return `FS_VAR_ERROR_NOT_SUPPORTED`.
+    pub fn set(&self, value: f64) -> VarResult<()> {
+        match self {
+            AnyVar::A(v, Some(i)) => v.set_indexed(*i, value),
+            AnyVar::A(v, None) => v.set(value),
+            AnyVar::L(v) => v.set(value),
+            AnyVar::O(v) => v.set(value),
+            AnyVar::C(v) => v.set(value),
+            AnyVar::E(_) => Err(VarError::Fs(FsVarError_FS_VAR_ERROR_NOT_SUPPORTED)),
+        }
+    }
+}
+
+/// `NAME:3` -> (`NAME`, Some(3)).
+fn split_index(name: &str) -> (&str, Option<u32>) {
+    if let Some((base, index)) = name.rsplit_once(':')
+        && !index.is_empty()
+        && index.bytes().all(|b| b.is_ascii_digit())
+        && let Ok(index) = index.parse()
+    {
+        return (base, Some(index));
+    }
+    (name, None)
+}
+
+/// Handle to a registered var; cheaper to look up than the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VarKey(u32);
+
+/// Vars registered once and looked up by name.
+///
+/// Names are kept as given (trimmed), so `A:PLANE ALTITUDE` and
+/// `PLANE ALTITUDE` are separate entries. The first registration of a name
+/// fixes its unit.
+#[derive(Debug, Default)]
+pub struct VarRegistry {
+    vars: Vec<AnyVar>,
+    keys: HashMap<Box<str>, VarKey>,
+}
+
+impl VarRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `name`, or return the existing entry if it is already known.
+    pub fn register(&mut self, name: &str, unit: &str) -> VarResult<VarKey> {
+        let name = name.trim();
+        if let Some(key) = self.keys.get(name) {
+            return Ok(*key);
+        }
+        let var = AnyVar::new(name, unit)?;
+        let key = VarKey(self.vars.len() as u32);
+        self.vars.push(var);
+        self.keys.insert(name.into(), key);
+        Ok(key)
+    }
+
+    pub fn key(&self, name: &str) -> Option<VarKey> {
+        self.keys.get(name.trim()).copied()
+    }
+
+    pub fn get(&self, name: &str) -> Option<AnyVar> {
+        self.key(name).map(|k| self.vars[k.0 as usize])
+    }
+
+    /// The var behind `key`. Keys are only valid for the registry that made them.
+    #[inline]
+    pub fn by_key(&self, key: VarKey) -> AnyVar {
+        self.vars[key.0 as usize]
+    }
+
+    /// Registered names and their vars, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, AnyVar)> + '_ {
+        self.keys
+            .iter()
+            .map(|(name, key)| (&**name, self.vars[key.0 as usize]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+}
@@ -0,0 +1,752 @@
+//! Constructors for common A-vars, with the SDK's names and a sensible unit.
+//!
+//! Saves copy-pasting var names (and guessing units) at each call site. Vars
+//! marked *indexed* take an engine, tank, radio, ... index: read them with
+//! [`Var::get_indexed`](super::Var::get_indexed). String vars (`TITLE`,
+//! `ATC ID`, ...) are read with [`a_var::get_string`](super::a_var::get_string).
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::vars::simvars;
+//!
+//! let ias = simvars::indicated_airspeed().get()?;
+//! let n1 = simvars::turb_eng_n1().get_indexed(1)?;
+//! let gear_down = simvars::gear_handle_position().get_as::<bool>()?;
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{
+    AVar, Unit,
+    units::{self, UnitFamily},
+};
+
+// Entries marked `read_only` are the ones the SDK lists as not settable.
+macro_rules! simvars {
+    ($($(#[$doc:meta])* $fn:ident => $name:literal, $unit:ident $(, $ro:ident)?;)*) => {
+        $(
+            $(#[$doc])*
+            #[doc = concat!("\n\n`", $name, "`, in `", stringify!($unit), "`", simvars!(@note $($ro)?), ".")]
+            pub fn $fn() -> AVar {
+                AVar::new_typed($name, Unit::$unit).expect("simvar names contain no NUL")
+            }
+        )*
+
+        /// Name and unit of every constructor in this module.
+        pub static ALL: &[(&str, Unit)] = &[$(($name, Unit::$unit)),*];
+
+        // Parallel to `ALL`.
+        static SETTABLE: &[bool] = &[$(simvars!(@settable $($ro)?)),*];
+    };
+    (@note) => { "" };
+    (@note read_only) => { ", read-only" };
+    (@settable) => { true };
+    (@settable read_only) => { false };
+}
+
+simvars! {
+    // Speeds
+    /// Indicated airspeed.
+    indicated_airspeed => "AIRSPEED INDICATED", Knots;
+    /// True airspeed.
+    true_airspeed => "AIRSPEED TRUE", Knots;
+    /// Current Mach number.
+    mach => "AIRSPEED MACH", Mach, read_only;
+    /// Maximum airspeed (Vmo/Mmo) at the current altitude.
+    barber_pole_airspeed => "AIRSPEED BARBER POLE", Knots, read_only;
+    /// Speed over the ground.
+    ground_velocity => "GROUND VELOCITY", Knots, read_only;
+    /// Vertical speed.
+    vertical_speed => "VERTICAL SPEED", FeetPerMinute;
+    /// Stall speed, landing configuration.
+    design_speed_vs0 => "DESIGN SPEED VS0", Knots, read_only;
+    /// Stall speed, clean.
+    design_speed_vs1 => "DESIGN SPEED VS1", Knots, read_only;
+    /// Design cruise speed.
+    design_speed_vc => "DESIGN SPEED VC", Knots, read_only;
+    /// Cruise speed from the aircraft configuration.
+    estimated_cruise_speed => "ESTIMATED CRUISE SPEED", Knots, read_only;
+
+    // Altitude and position
+    /// Altimeter reading. *Indexed* by altimeter.
+    indicated_altitude => "INDICATED ALTITUDE", Feet;
+    /// True altitude above mean sea level.
+    plane_altitude => "PLANE ALTITUDE", Feet;
+    /// Height above the ground.
+    plane_alt_above_ground => "PLANE ALT ABOVE GROUND", Feet;
+    /// Radar altimeter reading.
+    radio_height => "RADIO HEIGHT", Feet, read_only;
+    /// Ground elevation below the aircraft.
+    ground_altitude => "GROUND ALTITUDE", Feet, read_only;
+    /// Altitude for the standard pressure setting.
+    pressure_altitude => "PRESSURE ALTITUDE", Feet, read_only;
+    /// Altimeter setting. *Indexed* by altimeter.
+    kohlsman_setting_hg => "KOHLSMAN SETTING HG", InchesOfMercury;
+    /// Altimeter setting. *Indexed* by altimeter.
+    kohlsman_setting_mb => "KOHLSMAN SETTING MB", Millibars;
+    /// Latitude.
+    plane_latitude => "PLANE LATITUDE", Degrees;
+    /// Longitude.
+    plane_longitude => "PLANE LONGITUDE", Degrees;
+
+    // Attitude and rates
+    /// True heading.
+    plane_heading_degrees_true => "PLANE HEADING DEGREES TRUE", Degrees;
+    /// Magnetic heading.
+    plane_heading_degrees_magnetic => "PLANE HEADING DEGREES MAGNETIC", Degrees;
+    /// Heading shown by the gyro.
+    plane_heading_degrees_gyro => "PLANE HEADING DEGREES GYRO", Degrees;
+    /// Pitch; positive nose down.
+    plane_pitch_degrees => "PLANE PITCH DEGREES", Degrees;
+    /// Bank; positive left wing down.
+    plane_bank_degrees => "PLANE BANK DEGREES", Degrees;
+    /// Magnetic variation at the aircraft.
+    magvar => "MAGVAR", Degrees, read_only;
+    /// Pitch shown by the attitude indicator.
+    attitude_indicator_pitch_degrees => "ATTITUDE INDICATOR PITCH DEGREES", Degrees, read_only;
+    /// Bank shown by the attitude indicator.
+    attitude_indicator_bank_degrees => "ATTITUDE INDICATOR BANK DEGREES", Degrees, read_only;
+    /// Heading shown by the heading indicator.
+    heading_indicator => "HEADING INDICATOR", Degrees, read_only;
+    /// Turn rate shown by the turn indicator.
+    turn_indicator_rate => "TURN INDICATOR RATE", DegreesPerSecond, read_only;
+    /// Slip ball position, -127..=127.
+    turn_coordinator_ball => "TURN COORDINATOR BALL", Number, read_only;
+    /// Angle of attack.
+    incidence_alpha => "INCIDENCE ALPHA", Degrees, read_only;
+    /// Sideslip angle.
+    incidence_beta => "INCIDENCE BETA", Degrees, read_only;
+    /// Load factor.
+    g_force => "G FORCE", GForce;
+    /// Pitch rate (body axes).
+    rotation_velocity_body_x => "ROTATION VELOCITY BODY X", DegreesPerSecond;
+    /// Yaw rate (body axes).
+    rotation_velocity_body_y => "ROTATION VELOCITY BODY Y", DegreesPerSecond;
+    /// Roll rate (body axes).
+    rotation_velocity_body_z => "ROTATION VELOCITY BODY Z", DegreesPerSecond;
+    /// Lateral velocity (body axes).
+    velocity_body_x => "VELOCITY BODY X", FeetPerSecond;
+    /// Vertical velocity (body axes).
+    velocity_body_y => "VELOCITY BODY Y", FeetPerSecond;
+    /// Longitudinal velocity (body axes).
+    velocity_body_z => "VELOCITY BODY Z", FeetPerSecond;
+    /// Vertical speed at the last touchdown.
+    plane_touchdown_normal_velocity => "PLANE TOUCHDOWN NORMAL VELOCITY", FeetPerSecond, read_only;
+
+    // State
+    /// Whether the aircraft is on the ground.
+    sim_on_ground => "SIM ON GROUND", Bool, read_only;
+    /// Whether the aircraft is on a runway.
+    on_any_runway => "ON ANY RUNWAY", Bool, read_only;
+    /// Whether the aircraft is parked (cold and dark start state).
+    plane_in_parking_state => "PLANE IN PARKING STATE", Bool, read_only;
+    /// Stall warning active.
+    stall_warning => "STALL WARNING", Bool, read_only;
+    /// Overspeed warning active.
+    overspeed_warning => "OVERSPEED WARNING", Bool, read_only;
+    /// Whether slew mode is on.
+    is_slew_active => "IS SLEW ACTIVE", Bool;
+    /// Whether this is the user's aircraft.
+    is_user_sim => "IS USER SIM", Bool, read_only;
+    /// Pushback state (3 = no pushback).
+    pushback_state => "PUSHBACK STATE", Enum;
+    /// Surface under the aircraft.
+    surface_type => "SURFACE TYPE", Enum, read_only;
+    /// Unlimited fuel setting.
+    unlimited_fuel => "UNLIMITED FUEL", Bool, read_only;
+
+    // Weight and balance
+    /// Gross weight.
+    total_weight => "TOTAL WEIGHT", Pounds, read_only;
+    /// Empty weight.
+    empty_weight => "EMPTY WEIGHT", Pounds, read_only;
+    /// Maximum gross weight.
+    max_gross_weight => "MAX GROSS WEIGHT", Pounds, read_only;
+    /// Longitudinal center of gravity, as a fraction of MAC.
+    cg_percent => "CG PERCENT", PercentOver100, read_only;
+    /// Number of engines.
+    number_of_engines => "NUMBER OF ENGINES", Number, read_only;
+    /// Engine type (0 piston, 1 jet, 2 none, 3 helo turbine, 5 turboprop).
+    engine_type => "ENGINE TYPE", Enum, read_only;
+
+    // Environment
+    /// Outside air temperature.
+    ambient_temperature => "AMBIENT TEMPERATURE", Celsius, read_only;
+    /// Total air temperature.
+    total_air_temperature => "TOTAL AIR TEMPERATURE", Celsius, read_only;
+    /// Static air pressure.
+    ambient_pressure => "AMBIENT PRESSURE", InchesOfMercury, read_only;
+    /// Barometric pressure at sea level.
+    sea_level_pressure => "SEA LEVEL PRESSURE", Millibars, read_only;
+    /// Wind speed.
+    ambient_wind_velocity => "AMBIENT WIND VELOCITY", Knots, read_only;
+    /// Wind direction (from), true.
+    ambient_wind_direction => "AMBIENT WIND DIRECTION", Degrees, read_only;
+    /// Visibility.
+    ambient_visibility => "AMBIENT VISIBILITY", Meters, read_only;
+    /// Whether the aircraft is in a cloud.
+    ambient_in_cloud => "AMBIENT IN CLOUD", Bool, read_only;
+    /// Precipitation flags (2 none, 4 rain, 8 snow).
+    ambient_precip_state => "AMBIENT PRECIP STATE", Mask, read_only;
+    /// Airframe ice accumulation.
+    structural_ice_pct => "STRUCTURAL ICE PCT", PercentOver100;
+
+    // Engines (all indexed by engine, from 1)
+    /// *Indexed.* Engine RPM.
+    general_eng_rpm => "GENERAL ENG RPM", Rpm;
+    /// *Indexed.* Throttle lever.
+    general_eng_throttle_lever_position => "GENERAL ENG THROTTLE LEVER POSITION", Percent;
+    /// *Indexed.* Mixture lever.
+    general_eng_mixture_lever_position => "GENERAL ENG MIXTURE LEVER POSITION", Percent;
+    /// *Indexed.* Propeller lever.
+    general_eng_propeller_lever_position => "GENERAL ENG PROPELLER LEVER POSITION", Percent;
+    /// *Indexed.* Whether the engine is running.
+    general_eng_combustion => "GENERAL ENG COMBUSTION", Bool;
+    /// *Indexed.* Starter engaged.
+    general_eng_starter => "GENERAL ENG STARTER", Bool;
+    /// *Indexed.* Fuel valve open.
+    general_eng_fuel_valve => "GENERAL ENG FUEL VALVE", Bool;
+    /// *Indexed.* Fuel pump switch.
+    general_eng_fuel_pump_switch => "GENERAL ENG FUEL PUMP SWITCH", Bool;
+    /// *Indexed.* Fuel pump running.
+    general_eng_fuel_pump_on => "GENERAL ENG FUEL PUMP ON", Bool;
+    /// *Indexed.* Oil temperature.
+    general_eng_oil_temperature => "GENERAL ENG OIL TEMPERATURE", Fahrenheit;
+    /// *Indexed.* Oil pressure.
+    general_eng_oil_pressure => "GENERAL ENG OIL PRESSURE", Psi;
+    /// *Indexed.* Exhaust gas temperature.
+    general_eng_exhaust_gas_temperature => "GENERAL ENG EXHAUST GAS TEMPERATURE", Fahrenheit;
+    /// *Indexed.* Fuel pressure.
+    general_eng_fuel_pressure => "GENERAL ENG FUEL PRESSURE", Psi;
+    /// *Indexed.* Total running time.
+    general_eng_elapsed_time => "GENERAL ENG ELAPSED TIME", Hours;
+    /// *Indexed.* Engine failed.
+    general_eng_failed => "GENERAL ENG FAILED", Bool, read_only;
+    /// *Indexed.* Fuel burnt since engine start.
+    general_eng_fuel_used_since_start => "GENERAL ENG FUEL USED SINCE START", Pounds, read_only;
+    /// *Indexed.* Alternator switch.
+    general_eng_master_alternator => "GENERAL ENG MASTER ALTERNATOR", Bool;
+    /// *Indexed.* Generator switch.
+    general_eng_generator_switch => "GENERAL ENG GENERATOR SWITCH", Bool;
+    /// *Indexed.* Manifold pressure.
+    eng_manifold_pressure => "ENG MANIFOLD PRESSURE", InchesOfMercury;
+    /// *Indexed.* Fuel flow by volume.
+    eng_fuel_flow_gph => "ENG FUEL FLOW GPH", GallonsPerHour;
+    /// *Indexed.* Fuel flow by weight.
+    eng_fuel_flow_pph => "ENG FUEL FLOW PPH", PoundsPerHour;
+    /// *Indexed.* Cylinder head temperature.
+    eng_cylinder_head_temperature => "ENG CYLINDER HEAD TEMPERATURE", Fahrenheit;
+    /// *Indexed.* Exhaust gas temperature.
+    eng_exhaust_gas_temperature => "ENG EXHAUST GAS TEMPERATURE", Fahrenheit;
+    /// *Indexed.* Oil temperature.
+    eng_oil_temperature => "ENG OIL TEMPERATURE", Fahrenheit;
+    /// *Indexed.* Oil pressure.
+    eng_oil_pressure => "ENG OIL PRESSURE", Psi;
+    /// *Indexed.* Oil quantity.
+    eng_oil_quantity => "ENG OIL QUANTITY", PercentOver100;
+    /// *Indexed.* Hydraulic pressure.
+    eng_hydraulic_pressure => "ENG HYDRAULIC PRESSURE", Psi;
+    /// *Indexed.* Whether the engine is running.
+    eng_combustion => "ENG COMBUSTION", Bool;
+    /// *Indexed.* N1.
+    eng_n1_rpm => "ENG N1 RPM", Percent;
+    /// *Indexed.* N2.
+    eng_n2_rpm => "ENG N2 RPM", Percent;
+    /// *Indexed.* Engine fire.
+    eng_on_fire => "ENG ON FIRE", Bool;
+    /// *Indexed.* Vibration.
+    eng_vibration => "ENG VIBRATION", Number;
+    /// *Indexed.* Engine anti-ice.
+    eng_anti_ice => "ENG ANTI ICE", Bool;
+    /// *Indexed.* Turbine N1.
+    turb_eng_n1 => "TURB ENG N1", Percent;
+    /// *Indexed.* Turbine N2.
+    turb_eng_n2 => "TURB ENG N2", Percent;
+    /// *Indexed.* Corrected N1.
+    turb_eng_corrected_n1 => "TURB ENG CORRECTED N1", Percent;
+    /// *Indexed.* Interstage turbine temperature.
+    turb_eng_itt => "TURB ENG ITT", Celsius;
+    /// *Indexed.* Turbine fuel flow.
+    turb_eng_fuel_flow_pph => "TURB ENG FUEL FLOW PPH", PoundsPerHour;
+    /// *Indexed.* Jet thrust.
+    turb_eng_jet_thrust => "TURB ENG JET THRUST", Pounds, read_only;
+    /// *Indexed.* Thrust reverser deployment.
+    turb_eng_reverse_nozzle_percent => "TURB ENG REVERSE NOZZLE PERCENT", Percent;
+    /// *Indexed.* Ignition switch.
+    turb_eng_ignition_switch => "TURB ENG IGNITION SWITCH", Bool;
+    /// *Indexed.* Torque as a fraction of maximum.
+    turb_eng_max_torque_percent => "TURB ENG MAX TORQUE PERCENT", Percent;
+    /// *Indexed.* Afterburner on.
+    turb_eng_afterburner => "TURB ENG AFTERBURNER", Bool;
+    /// *Indexed.* Bleed air pressure.
+    turb_eng_bleed_air => "TURB ENG BLEED AIR", Psi;
+    /// *Indexed.* Turbine vibration.
+    turb_eng_vibration => "TURB ENG VIBRATION", Number;
+    /// *Indexed.* Piston manifold pressure.
+    recip_eng_manifold_pressure => "RECIP ENG MANIFOLD PRESSURE", Psi;
+    /// *Indexed.* Piston cylinder head temperature.
+    recip_eng_cylinder_head_temperature => "RECIP ENG CYLINDER HEAD TEMPERATURE", Celsius;
+    /// *Indexed.* Left magneto.
+    recip_eng_left_magneto => "RECIP ENG LEFT MAGNETO", Bool;
+    /// *Indexed.* Right magneto.
+    recip_eng_right_magneto => "RECIP ENG RIGHT MAGNETO", Bool;
+    /// *Indexed.* Piston fuel flow.
+    recip_eng_fuel_flow => "RECIP ENG FUEL FLOW", PoundsPerHour;
+    /// *Indexed.* Primer engaged.
+    recip_eng_primer => "RECIP ENG PRIMER", Bool;
+    /// *Indexed.* Propeller RPM.
+    prop_rpm => "PROP RPM", Rpm;
+    /// *Indexed.* Propeller blade angle.
+    prop_beta => "PROP BETA", Degrees;
+    /// *Indexed.* Propeller feathered.
+    prop_feathered => "PROP FEATHERED", Bool, read_only;
+    /// *Indexed.* Propeller de-ice switch.
+    prop_deice_switch => "PROP DEICE SWITCH", Bool;
+    /// APU RPM.
+    apu_pct_rpm => "APU PCT RPM", PercentOver100;
+    /// APU starter progress.
+    apu_pct_starter => "APU PCT STARTER", PercentOver100;
+    /// APU master switch.
+    apu_switch => "APU SWITCH", Bool;
+    /// APU generator switch.
+    apu_generator_switch => "APU GENERATOR SWITCH", Bool;
+
+    // Fuel
+    /// Fuel on board.
+    fuel_total_quantity => "FUEL TOTAL QUANTITY", Gallons, read_only;
+    /// Fuel on board, by weight.
+    fuel_total_quantity_weight => "FUEL TOTAL QUANTITY WEIGHT", Pounds, read_only;
+    /// Total usable fuel capacity.
+    fuel_total_capacity => "FUEL TOTAL CAPACITY", Gallons, read_only;
+    /// Fuel in the left tanks.
+    fuel_left_quantity => "FUEL LEFT QUANTITY", Gallons, read_only;
+    /// Fuel in the right tanks.
+    fuel_right_quantity => "FUEL RIGHT QUANTITY", Gallons, read_only;
+    /// Capacity of the left tanks.
+    fuel_left_capacity => "FUEL LEFT CAPACITY", Gallons, read_only;
+    /// Capacity of the right tanks.
+    fuel_right_capacity => "FUEL RIGHT CAPACITY", Gallons, read_only;
+    /// Center tank quantity.
+    fuel_tank_center_quantity => "FUEL TANK CENTER QUANTITY", Gallons;
+    /// Left main tank quantity.
+    fuel_tank_left_main_quantity => "FUEL TANK LEFT MAIN QUANTITY", Gallons;
+    /// Right main tank quantity.
+    fuel_tank_right_main_quantity => "FUEL TANK RIGHT MAIN QUANTITY", Gallons;
+    /// Left auxiliary tank quantity.
+    fuel_tank_left_aux_quantity => "FUEL TANK LEFT AUX QUANTITY", Gallons;
+    /// Right auxiliary tank quantity.
+    fuel_tank_right_aux_quantity => "FUEL TANK RIGHT AUX QUANTITY", Gallons;
+    /// Fuel density.
+    fuel_weight_per_gallon => "FUEL WEIGHT PER GALLON", Pounds, read_only;
+    /// *Indexed* by selector. Selected tank.
+    fuel_tank_selector => "FUEL TANK SELECTOR", Enum;
+    /// Quantity in the selected tanks.
+    fuel_selected_quantity => "FUEL SELECTED QUANTITY", Gallons, read_only;
+    /// Quantity in the selected tanks, as a fraction of their capacity.
+    fuel_selected_quantity_percent => "FUEL SELECTED QUANTITY PERCENT", PercentOver100, read_only;
+    /// Crossfeed state.
+    fuel_cross_feed => "FUEL CROSS FEED", Enum;
+    /// Fuel dump active.
+    fuel_dump_active => "FUEL DUMP ACTIVE", Bool;
+
+    // Electrical
+    /// Master battery switch.
+    electrical_master_battery => "ELECTRICAL MASTER BATTERY", Bool;
+    /// Main bus voltage.
+    electrical_main_bus_voltage => "ELECTRICAL MAIN BUS VOLTAGE", Volts;
+    /// Main bus load.
+    electrical_main_bus_amps => "ELECTRICAL MAIN BUS AMPS", Amperes;
+    /// Battery voltage.
+    electrical_battery_voltage => "ELECTRICAL BATTERY VOLTAGE", Volts;
+    /// Battery load.
+    electrical_battery_load => "ELECTRICAL BATTERY LOAD", Amperes;
+    /// Avionics bus voltage.
+    electrical_avionics_bus_voltage => "ELECTRICAL AVIONICS BUS VOLTAGE", Volts;
+    /// *Indexed.* Generator/alternator bus voltage.
+    electrical_genalt_bus_voltage => "ELECTRICAL GENALT BUS VOLTAGE", Volts;
+    /// *Indexed.* Generator/alternator load.
+    electrical_genalt_bus_amps => "ELECTRICAL GENALT BUS AMPS", Amperes;
+    /// Total electrical load.
+    electrical_total_load_amps => "ELECTRICAL TOTAL LOAD AMPS", Amperes;
+    /// Avionics master switch.
+    avionics_master_switch => "AVIONICS MASTER SWITCH", Bool;
+    /// *Indexed* by circuit. Circuit switch position.
+    circuit_switch_on => "CIRCUIT SWITCH ON", Bool;
+    /// *Indexed* by circuit. Circuit powered.
+    circuit_on => "CIRCUIT ON", Bool;
+    /// *Indexed.* External power connected.
+    external_power_on => "EXTERNAL POWER ON", Bool;
+    /// *Indexed.* External power available.
+    external_power_available => "EXTERNAL POWER AVAILABLE", Bool;
+
+    // Lights
+    /// Navigation lights.
+    light_nav => "LIGHT NAV", Bool;
+    /// Beacon.
+    light_beacon => "LIGHT BEACON", Bool;
+    /// Landing lights.
+    light_landing => "LIGHT LANDING", Bool;
+    /// Taxi lights.
+    light_taxi => "LIGHT TAXI", Bool;
+    /// Strobes.
+    light_strobe => "LIGHT STROBE", Bool;
+    /// Panel lights.
+    light_panel => "LIGHT PANEL", Bool;
+    /// Recognition lights.
+    light_recognition => "LIGHT RECOGNITION", Bool;
+    /// Wing lights.
+    light_wing => "LIGHT WING", Bool;
+    /// Logo lights.
+    light_logo => "LIGHT LOGO", Bool;
+    /// Cabin lights.
+    light_cabin => "LIGHT CABIN", Bool;
+    /// Lights that are actually on, as flags.
+    light_on_states => "LIGHT ON STATES", Mask, read_only;
+    /// Light switch positions, as flags.
+    light_states => "LIGHT STATES", Mask;
+    /// *Indexed* by potentiometer. Dimmer setting.
+    light_potentiometer => "LIGHT POTENTIOMETER", Percent;
+
+    // Autopilot
+    /// Aircraft has an autopilot.
+    autopilot_available => "AUTOPILOT AVAILABLE", Bool, read_only;
+    /// Autopilot engaged.
+    autopilot_master => "AUTOPILOT MASTER", Bool;
+    /// Autopilot was disengaged.
+    autopilot_disengaged => "AUTOPILOT DISENGAGED", Bool, read_only;
+    /// Flight director on.
+    autopilot_flight_director_active => "AUTOPILOT FLIGHT DIRECTOR ACTIVE", Bool;
+    /// Flight director pitch command.
+    autopilot_flight_director_pitch => "AUTOPILOT FLIGHT DIRECTOR PITCH", Degrees;
+    /// Flight director bank command.
+    autopilot_flight_director_bank => "AUTOPILOT FLIGHT DIRECTOR BANK", Degrees;
+    /// Heading mode.
+    autopilot_heading_lock => "AUTOPILOT HEADING LOCK", Bool;
+    /// Selected heading.
+    autopilot_heading_lock_dir => "AUTOPILOT HEADING LOCK DIR", Degrees;
+    /// Altitude hold.
+    autopilot_altitude_lock => "AUTOPILOT ALTITUDE LOCK", Bool;
+    /// Selected altitude.
+    autopilot_altitude_lock_var => "AUTOPILOT ALTITUDE LOCK VAR", Feet;
+    /// Vertical speed mode.
+    autopilot_vertical_hold => "AUTOPILOT VERTICAL HOLD", Bool;
+    /// Selected vertical speed.
+    autopilot_vertical_hold_var => "AUTOPILOT VERTICAL HOLD VAR", FeetPerMinute;
+    /// Airspeed hold.
+    autopilot_airspeed_hold => "AUTOPILOT AIRSPEED HOLD", Bool;
+    /// Selected airspeed.
+    autopilot_airspeed_hold_var => "AUTOPILOT AIRSPEED HOLD VAR", Knots;
+    /// Mach hold.
+    autopilot_mach_hold => "AUTOPILOT MACH HOLD", Bool;
+    /// Selected Mach.
+    autopilot_mach_hold_var => "AUTOPILOT MACH HOLD VAR", Number;
+    /// Flight level change mode.
+    autopilot_flight_level_change => "AUTOPILOT FLIGHT LEVEL CHANGE", Bool;
+    /// Pitch hold.
+    autopilot_pitch_hold => "AUTOPILOT PITCH HOLD", Bool;
+    /// Pitch hold reference.
+    autopilot_pitch_hold_ref => "AUTOPILOT PITCH HOLD REF", Degrees;
+    /// Attitude hold.
+    autopilot_attitude_hold => "AUTOPILOT ATTITUDE HOLD", Bool;
+    /// Wing leveler.
+    autopilot_wing_leveler => "AUTOPILOT WING LEVELER", Bool;
+    /// NAV mode.
+    autopilot_nav1_lock => "AUTOPILOT NAV1 LOCK", Bool;
+    /// NAV radio the autopilot follows.
+    autopilot_nav_selected => "AUTOPILOT NAV SELECTED", Number;
+    /// Approach mode.
+    autopilot_approach_hold => "AUTOPILOT APPROACH HOLD", Bool;
+    /// Glideslope captured.
+    autopilot_glideslope_hold => "AUTOPILOT GLIDESLOPE HOLD", Bool;
+    /// Back course mode.
+    autopilot_backcourse_hold => "AUTOPILOT BACKCOURSE HOLD", Bool;
+    /// Yaw damper.
+    autopilot_yaw_damper => "AUTOPILOT YAW DAMPER", Bool;
+    /// Bank angle limit.
+    autopilot_max_bank => "AUTOPILOT MAX BANK", Degrees;
+    /// Autothrottle armed.
+    autopilot_throttle_arm => "AUTOPILOT THROTTLE ARM", Bool;
+    /// TO/GA mode.
+    autopilot_takeoff_power_active => "AUTOPILOT TAKEOFF POWER ACTIVE", Bool;
+    /// Autothrottle engaged.
+    autothrottle_active => "AUTOTHROTTLE ACTIVE", Bool;
+
+    // Radios and navigation (radios indexed from 1)
+    /// *Indexed.* COM active frequency.
+    com_active_frequency => "COM ACTIVE FREQUENCY", Megahertz;
+    /// *Indexed.* COM standby frequency.
+    com_standby_frequency => "COM STANDBY FREQUENCY", Megahertz;
+    /// *Indexed.* COM transmit selected.
+    com_transmit => "COM TRANSMIT", Bool;
+    /// *Indexed.* COM receive selected.
+    com_receive => "COM RECEIVE", Bool;
+    /// *Indexed.* NAV active frequency.
+    nav_active_frequency => "NAV ACTIVE FREQUENCY", Megahertz;
+    /// *Indexed.* NAV standby frequency.
+    nav_standby_frequency => "NAV STANDBY FREQUENCY", Megahertz;
+    /// *Indexed.* Selected course.
+    nav_obs => "NAV OBS", Degrees;
+    /// *Indexed.* Radial the aircraft is on.
+    nav_radial => "NAV RADIAL", Degrees, read_only;
+    /// *Indexed.* Course deviation, -127..=127.
+    nav_cdi => "NAV CDI", Number, read_only;
+    /// *Indexed.* Glideslope deviation, -119..=119.
+    nav_gsi => "NAV GSI", Number, read_only;
+    /// *Indexed.* Receiving a VOR or localizer.
+    nav_has_nav => "NAV HAS NAV", Bool, read_only;
+    /// *Indexed.* Tuned to a localizer.
+    nav_has_localizer => "NAV HAS LOCALIZER", Bool, read_only;
+    /// *Indexed.* Glideslope available.
+    nav_has_glide_slope => "NAV HAS GLIDE SLOPE", Bool, read_only;
+    /// *Indexed.* DME available.
+    nav_has_dme => "NAV HAS DME", Bool, read_only;
+    /// *Indexed.* DME distance.
+    nav_dme => "NAV DME", NauticalMiles, read_only;
+    /// *Indexed.* DME ground speed.
+    nav_dmespeed => "NAV DMESPEED", Knots, read_only;
+    /// *Indexed.* TO/FROM flag (0 off, 1 to, 2 from).
+    nav_tofrom => "NAV TOFROM", Enum, read_only;
+    /// *Indexed.* Localizer course.
+    nav_localizer => "NAV LOCALIZER", Degrees, read_only;
+    /// *Indexed.* Signal strength.
+    nav_signal => "NAV SIGNAL", Number, read_only;
+    /// *Indexed.* ADF active frequency.
+    adf_active_frequency => "ADF ACTIVE FREQUENCY", Kilohertz;
+    /// *Indexed.* ADF standby frequency.
+    adf_standby_frequency => "ADF STANDBY FREQUENCY", Kilohertz;
+    /// *Indexed.* Relative bearing to the NDB.
+    adf_radial => "ADF RADIAL", Degrees, read_only;
+    /// *Indexed.* ADF signal strength.
+    adf_signal => "ADF SIGNAL", Number, read_only;
+    /// Transponder mode.
+    transponder_state => "TRANSPONDER STATE", Enum;
+    /// Marker beacon (0 none, 1 outer, 2 middle, 3 inner).
+    marker_beacon_state => "MARKER BEACON STATE", Enum;
+    /// GPS drives the NAV1 indicators.
+    gps_drives_nav1 => "GPS DRIVES NAV1", Bool;
+    /// HSI course deviation.
+    hsi_cdi_needle => "HSI CDI NEEDLE", Number, read_only;
+    /// HSI glideslope deviation.
+    hsi_gsi_needle => "HSI GSI NEEDLE", Number, read_only;
+    /// HSI distance.
+    hsi_distance => "HSI DISTANCE", NauticalMiles, read_only;
+    /// HSI bearing pointer.
+    hsi_bearing => "HSI BEARING", Degrees, read_only;
+
+    // GPS (see also `vars::gps`)
+    /// A flight plan is active.
+    gps_is_active_flight_plan => "GPS IS ACTIVE FLIGHT PLAN", Bool;
+    /// Direct-to is active.
+    gps_is_directto_flightplan => "GPS IS DIRECTTO FLIGHTPLAN", Bool;
+    /// An approach is active.
+    gps_is_approach_active => "GPS IS APPROACH ACTIVE", Bool;
+    /// GPS latitude.
+    gps_position_lat => "GPS POSITION LAT", Degrees;
+    /// GPS longitude.
+    gps_position_lon => "GPS POSITION LON", Degrees;
+    /// GPS altitude.
+    gps_position_alt => "GPS POSITION ALT", Feet;
+    /// GPS ground speed.
+    gps_ground_speed => "GPS GROUND SPEED", Knots;
+    /// True ground track.
+    gps_ground_true_track => "GPS GROUND TRUE TRACK", Degrees;
+    /// Magnetic ground track.
+    gps_ground_magnetic_track => "GPS GROUND MAGNETIC TRACK", Degrees;
+    /// Distance to the active waypoint.
+    gps_wp_distance => "GPS WP DISTANCE", NauticalMiles;
+    /// Bearing to the active waypoint.
+    gps_wp_bearing => "GPS WP BEARING", Degrees;
+    /// Cross-track error.
+    gps_wp_cross_trk => "GPS WP CROSS TRK", NauticalMiles;
+    /// Desired track to the active waypoint.
+    gps_wp_desired_track => "GPS WP DESIRED TRACK", Degrees;
+    /// Time to the active waypoint.
+    gps_wp_ete => "GPS WP ETE", Seconds;
+    /// Time to the destination.
+    gps_ete => "GPS ETE", Seconds;
+
+    // Flight controls
+    /// Elevator input.
+    elevator_position => "ELEVATOR POSITION", Position;
+    /// Aileron input.
+    aileron_position => "AILERON POSITION", Position;
+    /// Rudder input.
+    rudder_position => "RUDDER POSITION", Position;
+    /// Elevator deflection.
+    elevator_deflection => "ELEVATOR DEFLECTION", Degrees, read_only;
+    /// Average aileron deflection.
+    aileron_average_deflection => "AILERON AVERAGE DEFLECTION", Degrees, read_only;
+    /// Rudder deflection.
+    rudder_deflection => "RUDDER DEFLECTION", Degrees, read_only;
+    /// Elevator trim angle.
+    elevator_trim_position => "ELEVATOR TRIM POSITION", Degrees;
+    /// Elevator trim, -1..=1.
+    elevator_trim_pct => "ELEVATOR TRIM PCT", PercentOver100;
+    /// Aileron trim, -1..=1.
+    aileron_trim_pct => "AILERON TRIM PCT", PercentOver100;
+    /// Rudder trim, -1..=1.
+    rudder_trim_pct => "RUDDER TRIM PCT", PercentOver100;
+    /// Yoke lateral position.
+    yoke_x_position => "YOKE X POSITION", Position;
+    /// Yoke longitudinal position.
+    yoke_y_position => "YOKE Y POSITION", Position;
+    /// Rudder pedal position.
+    rudder_pedal_position => "RUDDER PEDAL POSITION", Position;
+    /// Flap handle detent.
+    flaps_handle_index => "FLAPS HANDLE INDEX", Number;
+    /// Flap handle position.
+    flaps_handle_percent => "FLAPS HANDLE PERCENT", PercentOver100;
+    /// Number of flap detents.
+    flaps_num_handle_positions => "FLAPS NUM HANDLE POSITIONS", Number, read_only;
+    /// Left trailing edge flap extension.
+    trailing_edge_flaps_left_percent => "TRAILING EDGE FLAPS LEFT PERCENT", PercentOver100;
+    /// Right trailing edge flap extension.
+    trailing_edge_flaps_right_percent => "TRAILING EDGE FLAPS RIGHT PERCENT", PercentOver100;
+    /// Left leading edge flap extension.
+    leading_edge_flaps_left_percent => "LEADING EDGE FLAPS LEFT PERCENT", PercentOver100;
+    /// Spoiler handle.
+    spoilers_handle_position => "SPOILERS HANDLE POSITION", Percent;
+    /// Ground spoilers armed.
+    spoilers_armed => "SPOILERS ARMED", Bool;
+    /// Aircraft has spoilers.
+    spoiler_available => "SPOILER AVAILABLE", Bool, read_only;
+    /// Left spoiler extension.
+    spoilers_left_position => "SPOILERS LEFT POSITION", PercentOver100;
+    /// Left brake.
+    brake_left_position => "BRAKE LEFT POSITION", Position;
+    /// Right brake.
+    brake_right_position => "BRAKE RIGHT POSITION", Position;
+    /// Parking brake set.
+    brake_parking_position => "BRAKE PARKING POSITION", Bool;
+    /// Parking brake indicator.
+    brake_parking_indicator => "BRAKE PARKING INDICATOR", Bool, read_only;
+    /// Autobrake setting.
+    auto_brake_switch_cb => "AUTO BRAKE SWITCH CB", Number;
+    /// Anti-skid active.
+    antiskid_brakes_active => "ANTISKID BRAKES ACTIVE", Bool;
+    /// Tailwheel locked.
+    tailwheel_lock_on => "TAILWHEEL LOCK ON", Bool;
+    /// Nosewheel steering input.
+    steer_input_control => "STEER INPUT CONTROL", PercentOver100;
+
+    // Landing gear
+    /// Gear handle down.
+    gear_handle_position => "GEAR HANDLE POSITION", Bool;
+    /// Nose/center gear extension.
+    gear_center_position => "GEAR CENTER POSITION", PercentOver100;
+    /// Left gear extension.
+    gear_left_position => "GEAR LEFT POSITION", PercentOver100;
+    /// Right gear extension.
+    gear_right_position => "GEAR RIGHT POSITION", PercentOver100;
+    /// Average gear extension.
+    gear_total_pct_extended => "GEAR TOTAL PCT EXTENDED", PercentOver100, read_only;
+    /// *Indexed.* Gear state (0 unknown, 1 up, 2 down).
+    gear_position => "GEAR POSITION", Enum;
+    /// *Indexed.* Gear warning state.
+    gear_warning => "GEAR WARNING", Enum, read_only;
+    /// Aircraft has retractable gear.
+    is_gear_retractable => "IS GEAR RETRACTABLE", Bool, read_only;
+    /// Aircraft is on floats.
+    is_gear_floats => "IS GEAR FLOATS", Bool, read_only;
+    /// Aircraft is a tail dragger.
+    is_tail_dragger => "IS TAIL DRAGGER", Bool, read_only;
+    /// Gear damaged by overspeed.
+    gear_damage_by_speed => "GEAR DAMAGE BY SPEED", Bool, read_only;
+
+    // Systems
+    /// Cabin altitude.
+    pressurization_cabin_altitude => "PRESSURIZATION CABIN ALTITUDE", Feet;
+    /// Cabin climb rate.
+    pressurization_cabin_altitude_rate => "PRESSURIZATION CABIN ALTITUDE RATE", FeetPerMinute;
+    /// Cabin differential pressure.
+    pressurization_pressure_differential => "PRESSURIZATION PRESSURE DIFFERENTIAL", Psi;
+    /// Dump switch.
+    pressurization_dump_switch => "PRESSURIZATION DUMP SWITCH", Bool;
+    /// Pitot heat switch.
+    pitot_heat => "PITOT HEAT", Bool;
+    /// Pitot ice.
+    pitot_ice_pct => "PITOT ICE PCT", PercentOver100;
+    /// Airframe de-ice switch.
+    structural_deice_switch => "STRUCTURAL DEICE SWITCH", Bool;
+    /// Windshield de-ice switch.
+    windshield_deice_switch => "WINDSHIELD DEICE SWITCH", Bool;
+    /// Panel anti-ice switch.
+    panel_anti_ice_switch => "PANEL ANTI ICE SWITCH", Bool;
+    /// Vacuum system suction.
+    suction_pressure => "SUCTION PRESSURE", InchesOfMercury;
+    /// *Indexed.* Hydraulic system pressure.
+    hydraulic_pressure => "HYDRAULIC PRESSURE", Psi;
+    /// Hydraulic pump switch.
+    hydraulic_switch => "HYDRAULIC SWITCH", Bool;
+    /// Low fuel warning.
+    warning_fuel => "WARNING FUEL", Bool, read_only;
+    /// Low height warning.
+    warning_low_height => "WARNING LOW HEIGHT", Bool, read_only;
+    /// Low oil pressure warning.
+    warning_oil_pressure => "WARNING OIL PRESSURE", Bool, read_only;
+    /// Low vacuum warning.
+    warning_vacuum => "WARNING VACUUM", Bool, read_only;
+    /// Low voltage warning.
+    warning_voltage => "WARNING VOLTAGE", Bool, read_only;
+    /// Canopy open.
+    canopy_open => "CANOPY OPEN", Percent;
+    /// *Indexed* by exit. Door/exit open.
+    exit_open => "EXIT OPEN", PercentOver100;
+    /// Smoke system on.
+    smoke_enable => "SMOKE ENABLE", Bool;
+    /// Seatbelt sign.
+    cabin_seatbelts_alert_switch => "CABIN SEATBELTS ALERT SWITCH", Bool;
+    /// No smoking sign.
+    cabin_no_smoking_alert_switch => "CABIN NO SMOKING ALERT SWITCH", Bool;
+}
+
+/// The unit `name` is listed with in [`ALL`]. Case, an `A:` prefix and an
+/// `:index` suffix are ignored.
+pub fn canonical_unit(name: &str) -> Option<Unit> {
+    let name = super::a_var::base_name(name);
+    ALL.iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|&(_, unit)| unit)
+}
+
+/// Whether the SDK lists `name` as settable, or `None` if it's missing from
+/// [`ALL`]. Case, an `A:` prefix and an `:index` suffix are ignored.
+pub fn is_settable(name: &str) -> Option<bool> {
+    let name = super::a_var::base_name(name);
+    ALL.iter()
+        .position(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|i| SETTABLE[i])
+}
+
+/// A unit from a different quantity family than the var's, see [`check_unit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitMismatch {
+    /// The var's unit in [`ALL`].
+    pub expected: Unit,
+    /// Family of the unit that was passed.
+    pub found: UnitFamily,
+}
+
+/// Check `unit` against `name`'s canonical unit, e.g. `PLANE ALTITUDE` in
+/// `knots` fails. Vars missing from [`ALL`], unknown units and unitless
+/// families (ratios, positions, numbers, BCD) always pass, since the sim
+/// accepts those for almost anything.
+pub fn check_unit(name: &str, unit: &str) -> Result<(), UnitMismatch> {
+    let (Some(expected), Some(found)) = (canonical_unit(name), units::lookup(unit)) else {
+        return Ok(());
+    };
+    let unitless = |f: UnitFamily| {
+        matches!(
+            f,
+            UnitFamily::Ratio | UnitFamily::Position | UnitFamily::Discrete | UnitFamily::Encoded
+        )
+    };
+    let expected_family = expected.info().family;
+    if unitless(expected_family) || unitless(found.family) || expected_family == found.family {
+        Ok(())
+    } else {
+        Err(UnitMismatch {
+            expected,
+            found: found.family,
+        })
+    }
+}
@@ -0,0 +1,215 @@
+//! Push-style A-var updates through a SimConnect data definition.
+//!
+//! Instead of reading every var each frame, the sim sends the whole set at a
+//! fixed [`UpdatePeriod`] (optionally only when something changed) and
+//! [`VarSubscription::poll`] hands the latest values to a callback. For dozens
+//! of vars this is much cheaper than polling them one by one.
+//!
+//! ```no_run
+//! use msfs::vars::subscription::{UpdatePeriod, VarSubscription};
+//!
+//! let mut sub = VarSubscription::new(UpdatePeriod::SimFrame).expect("SimConnect unavailable");
+//! let alt = sub.add("PLANE ALTITUDE", "Feet").unwrap();
+//! let ias = sub.add("AIRSPEED INDICATED", "Knots").unwrap();
+//! sub.changed_only(true);
+//! sub.start(move |values| {
+//!     println!("{} ft, {} kt", values[alt], values[ias]);
+//! });
+//!
+//! // in update():
+//! sub.poll();
+//! ```
+
+use crate::simconnect::{self, read_f64, read_u32};
+use crate::sys::{
+    DWORD, HANDLE, SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64, SIMCONNECT_PERIOD,
+    SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_NEVER, SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_SECOND,
+    SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_SIM_FRAME,
+    SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_VISUAL_FRAME,
+    SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA, SimConnect_AddToDataDefinition,
+    SimConnect_Close, SimConnect_RequestDataOnSimObject,
+};
+use std::ffi::CString;
+
+/// How often the sim sends the subscribed values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdatePeriod {
+    /// Every rendered frame.
+    VisualFrame,
+    /// Every simulation frame.
+    SimFrame,
+    /// Once per second.
+    Second,
+}
+
+impl UpdatePeriod {
+    fn raw(self) -> SIMCONNECT_PERIOD {
+        match self {
+            UpdatePeriod::VisualFrame => SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_VISUAL_FRAME,
+            UpdatePeriod::SimFrame => SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_SIM_FRAME,
+            UpdatePeriod::Second => SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_SECOND,
+        }
+    }
+}
+
+const DEFINITION_ID: DWORD = 1;
+const REQUEST_ID: DWORD = 1;
+// `static const`s in SimConnect.h, which bindgen doesn't emit.
+const OBJECT_ID_USER: DWORD = 0;
+const DATA_REQUEST_FLAG_CHANGED: DWORD = 1;
+
+// `SIMCONNECT_RECV_SIMOBJECT_DATA` is packed: dwSize dwVersion dwID dwRequestID
+// dwObjectID dwDefineID dwFlags dwentrynumber dwoutof dwDefineCount, then the
+// data in definition order.
+const DATA: usize = 40;
+
+type Callback = Box<dyn FnMut(&[f64])>;
+
+/// A set of user-aircraft A-vars the sim pushes at a fixed rate.
+///
+/// Add every var, then [`start`](VarSubscription::start); vars can't be added
+/// to a running subscription. Each one opens its own SimConnect client, so
+/// prefer one subscription with many vars over many small ones.
+pub struct VarSubscription {
+    handle: HANDLE,
+    period: UpdatePeriod,
+    changed_only: bool,
+    values: Vec<f64>,
+    callback: Option<Callback>,
+    started: bool,
+    fresh: bool,
+}
+
+impl VarSubscription {
+    /// Open a SimConnect client. `None` if SimConnect is unavailable.
+    pub fn new(period: UpdatePeriod) -> Option<Self> {
+        let handle = simconnect::open("infinity-rs subscription")?;
+        Some(Self {
+            handle,
+            period,
+            changed_only: false,
+            values: Vec::new(),
+            callback: None,
+            started: false,
+            fresh: false,
+        })
+    }
+
+    /// Add an A-var, read in `unit`. Returns its slot in the values slice, or
+    /// `None` if SimConnect rejected it or the subscription already started.
+    pub fn add(&mut self, name: &str, unit: &str) -> Option<usize> {
+        if self.started {
+            return None;
+        }
+        let name = CString::new(name).ok()?;
+        let unit = CString::new(unit).ok()?;
+        let ok = unsafe {
+            SimConnect_AddToDataDefinition(
+                self.handle,
+                DEFINITION_ID,
+                name.as_ptr(),
+                unit.as_ptr(),
+                SIMCONNECT_DATATYPE_SIMCONNECT_DATATYPE_FLOAT64,
+                0.0,
+                u32::MAX as DWORD,
+            ) >= 0
+        };
+        if !ok {
+            return None;
+        }
+        self.values.push(f64::NAN);
+        Some(self.values.len() - 1)
+    }
+
+    /// Only send the set when at least one value changed. Takes effect on the
+    /// next [`start`](VarSubscription::start).
+    pub fn changed_only(&mut self, changed_only: bool) {
+        self.changed_only = changed_only;
+    }
+
+    /// Ask the sim to start sending. `callback` gets every value, by slot,
+    /// from [`poll`](VarSubscription::poll).
+    pub fn start(&mut self, callback: impl FnMut(&[f64]) + 'static) -> bool {
+        self.callback = Some(Box::new(callback));
+        self.request(self.period.raw())
+    }
+
+    /// Stop sending. [`start`](VarSubscription::start) resumes, but new vars
+    /// still can't be added.
+    pub fn stop(&mut self) -> bool {
+        self.request(SIMCONNECT_PERIOD_SIMCONNECT_PERIOD_NEVER)
+    }
+
+    fn request(&mut self, period: SIMCONNECT_PERIOD) -> bool {
+        let flags = if self.changed_only {
+            DATA_REQUEST_FLAG_CHANGED
+        } else {
+            0
+        };
+        let ok = unsafe {
+            SimConnect_RequestDataOnSimObject(
+                self.handle,
+                REQUEST_ID,
+                DEFINITION_ID,
+                OBJECT_ID_USER,
+                period,
+                flags,
+                0,
+                0,
+                0,
+            ) >= 0
+        };
+        self.started |= ok;
+        ok
+    }
+
+    /// Process pending SimConnect messages and, if new values arrived, call
+    /// the callback once with the latest. Returns whether it was called.
+    pub fn poll(&mut self) -> bool {
+        let handle = self.handle;
+        simconnect::dispatch(handle, |bytes| self.receive(bytes));
+        if !std::mem::take(&mut self.fresh) {
+            return false;
+        }
+        if let Some(callback) = self.callback.as_mut() {
+            callback(&self.values);
+        }
+        true
+    }
+
+    /// The latest values, by slot. `NaN` until the first update arrives.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn receive(&mut self, bytes: &[u8]) {
+        if simconnect::recv_id(bytes).map(|id| id as i32)
+            != Some(SIMCONNECT_RECV_ID_SIMCONNECT_RECV_ID_SIMOBJECT_DATA as i32)
+            || read_u32(bytes, 12) != Some(REQUEST_ID as u32)
+        {
+            return;
+        }
+        for (i, value) in self.values.iter_mut().enumerate() {
+            if let Some(v) = read_f64(bytes, DATA + i * 8) {
+                *value = v;
+            }
+        }
+        self.fresh = true;
+    }
+}
+
+impl Drop for VarSubscription {
+    fn drop(&mut self) {
+        unsafe {
+            SimConnect_Close(self.handle);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// The bundled unit table, shared with `msfs_derive`, which `include!`s it to
+// check `#[var(unit = "...")]` at compile time. Each crate defines its own
+// `units!` macro to expand it. One line per unit: family, canonical name as
+// documented in the SDK, then accepted aliases.
+units! {
+    Length: "meters" ["meter", "m"];
+    Length: "centimeters" ["centimeter", "cm"];
+    Length: "millimeters" ["millimeter", "mm"];
+    Length: "kilometers" ["kilometer", "km"];
+    Length: "feet" ["foot", "ft"];
+    Length: "inches" ["inch", "in"];
+    Length: "yards" ["yard", "yd"];
+    Length: "miles" ["mile"];
+    Length: "nautical miles" ["nautical mile", "nmile", "nmiles"];
+    Area: "square meters" ["square meter", "sq m", "m2"];
+    Area: "square feet" ["square foot", "sq ft", "ft2"];
+    Area: "square inches" ["square inch", "sq in", "in2"];
+    Volume: "cubic meters" ["cubic meter", "cu m", "m3"];
+    Volume: "cubic feet" ["cubic foot", "cu ft", "ft3"];
+    Volume: "cubic inches" ["cubic inch", "cu in", "in3"];
+    Volume: "liters" ["liter"];
+    Volume: "gallons" ["gallon"];
+    Volume: "quarts" ["quart"];
+    Angle: "radians" ["radian"];
+    Angle: "degrees" ["degree"];
+    Angle: "grads" ["grad"];
+    Angle: "degrees latitude" ["degree latitude"];
+    Angle: "degrees longitude" ["degree longitude"];
+    AngularVelocity: "radians per second" ["radian per second"];
+    AngularVelocity: "degrees per second" ["degree per second"];
+    AngularVelocity: "rpm" ["rpms", "revolution per minute", "revolutions per minute"];
+    Speed: "knots" ["knot", "kt", "kts"];
+    Speed: "meters per second" ["meter per second", "m/s"];
+    Speed: "meters per minute" ["meter per minute"];
+    Speed: "feet per second" ["foot per second", "ft/s"];
+    Speed: "feet per minute" ["foot per minute", "ft/min"];
+    Speed: "kilometers per hour" ["kilometer per hour", "kph", "km/h"];
+    Speed: "miles per hour" ["mile per hour", "mph"];
+    Speed: "mach" ["machs"];
+    Acceleration: "meters per second squared" ["meter per second squared"];
+    Acceleration: "feet per second squared" ["foot per second squared"];
+    Acceleration: "gforce" ["g force"];
+    AngularAcceleration: "radians per second squared" ["radian per second squared"];
+    AngularAcceleration: "degrees per second squared" ["degree per second squared"];
+    Time: "seconds" ["second", "sec", "secs"];
+    Time: "minutes" ["minute", "min"];
+    Time: "hours" ["hour"];
+    Time: "days" ["day"];
+    Pressure: "pascals" ["pascal", "pa"];
+    Pressure: "kilopascals" ["kilopascal", "kpa"];
+    Pressure: "millibars" ["millibar", "mbar", "mbars", "hectopascal", "hectopascals"];
+    Pressure: "inches of mercury" ["inch of mercury", "inhg", "in hg"];
+    Pressure: "millimeters of mercury" ["millimeter of mercury", "mmhg", "mm hg"];
+    Pressure: "psi" ["pound per square inch", "pounds per square inch"];
+    Pressure: "psf" ["pound per square foot", "pounds per square foot"];
+    Pressure: "atmospheres" ["atmosphere", "atm"];
+    Temperature: "celsius" ["degrees celsius", "c"];
+    Temperature: "fahrenheit" ["degrees fahrenheit", "f"];
+    Temperature: "kelvin" ["k"];
+    Temperature: "rankine" ["degrees rankine"];
+    Mass: "kilograms" ["kilogram", "kg", "kgs"];
+    Mass: "pounds" ["pound", "lbs", "lb"];
+    Mass: "slugs" ["slug"];
+    Force: "newtons" ["newton", "n"];
+    Force: "pounds force" ["pound force", "lbf"];
+    Torque: "newton meters" ["newton meter", "nm"];
+    Torque: "foot pounds" ["foot pound", "ft-lbs", "foot-pound"];
+    MassFlow: "pounds per hour" ["pound per hour", "pph"];
+    MassFlow: "kilograms per second" ["kilogram per second"];
+    VolumeFlow: "gallons per hour" ["gallon per hour", "gph"];
+    VolumeFlow: "liters per hour" ["liter per hour"];
+    Density: "slugs per cubic feet" ["slug per cubic foot", "slug/ft3"];
+    Density: "kilograms per cubic meter" ["kilogram per cubic meter", "kg/m3"];
+    Density: "pounds per gallon" ["pound per gallon"];
+    MomentOfInertia: "slug feet squared" ["slug foot squared", "slugs feet squared"];
+    MomentOfInertia: "kilogram meters squared" ["kilogram meter squared"];
+    Frequency: "hertz" ["hz"];
+    Frequency: "kilohertz" ["khz"];
+    Frequency: "megahertz" ["mhz"];
+    Electrical: "volts" ["volt"];
+    Electrical: "amperes" ["ampere", "amps", "amp"];
+    Power: "watts" ["watt"];
+    Power: "kilowatts" ["kilowatt"];
+    Power: "horsepower" ["hp"];
+    Power: "ft lb per second" ["foot pounds per second"];
+    Ratio: "percent" ["percentage"];
+    Ratio: "percent over 100" ["percent scaler 16k"];
+    Ratio: "ratio" ["part", "scalar"];
+    Position: "position" [];
+    Position: "position 16k" [];
+    Position: "position 32k" [];
+    Position: "position 128" [];
+    Discrete: "number" ["numbers"];
+    Discrete: "bool" ["boolean"];
+    Discrete: "enum" [];
+    Discrete: "mask" ["flags"];
+    Encoded: "bco16" [];
+    Encoded: "bcd16" [];
+    Encoded: "bcd32" [];
+    Encoded: "frequency bcd16" [];
+    Encoded: "frequency bcd32" [];
+    Encoded: "frequency adf bcd32" [];
+}
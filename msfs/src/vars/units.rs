@@ -0,0 +1,371 @@
+//! Unit introspection.
+//!
+//! The SDK only resolves unit names to ids, so this module ships a table of the
+//! documented simulation units (canonical name, accepted aliases, quantity family)
+//! and uses it for reverse lookups and validation.
+
+use super::UnitId;
+use crate::sys::FsUnitId;
+use std::{
+    collections::HashMap,
+    sync::{
+        OnceLock,
+        atomic::{AtomicI32, Ordering},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitFamily {
+    Length,
+    Area,
+    Volume,
+    Angle,
+    AngularVelocity,
+    Speed,
+    Acceleration,
+    AngularAcceleration,
+    Time,
+    Pressure,
+    Temperature,
+    Mass,
+    Force,
+    Torque,
+    MassFlow,
+    VolumeFlow,
+    Density,
+    MomentOfInertia,
+    Frequency,
+    Electrical,
+    Power,
+    Ratio,
+    Position,
+    Discrete,
+    Encoded,
+}
+
+#[derive(Debug)]
+pub struct UnitInfo {
+    /// Canonical unit name as documented in the SDK.
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub family: UnitFamily,
+}
+
+impl UnitInfo {
+    /// Whether `name` (case-insensitive) refers to this unit.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.trim();
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+    }
+}
+
+macro_rules! units {
+    ($($family:ident: $name:literal $([$($alias:literal),* $(,)?])?;)*) => {
+        &[$(UnitInfo {
+            name: $name,
+            aliases: &[$($($alias),*)?],
+            family: UnitFamily::$family,
+        }),*]
+    };
+}
+
+static UNITS: &[UnitInfo] = include!("unit_table.rs");
+
+/// All bundled units, in table order.
+#[inline]
+pub fn all() -> &'static [UnitInfo] {
+    UNITS
+}
+
+/// Find a bundled unit by canonical name or alias (case-insensitive).
+pub fn lookup(name: &str) -> Option<&'static UnitInfo> {
+    UNITS.iter().find(|u| u.matches(name))
+}
+
+/// Whether `name` is in the bundled table. Does not call into the sim.
+#[inline]
+pub fn is_known(name: &str) -> bool {
+    lookup(name).is_some()
+}
+
+/// Whether the sim resolves `name` to a unit id.
+pub fn is_valid(name: &str) -> bool {
+    UnitId::from_str(name).is_ok_and(|id| id.is_valid())
+}
+
+fn reverse_table() -> &'static HashMap<FsUnitId, &'static UnitInfo> {
+    static TABLE: OnceLock<HashMap<FsUnitId, &'static UnitInfo>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut map = HashMap::new();
+        for info in UNITS {
+            if let Ok(id) = UnitId::from_str(info.name)
+                && id.is_valid()
+            {
+                map.entry(id.0).or_insert(info);
+            }
+        }
+        map
+    })
+}
+
+impl UnitId {
+    /// `false` when the sim did not recognise the unit name.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.0 >= 0
+    }
+
+    /// Bundled metadata for this id. The first call resolves the whole table.
+    pub fn info(&self) -> Option<&'static UnitInfo> {
+        reverse_table().get(&self.0).copied()
+    }
+
+    /// Canonical name for this id, if it is in the bundled table.
+    pub fn name(&self) -> Option<&'static str> {
+        self.info().map(|u| u.name)
+    }
+}
+
+macro_rules! unit_enum {
+    ($($variant:ident => $name:literal,)*) => {
+        /// Common units, for compile-time checked var declarations.
+        ///
+        /// Each resolves to its [`UnitId`] on first use and caches it; the string
+        /// overloads (`Var::new(name, "knots")`) remain for everything else.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Unit {
+            $($variant,)*
+        }
+
+        impl Unit {
+            pub const ALL: &'static [Unit] = &[$(Unit::$variant,)*];
+
+            /// Canonical name in the bundled table.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Unit::$variant => $name,)*
+                }
+            }
+        }
+    };
+}
+
+unit_enum! {
+    Meters => "meters",
+    Feet => "feet",
+    Inches => "inches",
+    Kilometers => "kilometers",
+    NauticalMiles => "nautical miles",
+    Knots => "knots",
+    MetersPerSecond => "meters per second",
+    FeetPerSecond => "feet per second",
+    FeetPerMinute => "feet per minute",
+    KilometersPerHour => "kilometers per hour",
+    Mach => "mach",
+    GForce => "gforce",
+    Degrees => "degrees",
+    Radians => "radians",
+    DegreesPerSecond => "degrees per second",
+    RadiansPerSecond => "radians per second",
+    Rpm => "rpm",
+    Seconds => "seconds",
+    Minutes => "minutes",
+    Hours => "hours",
+    InchesOfMercury => "inches of mercury",
+    Millibars => "millibars",
+    Psi => "psi",
+    Celsius => "celsius",
+    Fahrenheit => "fahrenheit",
+    Kelvin => "kelvin",
+    Kilograms => "kilograms",
+    Pounds => "pounds",
+    Gallons => "gallons",
+    Liters => "liters",
+    PoundsPerHour => "pounds per hour",
+    GallonsPerHour => "gallons per hour",
+    Hertz => "hertz",
+    Kilohertz => "kilohertz",
+    Megahertz => "megahertz",
+    Volts => "volts",
+    Amperes => "amperes",
+    Percent => "percent",
+    PercentOver100 => "percent over 100",
+    Ratio => "ratio",
+    Position => "position",
+    Number => "number",
+    Bool => "bool",
+    Enum => "enum",
+    Mask => "mask",
+    Bcd16 => "bcd16",
+    FrequencyBcd16 => "frequency bcd16",
+    FrequencyBcd32 => "frequency bcd32",
+}
+
+/// Not yet resolved; real ids are `>= 0` and the sim's "unknown" is negative but
+/// never `i32::MIN`.
+const UNRESOLVED: i32 = i32::MIN;
+
+static RESOLVED: [AtomicI32; Unit::ALL.len()] =
+    [const { AtomicI32::new(UNRESOLVED) }; Unit::ALL.len()];
+
+impl Unit {
+    /// The sim's id for this unit, resolved on first use.
+    pub fn id(self) -> UnitId {
+        let slot = &RESOLVED[self as usize];
+        let cached = slot.load(Ordering::Relaxed);
+        if cached != UNRESOLVED {
+            return UnitId(cached);
+        }
+        // Table names never contain NUL.
+        let id = UnitId::from_str(self.name()).unwrap_or(UnitId(-1));
+        slot.store(id.0, Ordering::Relaxed);
+        id
+    }
+
+    /// Bundled metadata (aliases, family).
+    pub fn info(self) -> &'static UnitInfo {
+        lookup(self.name()).expect("every Unit is in the bundled table")
+    }
+
+    #[inline]
+    pub fn family(self) -> UnitFamily {
+        self.info().family
+    }
+}
+
+impl From<Unit> for UnitId {
+    #[inline]
+    fn from(unit: Unit) -> Self {
+        unit.id()
+    }
+}
+
+impl Unit {
+    /// `(scale, offset)` such that `value * scale + offset` is in the family's SI
+    /// unit. `None` for units that are not a plain linear scale (mach, bool, BCD,
+    /// ...) or whose family mixes quantities (volts and amperes).
+    const fn to_base(self) -> Option<(f64, f64)> {
+        const DEG: f64 = core::f64::consts::PI / 180.0;
+        const FOOT: f64 = 0.3048;
+        const POUND: f64 = 0.45359237;
+        const GALLON: f64 = 0.003785411784;
+        Some(match self {
+            Unit::Meters => (1.0, 0.0),
+            Unit::Feet => (FOOT, 0.0),
+            Unit::Inches => (0.0254, 0.0),
+            Unit::Kilometers => (1000.0, 0.0),
+            Unit::NauticalMiles => (1852.0, 0.0),
+            Unit::Knots => (1852.0 / 3600.0, 0.0),
+            Unit::MetersPerSecond => (1.0, 0.0),
+            Unit::FeetPerSecond => (FOOT, 0.0),
+            Unit::FeetPerMinute => (FOOT / 60.0, 0.0),
+            Unit::KilometersPerHour => (1.0 / 3.6, 0.0),
+            Unit::GForce => (9.80665, 0.0),
+            Unit::Degrees => (DEG, 0.0),
+            Unit::Radians => (1.0, 0.0),
+            Unit::DegreesPerSecond => (DEG, 0.0),
+            Unit::RadiansPerSecond => (1.0, 0.0),
+            Unit::Rpm => (2.0 * core::f64::consts::PI / 60.0, 0.0),
+            Unit::Seconds => (1.0, 0.0),
+            Unit::Minutes => (60.0, 0.0),
+            Unit::Hours => (3600.0, 0.0),
+            Unit::InchesOfMercury => (3386.389, 0.0),
+            Unit::Millibars => (100.0, 0.0),
+            Unit::Psi => (6894.757293168, 0.0),
+            Unit::Celsius => (1.0, 273.15),
+            Unit::Fahrenheit => (5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+            Unit::Kelvin => (1.0, 0.0),
+            Unit::Kilograms => (1.0, 0.0),
+            Unit::Pounds => (POUND, 0.0),
+            Unit::Gallons => (GALLON, 0.0),
+            Unit::Liters => (0.001, 0.0),
+            Unit::PoundsPerHour => (POUND / 3600.0, 0.0),
+            Unit::GallonsPerHour => (GALLON / 3600.0, 0.0),
+            Unit::Hertz => (1.0, 0.0),
+            Unit::Kilohertz => (1e3, 0.0),
+            Unit::Megahertz => (1e6, 0.0),
+            Unit::Percent => (0.01, 0.0),
+            Unit::PercentOver100 | Unit::Ratio => (1.0, 0.0),
+            _ => return None,
+        })
+    }
+}
+
+/// Convert `value` between two units of the same quantity, e.g. knots to
+/// meters per second or Fahrenheit to Celsius.
+///
+/// Uses a built-in table, so it works outside the sim and costs no FFI call.
+/// Returns `None` when the units measure different things or either one is
+/// not linear (mach, BCD, bool, ...); converting a unit to itself always works.
+pub fn try_convert(value: f64, from: Unit, to: Unit) -> Option<f64> {
+    if from == to {
+        return Some(value);
+    }
+    if from.family() != to.family() {
+        return None;
+    }
+    let (from_scale, from_offset) = from.to_base()?;
+    let (to_scale, to_offset) = to.to_base()?;
+    Some((value * from_scale + from_offset - to_offset) / to_scale)
+}
+
+/// [`try_convert`], with `NaN` for incompatible units.
+///
+/// ```
+/// use msfs::vars::{Unit, convert};
+///
+/// let mps = convert(100.0, Unit::Knots, Unit::MetersPerSecond); // 51.44...
+/// let c = convert(212.0, Unit::Fahrenheit, Unit::Celsius); // 100.0
+/// ```
+#[inline]
+pub fn convert(value: f64, from: Unit, to: Unit) -> f64 {
+    try_convert(value, from, to).unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Option<f64>, b: f64) -> bool {
+        a.is_some_and(|a| (a - b).abs() < 1e-9)
+    }
+
+    #[test]
+    fn converts_linear_units() {
+        assert!(close(
+            try_convert(100.0, Unit::Knots, Unit::MetersPerSecond),
+            51.444444444
+        ));
+        assert!(close(try_convert(1000.0, Unit::Feet, Unit::Meters), 304.8));
+        assert!(close(try_convert(304.8, Unit::Meters, Unit::Feet), 1000.0));
+    }
+
+    #[test]
+    fn converts_with_offset() {
+        assert!(close(
+            try_convert(212.0, Unit::Fahrenheit, Unit::Celsius),
+            100.0
+        ));
+        assert!(close(
+            try_convert(-40.0, Unit::Celsius, Unit::Fahrenheit),
+            -40.0
+        ));
+        assert!(close(try_convert(0.0, Unit::Celsius, Unit::Kelvin), 273.15));
+    }
+
+    #[test]
+    fn same_unit_is_identity() {
+        assert_eq!(try_convert(0.8, Unit::Mach, Unit::Mach), Some(0.8));
+        assert_eq!(try_convert(1.0, Unit::Bool, Unit::Bool), Some(1.0));
+        assert_eq!(convert(42.0, Unit::Feet, Unit::Feet), 42.0);
+    }
+
+    #[test]
+    fn rejects_incompatible_units() {
+        assert_eq!(try_convert(1.0, Unit::Feet, Unit::Kelvin), None);
+        assert_eq!(try_convert(0.8, Unit::Mach, Unit::Knots), None);
+        assert_eq!(try_convert(1.0, Unit::Bool, Unit::Percent), None);
+        assert!(convert(1.0, Unit::Knots, Unit::Seconds).is_nan());
+    }
+}
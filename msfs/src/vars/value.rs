@@ -0,0 +1,66 @@
+//! Conversions between var values (always `f64` on the wire) and Rust types.
+
+/// A type a var can be read as or written from; see [`Var::get_as`](super::Var::get_as).
+///
+/// Integers are rounded to nearest and saturate at the type's bounds (`NaN`
+/// becomes 0); `bool` is `true` for anything that rounds to non-zero.
+/// Implement it for your own newtypes to use them with `get_as`/`set_from`.
+pub trait VarValue: Sized {
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl VarValue for f64 {
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl VarValue for f32 {
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl VarValue for bool {
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value.round() != 0.0
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        if self { 1.0 } else { 0.0 }
+    }
+}
+
+macro_rules! int_var_value {
+    ($($t:ty),*) => {$(
+        impl VarValue for $t {
+            #[inline]
+            fn from_f64(value: f64) -> Self {
+                // `as` saturates and maps NaN to 0.
+                value.round() as $t
+            }
+
+            #[inline]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+int_var_value!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
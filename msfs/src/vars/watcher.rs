@@ -0,0 +1,133 @@
+//! Change detection for vars, pumped from `update()`.
+//!
+//! # Examples
+//! ```no_run
+//! use msfs::vars::{AVar, LVar, VarWatcher};
+//!
+//! let mut watcher = VarWatcher::new();
+//! watcher.watch(AVar::new("PLANE ALTITUDE", "Feet")?, 10.0, |alt, _old| {
+//!     println!("altitude now {alt:.0} ft");
+//! });
+//! watcher.watch(LVar::new("L:MY_MODE", "Number")?, 0.0, |mode, old| {
+//!     println!("mode {old:?} -> {mode}");
+//! });
+//!
+//! // in update():
+//! watcher.update();
+//! # Ok::<(), msfs::vars::VarError>(())
+//! ```
+
+use super::{Var, VarKind, VarResult};
+
+/// Handle returned by [`VarWatcher::watch`], for [`VarWatcher::unwatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchId(u64);
+
+struct Entry {
+    id: WatchId,
+    read: Box<dyn Fn() -> VarResult<f64>>,
+    epsilon: f64,
+    last: Option<f64>,
+    cb: Box<dyn FnMut(f64, Option<f64>)>,
+}
+
+/// Calls back when a watched value moves by more than its epsilon.
+///
+/// The first successful read of each var is reported with `old = None`. Failed
+/// reads are skipped and compared against the last good value next time.
+#[derive(Default)]
+pub struct VarWatcher {
+    entries: Vec<Entry>,
+    next_id: u64,
+}
+
+impl VarWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Watch `var`; `cb(new, old)` runs from [`VarWatcher::update`] when
+    /// `|new - old| > epsilon`. An epsilon of `0.0` reports every change.
+    pub fn watch<K: VarKind + 'static>(
+        &mut self,
+        var: Var<K>,
+        epsilon: f64,
+        cb: impl FnMut(f64, Option<f64>) + 'static,
+    ) -> WatchId {
+        self.watch_with(move || var.get(), epsilon, cb)
+    }
+
+    /// Watch one index of an indexed var, e.g. engine 2.
+    pub fn watch_indexed<K: VarKind + 'static>(
+        &mut self,
+        var: Var<K>,
+        index: u32,
+        epsilon: f64,
+        cb: impl FnMut(f64, Option<f64>) + 'static,
+    ) -> WatchId {
+        self.watch_with(move || var.get_indexed(index), epsilon, cb)
+    }
+
+    /// Watch any value, e.g. a var with a custom target or a derived quantity.
+    pub fn watch_with(
+        &mut self,
+        read: impl Fn() -> VarResult<f64> + 'static,
+        epsilon: f64,
+        cb: impl FnMut(f64, Option<f64>) + 'static,
+    ) -> WatchId {
+        let id = WatchId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry {
+            id,
+            read: Box::new(read),
+            epsilon: epsilon.abs(),
+            last: None,
+            cb: Box::new(cb),
+        });
+        id
+    }
+
+    /// Stop watching. Returns `false` if `id` was not registered.
+    pub fn unwatch(&mut self, id: WatchId) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        self.entries.len() != before
+    }
+
+    /// Read every watched var and run the callbacks of those that changed.
+    /// Returns how many callbacks ran.
+    pub fn update(&mut self) -> usize {
+        let mut fired = 0;
+        for entry in &mut self.entries {
+            let Ok(value) = (entry.read)() else {
+                continue;
+            };
+            let changed = match entry.last {
+                None => true,
+                Some(last) if last.is_nan() || value.is_nan() => last.is_nan() != value.is_nan(),
+                Some(last) => (value - last).abs() > entry.epsilon,
+            };
+            if changed {
+                let old = entry.last.replace(value);
+                (entry.cb)(value, old);
+                fired += 1;
+            }
+        }
+        fired
+    }
+
+    /// Forget the last values so every var is reported again on the next update.
+    pub fn reset(&mut self) {
+        for entry in &mut self.entries {
+            entry.last = None;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
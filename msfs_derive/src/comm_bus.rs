@@ -0,0 +1,325 @@
+use proc_macro::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{
+    Data, DeriveInput, Fields, GenericArgument, LitStr, PathArguments, Type, spanned::Spanned,
+};
+
+struct MessageSpec {
+    event: String,
+    ts_export: Option<String>,
+}
+
+pub(crate) fn derive_comm_bus_message_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let input_span = input.span();
+    let struct_ident = input.ident.clone();
+
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => named.named.clone(),
+            _ => {
+                return Err(syn::Error::new(
+                    s.fields.span(),
+                    "CommBusMessage can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input_span,
+                "CommBusMessage can only be derived for structs",
+            ));
+        }
+    };
+
+    let spec = parse_message_attr(&input)?;
+
+    let ts_fields = fields
+        .iter()
+        .filter_map(|f| f.ident.as_ref().map(|i| (i.to_string(), &f.ty)))
+        .map(|(name, ty)| ts_field(&name, ty))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let schema = schema_hash(&struct_ident.to_string(), &fields);
+    let typescript = render_typescript(&struct_ident.to_string(), &spec.event, schema, &ts_fields);
+
+    if let Some(dir) = &spec.ts_export {
+        write_typescript(dir, &struct_ident.to_string(), &typescript)
+            .map_err(|e| syn::Error::new(input_span, e))?;
+    }
+
+    let event_lit = LitStr::new(&spec.event, struct_ident.span());
+    let ts_lit = LitStr::new(&typescript, struct_ident.span());
+
+    let json = json_impl(&struct_ident, &fields);
+
+    let expanded = quote! {
+        #json
+
+        impl ::msfs::comm_bus::CommBusMessage for #struct_ident {
+            const EVENT: &'static str = #event_lit;
+            const TYPESCRIPT: &'static str = #ts_lit;
+            const SCHEMA_HASH: u32 = #schema;
+        }
+    };
+
+    Ok(expanded.into())
+}
+
+/// JSON object with one key per field, named as in the TypeScript interface.
+fn json_impl(
+    struct_ident: &syn::Ident,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> proc_macro2::TokenStream {
+    let idents: Vec<_> = fields.iter().filter_map(|f| f.ident.as_ref()).collect();
+    let keys: Vec<_> = idents
+        .iter()
+        .map(|i| LitStr::new(&i.to_string(), i.span()))
+        .collect();
+    let locals: Vec<_> = idents
+        .iter()
+        .map(|i| quote::format_ident!("__f_{}", i))
+        .collect();
+    let types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+
+    let writes = keys
+        .iter()
+        .zip(&idents)
+        .enumerate()
+        .map(|(n, (key, ident))| {
+            let sep = if n == 0 { "" } else { "," };
+            let prefix = LitStr::new(&format!("{sep}\"{}\":", key.value()), key.span());
+            quote! {
+                out.push_str(#prefix);
+                ::msfs::comm_bus::json::Json::write_json(&self.#ident, out);
+            }
+        });
+
+    quote! {
+        impl ::msfs::comm_bus::json::Json for #struct_ident {
+            fn write_json(&self, out: &mut ::std::string::String) {
+                out.push('{');
+                #(#writes)*
+                out.push('}');
+            }
+
+            fn read_json(
+                r: &mut ::msfs::comm_bus::json::Reader<'_>,
+            ) -> ::core::result::Result<Self, ::msfs::comm_bus::json::DecodeError> {
+                #(let mut #locals: ::core::option::Option<#types> = ::core::option::Option::None;)*
+
+                r.begin_object()?;
+                let mut first = true;
+                while let ::core::option::Option::Some(key) = r.next_key(first)? {
+                    first = false;
+                    match key.as_str() {
+                        #(#keys => #locals = ::core::option::Option::Some(
+                            ::msfs::comm_bus::json::Json::read_json(r)?
+                        ),)*
+                        _ => r.skip_value()?,
+                    }
+                }
+
+                ::core::result::Result::Ok(Self {
+                    #(#idents: match #locals.or_else(<#types as ::msfs::comm_bus::json::Json>::missing) {
+                        ::core::option::Option::Some(v) => v,
+                        ::core::option::Option::None => {
+                            return ::core::result::Result::Err(r.error(concat!("missing field ", #keys)));
+                        }
+                    },)*
+                })
+            }
+        }
+    }
+}
+
+fn parse_message_attr(input: &DeriveInput) -> syn::Result<MessageSpec> {
+    let attr = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("comm_bus"))
+        .ok_or_else(|| {
+            syn::Error::new(
+                input.ident.span(),
+                "missing #[comm_bus(event = \"...\")] attribute",
+            )
+        })?;
+
+    let mut event: Option<String> = None;
+    let mut ts_export: Option<String> = None;
+
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("event") {
+            let lit: LitStr = meta.value()?.parse()?;
+            event = Some(lit.value());
+            return Ok(());
+        }
+        if meta.path.is_ident("ts_export") {
+            let lit: LitStr = meta.value()?.parse()?;
+            ts_export = Some(lit.value());
+            return Ok(());
+        }
+
+        Err(meta.error("unsupported #[comm_bus(...)] key"))
+    })?;
+
+    let event = event.ok_or_else(|| syn::Error::new(attr.span(), "#[comm_bus] requires event"))?;
+
+    Ok(MessageSpec { event, ts_export })
+}
+
+/// Maps a Rust field type onto the TypeScript type JS sees after decoding.
+fn ts_type(ty: &Type) -> syn::Result<String> {
+    match ty {
+        Type::Reference(r) => ts_type(&r.elem),
+        Type::Array(a) => Ok(format!("{}[]", ts_type(&a.elem)?)),
+        Type::Slice(s) => Ok(format!("{}[]", ts_type(&s.elem)?)),
+        Type::Tuple(t) if t.elems.is_empty() => Ok("null".to_string()),
+        Type::Tuple(t) => {
+            let elems = t
+                .elems
+                .iter()
+                .map(ts_type)
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(format!("[{}]", elems.join(", ")))
+        }
+        Type::Path(p) if p.qself.is_none() => {
+            let seg = p
+                .path
+                .segments
+                .last()
+                .ok_or_else(|| syn::Error::new(p.span(), "unsupported field type"))?;
+            let name = seg.ident.to_string();
+            match name.as_str() {
+                "f32" | "f64" | "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32"
+                | "u64" | "usize" => Ok("number".to_string()),
+                "bool" => Ok("boolean".to_string()),
+                "String" | "str" | "char" => Ok("string".to_string()),
+                "Vec" | "VecDeque" => Ok(format!("{}[]", ts_type(single_generic(seg)?)?)),
+                "Option" => Ok(format!("({} | null)", ts_type(single_generic(seg)?)?)),
+                "Box" => ts_type(single_generic(seg)?),
+                _ => Ok(name),
+            }
+        }
+        other => Err(syn::Error::new(
+            other.span(),
+            "CommBusMessage cannot map this field type to TypeScript",
+        )),
+    }
+}
+
+fn single_generic(seg: &syn::PathSegment) -> syn::Result<&Type> {
+    if let PathArguments::AngleBracketed(args) = &seg.arguments
+        && let Some(GenericArgument::Type(ty)) = args.args.first()
+    {
+        return Ok(ty);
+    }
+    Err(syn::Error::new(
+        seg.span(),
+        format!("expected a type parameter on {}", seg.ident),
+    ))
+}
+
+fn ts_field(name: &str, ty: &Type) -> syn::Result<String> {
+    Ok(format!("    {name}: {};", ts_type(ty)?))
+}
+
+/// FNV-1a over the struct name and each field's name and type, so any layout change
+/// (rename, reorder, retype) yields a different hash on both sides of the bus.
+fn schema_hash(
+    struct_name: &str,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+) -> u32 {
+    let mut canonical = format!("{struct_name}{{");
+    for f in fields {
+        let name = f.ident.as_ref().map(|i| i.to_string()).unwrap_or_default();
+        let ty = f.ty.to_token_stream().to_string().replace(' ', "");
+        canonical.push_str(&format!("{name}:{ty};"));
+    }
+    canonical.push('}');
+
+    let mut hash: u32 = 0x811c_9dc5;
+    for b in canonical.bytes() {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+fn render_typescript(struct_name: &str, event: &str, schema: u32, fields: &[String]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "// Generated by msfs_derive from `{struct_name}`. Do not edit.\n"
+    ));
+    let prefix = screaming_snake(struct_name);
+    out.push_str(&format!("export const {prefix}_EVENT = {event:?};\n"));
+    out.push_str(&format!(
+        "export const {prefix}_SCHEMA = 0x{schema:08x};\n\n"
+    ));
+    out.push_str(&format!("export interface {struct_name} {{\n"));
+    for f in fields {
+        out.push_str(f);
+        out.push('\n');
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn write_typescript(dir: &str, struct_name: &str, contents: &str) -> Result<(), String> {
+    let root = std::env::var("CARGO_MANIFEST_DIR").map_err(|e| e.to_string())?;
+    let dir = std::path::Path::new(&root).join(dir);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+
+    let path = dir.join(format!("{struct_name}.ts"));
+    // Skip the write when nothing changed so JS tooling watching the folder stays quiet.
+    if std::fs::read_to_string(&path).is_ok_and(|old| old == contents) {
+        return Ok(());
+    }
+    std::fs::write(&path, contents).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+/// `FmsState` -> `FMS_STATE`; a run of capitals is one word, so
+/// `HTTPResponse` -> `HTTP_RESPONSE`.
+pub(crate) fn screaming_snake(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i != 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.push(ch.to_ascii_uppercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(ty: &str) -> String {
+        ts_type(&syn::parse_str(ty).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn ts_types() {
+        assert_eq!(ts("f64"), "number");
+        assert_eq!(ts("Option<f64>"), "(number | null)");
+        assert_eq!(ts("Vec<Option<f64>>"), "(number | null)[]");
+        assert_eq!(ts("Option<Vec<String>>"), "(string[] | null)");
+        assert_eq!(ts("(u8, bool)"), "[number, boolean]");
+    }
+
+    #[test]
+    fn screaming_snake_words() {
+        assert_eq!(screaming_snake("FmsState"), "FMS_STATE");
+        assert_eq!(screaming_snake("HTTPResponse"), "HTTP_RESPONSE");
+        assert_eq!(screaming_snake("ApMaster"), "AP_MASTER");
+        assert_eq!(screaming_snake("Com1Radio"), "COM1_RADIO");
+        assert_eq!(screaming_snake("ToggleGPS"), "TOGGLE_GPS");
+        assert_eq!(screaming_snake("Nav"), "NAV");
+    }
+}
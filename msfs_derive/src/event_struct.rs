@@ -0,0 +1,93 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, spanned::Spanned};
+
+use crate::comm_bus::screaming_snake;
+
+struct EventSpec {
+    variant: syn::Ident,
+    name: String,
+}
+
+pub(crate) fn derive_event_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let enum_ident = &input.ident;
+
+    let variants = match &input.data {
+        Data::Enum(e) => &e.variants,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "EventStruct can only be derived for enums (one unit variant per key event)",
+            ));
+        }
+    };
+
+    let mut specs = Vec::new();
+    for v in variants {
+        if !matches!(v.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                v.fields.span(),
+                "EventStruct variants must be unit variants",
+            ));
+        }
+        specs.push(EventSpec {
+            variant: v.ident.clone(),
+            name: parse_event_attr(v)?.unwrap_or_else(|| screaming_snake(&v.ident.to_string())),
+        });
+    }
+
+    let arms = specs.iter().map(|s| {
+        let variant = &s.variant;
+        let name = LitStr::new(&s.name, variant.span());
+        quote!(Self::#variant => #name)
+    });
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics #enum_ident #ty_generics #where_clause {
+            /// Key event name, without the `K:` prefix.
+            pub const fn event_name(&self) -> &'static str {
+                match self {
+                    #(#arms,)*
+                }
+            }
+
+            /// Fire the key event with no parameter.
+            pub fn fire(&self) -> bool {
+                ::msfs::events::fire_key_event(self.event_name(), &[])
+            }
+
+            /// Fire the key event with a single parameter.
+            pub fn fire_with(&self, param: u32) -> bool {
+                ::msfs::events::fire_key_event(self.event_name(), &[param])
+            }
+        }
+    };
+
+    Ok(expanded.into())
+}
+
+/// `#[event(name = "...")]`; a leading `K:` is stripped.
+fn parse_event_attr(variant: &syn::Variant) -> syn::Result<Option<String>> {
+    let mut name = None;
+    for attr in variant.attrs.iter().filter(|a| a.path().is_ident("event")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let value = lit.value();
+                let value = match value.get(..2) {
+                    Some(p) if p.eq_ignore_ascii_case("K:") => value[2..].to_string(),
+                    _ => value,
+                };
+                if value.is_empty() || value.contains(char::is_whitespace) {
+                    return Err(syn::Error::new(lit.span(), "invalid key event name"));
+                }
+                name = Some(value);
+                return Ok(());
+            }
+            Err(meta.error("unsupported #[event(...)] key"))
+        })?;
+    }
+    Ok(name)
+}
@@ -0,0 +1,194 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Data, DeriveInput, Expr, Fields, GenericArgument, LitStr, PathArguments, Type, spanned::Spanned,
+};
+
+enum DefaultSel {
+    /// Missing key is an error.
+    Required,
+    /// `Option<T>` field: missing key is `None`.
+    OptionNone,
+    /// `#[config(default)]`
+    TypeDefault,
+    /// `#[config(default = expr)]`
+    Expr(Expr),
+}
+
+struct ConfigField {
+    ident: syn::Ident,
+    key: String,
+    /// Type parsed from the value (`T` for `Option<T>`).
+    value_ty: Type,
+    default: DefaultSel,
+}
+
+pub(crate) fn derive_gauge_config_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let input_span = input.span();
+    let struct_ident = input.ident.clone();
+
+    let mut allow_unknown = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("config")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("allow_unknown") {
+                allow_unknown = true;
+                return Ok(());
+            }
+            Err(meta.error("unsupported #[config(...)] key on struct"))
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => named.named.clone(),
+            _ => {
+                return Err(syn::Error::new(
+                    s.fields.span(),
+                    "GaugeConfig can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input_span,
+                "GaugeConfig can only be derived for structs",
+            ));
+        }
+    };
+
+    let specs = fields
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let slots = specs.iter().map(|s| format_ident!("__{}", s.ident));
+
+    let slot_decls = specs.iter().zip(slots.clone()).map(|(s, slot)| {
+        let ty = &s.value_ty;
+        quote! { let mut #slot: ::core::option::Option<#ty> = ::core::option::Option::None; }
+    });
+
+    let arms = specs.iter().zip(slots.clone()).map(|(s, slot)| {
+        let key = LitStr::new(&s.key, s.ident.span());
+        quote! {
+            if __key.eq_ignore_ascii_case(#key) {
+                if #slot.is_some() {
+                    return ::core::result::Result::Err(
+                        ::msfs::config::ConfigError::DuplicateKey(__key.to_string()),
+                    );
+                }
+                #slot = ::core::option::Option::Some(
+                    ::msfs::config::__parse_field(__key, __value)?,
+                );
+                continue;
+            }
+        }
+    });
+
+    let unknown = if allow_unknown {
+        quote! {}
+    } else {
+        quote! {
+            return ::core::result::Result::Err(
+                ::msfs::config::ConfigError::UnknownKey(__key.to_string()),
+            );
+        }
+    };
+
+    let inits = specs.iter().zip(slots).map(|(s, slot)| {
+        let ident = &s.ident;
+        let key = LitStr::new(&s.key, s.ident.span());
+        let value = match &s.default {
+            DefaultSel::Required => quote! {
+                #slot.ok_or(::msfs::config::ConfigError::MissingKey(#key))?
+            },
+            DefaultSel::OptionNone => quote! { #slot },
+            DefaultSel::TypeDefault => quote! { #slot.unwrap_or_default() },
+            DefaultSel::Expr(e) => quote! { #slot.unwrap_or_else(|| #e) },
+        };
+        quote! { #ident: #value, }
+    });
+
+    let expanded = quote! {
+        impl ::msfs::config::GaugeConfig for #struct_ident {
+            fn from_params(params: &str) -> ::msfs::config::ConfigResult<Self> {
+                #(#slot_decls)*
+
+                for (__key, __value) in ::msfs::config::parse_pairs(params)? {
+                    #(#arms)*
+                    #unknown
+                }
+
+                ::core::result::Result::Ok(Self {
+                    #(#inits)*
+                })
+            }
+        }
+    };
+
+    Ok(expanded.into())
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<ConfigField> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| syn::Error::new(field.span(), "expected a named field"))?;
+
+    let mut key: Option<String> = None;
+    let mut default: Option<DefaultSel> = None;
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("config")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                let lit: LitStr = meta.value()?.parse()?;
+                key = Some(lit.value());
+                return Ok(());
+            }
+            if meta.path.is_ident("default") {
+                default = Some(if meta.input.peek(syn::Token![=]) {
+                    DefaultSel::Expr(meta.value()?.parse()?)
+                } else {
+                    DefaultSel::TypeDefault
+                });
+                return Ok(());
+            }
+            Err(meta.error("unsupported #[config(...)] key"))
+        })?;
+    }
+
+    let option_inner = option_inner(&field.ty);
+    let (value_ty, default) = match (option_inner, default) {
+        (Some(inner), None) => (inner.clone(), DefaultSel::OptionNone),
+        (Some(_), Some(_)) => {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "Option fields are already optional; drop `default`",
+            ));
+        }
+        (None, d) => (field.ty.clone(), d.unwrap_or(DefaultSel::Required)),
+    };
+
+    Ok(ConfigField {
+        key: key.unwrap_or_else(|| ident.to_string()),
+        ident,
+        value_ty,
+        default,
+    })
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(p) = ty else {
+        return None;
+    };
+    let seg = p.path.segments.last()?;
+    if seg.ident != "Option" {
+        return None;
+    }
+    if let PathArguments::AngleBracketed(args) = &seg.arguments
+        && let Some(GenericArgument::Type(inner)) = args.args.first()
+    {
+        return Some(inner);
+    }
+    None
+}
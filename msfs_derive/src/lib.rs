@@ -5,6 +5,12 @@ use syn::{
     spanned::Spanned,
 };
 
+mod comm_bus;
+mod event_struct;
+mod gauge_config;
+mod module;
+mod units;
+
 #[proc_macro_derive(VarStruct, attributes(var))]
 pub fn derive_var_struct(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -15,10 +21,105 @@ pub fn derive_var_struct(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Derives `msfs::comm_bus::CommBusMessage` for a struct with named fields.
+///
+/// `#[comm_bus(event = "...")]` names the comm bus event. Adding
+/// `ts_export = "dir"` also writes `dir/<Struct>.ts` (relative to the crate root)
+/// with the event name constant and a matching interface for the JS side.
+#[proc_macro_derive(CommBusMessage, attributes(comm_bus))]
+pub fn derive_comm_bus_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match comm_bus::derive_comm_bus_message_impl(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Derives `msfs::config::GaugeConfig`, parsing the gauge's install parameter
+/// string into the struct.
+///
+/// Field attributes: `#[config(key = "...")]` renames the key, `#[config(default)]`
+/// falls back to `Default::default()`, `#[config(default = expr)]` to `expr`.
+/// `Option<T>` fields are optional. `#[config(allow_unknown)]` on the struct
+/// ignores keys that match no field.
+#[proc_macro_derive(GaugeConfig, attributes(config))]
+pub fn derive_gauge_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match gauge_config::derive_gauge_config_impl(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates the `<name>_gauge_*` exports for a type implementing `msfs::Gauge`,
+/// the same as `export_gauge!`.
+///
+/// `#[gauge(name = "...")]` sets the export name (defaults to the type name in
+/// snake_case); `ctor = expr` builds the state (defaults to `Default::default()`);
+/// `abi = Type` exports against another `msfs::abi::Abi` via `export_gauge_abi!`.
+#[proc_macro_derive(GaugeModule, attributes(gauge))]
+pub fn derive_gauge_module(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match module::derive_gauge_module_impl(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates the `<name>_system_*` exports for a type implementing `msfs::System`,
+/// the same as `export_system!`.
+///
+/// Takes `#[system(name = "...", ctor = expr)]` with the same defaults as
+/// [`GaugeModule`](derive@GaugeModule).
+#[proc_macro_derive(SystemModule, attributes(system))]
+pub fn derive_system_module(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match module::derive_system_module_impl(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Maps each unit variant of an enum to a key event (`K:`), generating
+/// `event_name()`, `fire()` and `fire_with(param)`.
+///
+/// The event name is `#[event(name = "...")]`, or the variant name in
+/// SCREAMING_SNAKE_CASE (`ToggleMasterBattery` -> `TOGGLE_MASTER_BATTERY`).
+#[proc_macro_derive(EventStruct, attributes(event))]
+pub fn derive_event_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match event_struct::derive_event_struct_impl(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum VarKindSel {
     A,
     L,
+    /// Environment var; read-only, so `set()` skips it.
+    E,
+}
+
+/// Value type of a `#[var(...)]` field, under an optional `Option<...>`.
+#[derive(Clone)]
+enum FieldTy {
+    F64,
+    /// String-valued A-var, read through `a_var::get_string`; never written.
+    String,
+    /// Nonzero after rounding; written as 1.0 / 0.0.
+    Bool,
+    /// Any other type: converted from the rounded value with `TryFrom<i32>` and
+    /// written back with `as i32` (fieldless `#[repr(i32)]`-style enums, integers).
+    /// `set()` reads the field by value (`self.x as i32`, `if let Some(v) =
+    /// self.x`), so the type must be `Copy`.
+    Enum(Box<syn::Type>),
 }
 
 struct FieldSpec {
@@ -28,8 +129,38 @@ struct FieldSpec {
     kind: VarKindSel,
     index: Option<u32>,
     target: Option<VarTargetSel>,
+    ty: FieldTy,
+    optional: bool,
+    /// Enum fields: used when the value has no matching variant.
+    fallback: Option<Expr>,
+    /// Reads within this many milliseconds of the last one reuse its value.
+    max_age_ms: Option<u64>,
+    access: VarAccess,
+    /// Used instead of failing `get()` when the read errors.
+    default: Option<Expr>,
+    /// `field = raw * scale + offset` on get, inverted on set.
+    scale: Option<Expr>,
+    offset: Option<Expr>,
+}
+
+/// A `#[var(flatten)]` field holding another `VarStruct`.
+struct FlattenSpec {
+    ident: syn::Ident,
+    ty: syn::Type,
+    access: VarAccess,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VarAccess {
+    ReadWrite,
+    /// Skipped by `set()`.
+    ReadOnly,
+    /// Not read by `get()`; the field is filled with its default.
+    WriteOnly,
 }
 
+// Variant names mirror the FS_OBJECT_ID_USER_* constants they map to.
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum VarTargetSel {
     UserAircraft,
@@ -60,6 +191,9 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
     };
 
     let mut specs = Vec::<FieldSpec>::new();
+    let mut flattened = Vec::<FlattenSpec>::new();
+    // `#[var(skip)]` plain fields, filled with `Default::default()` by `get()`.
+    let mut skipped = Vec::<syn::Ident>::new();
 
     for field in fields {
         let field_span = field.span();
@@ -67,14 +201,6 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             continue;
         };
 
-        // Currently only supports f64 fields.
-        if !is_f64_type(&field.ty) {
-            return Err(syn::Error::new(
-                field.ty.span(),
-                "VarStruct currently only supports fields of type f64",
-            ));
-        }
-
         let var_attr = field
             .attrs
             .iter()
@@ -82,7 +208,7 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             .ok_or_else(|| {
                 syn::Error::new(
                     field_span,
-                    "missing #[var(...)] attribute (expected at least name + unit)",
+                    "missing #[var(...)] attribute (expected at least name + unit, or skip)",
                 )
             })?;
 
@@ -91,8 +217,56 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
         let mut kind: Option<VarKindSel> = None;
         let mut index: Option<u32> = None;
         let mut target: Option<VarTargetSel> = None;
+        let mut readonly = false;
+        let mut writeonly = false;
+        let mut flatten = false;
+        let mut skip = false;
+        let mut unit_span: Option<proc_macro2::Span> = None;
+        let mut unchecked_unit = false;
+        let mut default: Option<Expr> = None;
+        let mut scale: Option<Expr> = None;
+        let mut offset: Option<Expr> = None;
+        let mut fallback: Option<Expr> = None;
+        let mut max_age_ms: Option<u64> = None;
 
         var_attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("scale") {
+                scale = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("offset") {
+                offset = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("max_age_ms") {
+                let lit: LitInt = meta.value()?.parse()?;
+                max_age_ms = Some(lit.base10_parse::<u64>()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("fallback") {
+                fallback = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("default") {
+                default = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("flatten") {
+                flatten = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("readonly") {
+                readonly = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("writeonly") {
+                writeonly = true;
+                return Ok(());
+            }
             if meta.path.is_ident("name") {
                 let lit: LitStr = meta.value()?.parse()?;
                 name = Some(lit.value());
@@ -101,10 +275,15 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             if meta.path.is_ident("unit") {
                 let lit: LitStr = meta.value()?.parse()?;
                 unit = Some(lit.value());
+                unit_span = Some(lit.span());
+                return Ok(());
+            }
+            if meta.path.is_ident("unchecked_unit") {
+                unchecked_unit = true;
                 return Ok(());
             }
             if meta.path.is_ident("kind") {
-                // Allow: kind = "A" | "AVar" | "L" | "LVar" OR kind = A | L
+                // Allow: kind = "A" | "AVar" | "L" | "LVar" | "E" | "EVar" OR kind = A | L | E
                 let expr: Expr = meta.value()?.parse()?;
                 let (value, span) = match expr {
                     Expr::Lit(ExprLit {
@@ -121,7 +300,7 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                     other => {
                         return Err(syn::Error::new(
                             other.span(),
-                            "kind must be a string literal (\"A\"/\"L\") or an identifier (A/L)",
+                            "kind must be a string literal (\"A\"/\"L\"/\"E\") or an identifier (A/L/E)",
                         ));
                     }
                 };
@@ -164,16 +343,161 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             Err(meta.error("unsupported #[var(...)] key"))
         })?;
 
+        if skip {
+            if name.is_some()
+                || unit.is_some()
+                || kind.is_some()
+                || index.is_some()
+                || target.is_some()
+                || default.is_some()
+                || scale.is_some()
+                || offset.is_some()
+                || fallback.is_some()
+                || max_age_ms.is_some()
+                || readonly
+                || writeonly
+                || flatten
+            {
+                return Err(syn::Error::new(
+                    var_attr.span(),
+                    "#[var(skip)] cannot be combined with other keys",
+                ));
+            }
+            skipped.push(ident);
+            continue;
+        }
+
+        let access = match (readonly, writeonly) {
+            (false, false) => VarAccess::ReadWrite,
+            (true, false) => VarAccess::ReadOnly,
+            (false, true) => VarAccess::WriteOnly,
+            (true, true) => {
+                return Err(syn::Error::new(
+                    var_attr.span(),
+                    "#[var] cannot be both readonly and writeonly",
+                ));
+            }
+        };
+
+        if flatten {
+            if name.is_some()
+                || default.is_some()
+                || scale.is_some()
+                || offset.is_some()
+                || fallback.is_some()
+                || max_age_ms.is_some()
+                || unit.is_some()
+                || kind.is_some()
+                || index.is_some()
+                || target.is_some()
+            {
+                return Err(syn::Error::new(
+                    var_attr.span(),
+                    "#[var(flatten)] only combines with readonly/writeonly",
+                ));
+            }
+            flattened.push(FlattenSpec {
+                ident,
+                ty: field.ty.clone(),
+                access,
+            });
+            continue;
+        }
+
+        // `Option<...>` fields become `None` when their read fails.
+        let (ty, optional) = field_ty(&field.ty)?;
+
+        if fallback.is_some() && !matches!(ty, FieldTy::Enum(_)) {
+            return Err(syn::Error::new(
+                var_attr.span(),
+                "#[var(fallback = ...)] is for enum fields",
+            ));
+        }
+        if matches!(ty, FieldTy::Enum(_) | FieldTy::Bool) && (scale.is_some() || offset.is_some()) {
+            return Err(syn::Error::new(
+                var_attr.span(),
+                "scale/offset only apply to f64 fields",
+            ));
+        }
+
+        if optional && default.is_some() {
+            return Err(syn::Error::new(
+                var_attr.span(),
+                "#[var(default = ...)] is for non-Option fields; Option fields already fall back to None",
+            ));
+        }
+
         let name = name.ok_or_else(|| syn::Error::new(var_attr.span(), "#[var] requires name"))?;
-        let unit = unit.unwrap_or_else(|| "Number".to_string());
+
+        if matches!(ty, FieldTy::String) {
+            if unit
+                .as_ref()
+                .is_some_and(|u| !u.eq_ignore_ascii_case("string"))
+            {
+                return Err(syn::Error::new(
+                    unit_span.unwrap_or_else(|| var_attr.span()),
+                    "String fields are read as text; omit unit or use unit = \"String\"",
+                ));
+            }
+            if scale.is_some() || offset.is_some() {
+                return Err(syn::Error::new(
+                    var_attr.span(),
+                    "scale/offset only apply to f64 fields",
+                ));
+            }
+            if writeonly {
+                return Err(syn::Error::new(
+                    var_attr.span(),
+                    "String vars are read-only and cannot be writeonly",
+                ));
+            }
+            if kind.or_else(|| infer_kind_from_name(&name)) != Some(VarKindSel::A) {
+                return Err(syn::Error::new(
+                    var_attr.span(),
+                    "String fields are only supported for A vars (kind = \"A\" or an \"A:\" name)",
+                ));
+            }
+        }
+
+        let unit = unit.unwrap_or_else(|| match ty {
+            FieldTy::String => "String".to_string(),
+            FieldTy::Bool => "Bool".to_string(),
+            _ => "Number".to_string(),
+        });
+        if !matches!(ty, FieldTy::String) && !unchecked_unit && !units::is_known(&unit) {
+            let hint = match units::suggest(&unit) {
+                Some(s) => format!(" (did you mean \"{s}\"?)"),
+                None => String::new(),
+            };
+            return Err(syn::Error::new(
+                unit_span.unwrap_or_else(|| var_attr.span()),
+                format!(
+                    "unknown unit \"{unit}\"{hint}; add `unchecked_unit` if the sim accepts it"
+                ),
+            ));
+        }
         let kind = kind.or_else(|| infer_kind_from_name(&name));
         let Some(kind) = kind else {
             return Err(syn::Error::new(
                 var_attr.span(),
-                r#"#[var] requires kind ("A"/"L") or a name prefixed with "A:" or "L:""#,
+                r#"#[var] requires kind ("A"/"L"/"E") or a name prefixed with "A:", "L:" or "E:""#,
             ));
         };
 
+        if max_age_ms.is_some() && access == VarAccess::WriteOnly {
+            return Err(syn::Error::new(
+                var_attr.span(),
+                "#[var(max_age_ms = ...)] has nothing to cache on a writeonly field",
+            ));
+        }
+
+        if kind == VarKindSel::E && access == VarAccess::WriteOnly {
+            return Err(syn::Error::new(
+                var_attr.span(),
+                "E vars are read-only and cannot be writeonly",
+            ));
+        }
+
         if index.is_some() && kind != VarKindSel::A {
             return Err(syn::Error::new(
                 var_attr.span(),
@@ -188,97 +512,142 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
             kind,
             index,
             target,
+            ty,
+            optional,
+            fallback,
+            max_age_ms,
+            access,
+            default,
+            scale,
+            offset,
         });
     }
 
-    if specs.is_empty() {
+    if specs.is_empty() && flattened.is_empty() {
         return Err(syn::Error::new(
             struct_ident.span(),
-            "VarStruct requires at least one #[var(...)] field",
+            "VarStruct requires at least one #[var(...)] or #[var(flatten)] field",
         ));
     }
 
-    let helpers = specs.iter().map(|spec| {
-        let field_ident = &spec.ident;
+    // String fields are read by name, so they need no registered var.
+    let helpers = specs
+        .iter()
+        .filter(|spec| !matches!(spec.ty, FieldTy::String))
+        .map(|spec| {
+            let field_ident = &spec.ident;
 
-        let helper_fn_ident =
-            format_ident!("__msfs_varstruct_get_var_{}_{}", struct_ident, field_ident);
-        let cell_ident = format_ident!("__MSFS_VARSTRUCT_CELL_{}_{}", struct_ident, field_ident);
+            let helper_fn_ident = helper_ident(&struct_ident, field_ident);
 
-        let name_lit = LitStr::new(&spec.name, field_ident.span());
-        let unit_lit = LitStr::new(&spec.unit, field_ident.span());
+            let name_lit = LitStr::new(&spec.name, field_ident.span());
+            let unit_lit = LitStr::new(&spec.unit, field_ident.span());
 
-        let var_ty = match spec.kind {
-            VarKindSel::A => quote!(::msfs::vars::a_var::AVar),
-            VarKindSel::L => quote!(::msfs::vars::l_var::LVar),
-        };
+            let var_ty = match spec.kind {
+                VarKindSel::A => quote!(::msfs::vars::a_var::AVar),
+                VarKindSel::L => quote!(::msfs::vars::l_var::LVar),
+                VarKindSel::E => quote!(::msfs::vars::e_var::EVar),
+            };
 
-        quote! {
-            #[inline]
-            fn #helper_fn_ident() -> ::msfs::vars::VarResult<#var_ty> {
-                static #cell_ident: ::std::sync::OnceLock<::msfs::vars::VarResult<#var_ty>> =
-                    ::std::sync::OnceLock::new();
-
-                match #cell_ident.get_or_init(|| #var_ty::new(#name_lit, #unit_lit)) {
-                    Ok(v) => Ok(*v),
-                    Err(e) => Err(e.clone()),
+            // Looked up in the shared var table on each access. Failures aren't
+            // remembered, so a var the sim doesn't know yet is retried on the
+            // next access.
+            quote! {
+                #[inline]
+                #[allow(non_snake_case)]
+                fn #helper_fn_ident() -> ::msfs::vars::VarResult<#var_ty> {
+                    #var_ty::new(#name_lit, #unit_lit)
                 }
             }
-        }
-    });
-
-    let get_inits = specs.iter().map(|spec| {
-        let field_ident = &spec.ident;
-        let helper_fn_ident =
-            format_ident!("__msfs_varstruct_get_var_{}_{}", struct_ident, field_ident);
+        });
 
-        let target_expr = spec.target.map(target_to_tokens);
-        let index_expr = spec.index;
+    let caches = specs
+        .iter()
+        .filter(|spec| spec.max_age_ms.is_some())
+        .map(|spec| {
+            let cache_fn_ident = cache_ident(&struct_ident, &spec.ident);
+            let static_ident =
+                format_ident!("__MSFS_VARSTRUCT_AGE_{}_{}", struct_ident, spec.ident);
+            let raw_ty = match spec.ty {
+                FieldTy::String => quote!(::std::string::String),
+                _ => quote!(f64),
+            };
 
-        match (index_expr, target_expr) {
-            (Some(index), Some(target)) => {
-                quote!(#field_ident: #helper_fn_ident()?.get_indexed_target(#index, #target)?)
+            quote! {
+                #[inline]
+                #[allow(non_snake_case, non_upper_case_globals)]
+                fn #cache_fn_ident() -> &'static ::msfs::vars::__MaxAgeCache<#raw_ty> {
+                    static #static_ident: ::msfs::vars::__MaxAgeCache<#raw_ty> =
+                        ::msfs::vars::__MaxAgeCache::new();
+                    &#static_ident
+                }
             }
-            (Some(index), None) => quote!(#field_ident: #helper_fn_ident()?.get_indexed(#index)?),
-            (None, Some(target)) => quote!(#field_ident: #helper_fn_ident()?.get_target(#target)?),
-            (None, None) => quote!(#field_ident: #helper_fn_ident()?.get()?),
-        }
-    });
-
-    let set_stmts = specs.iter().map(|spec| {
-        let field_ident = &spec.ident;
-        let helper_fn_ident =
-            format_ident!("__msfs_varstruct_get_var_{}_{}", struct_ident, field_ident);
-
-        let target_expr = spec.target.map(target_to_tokens);
-        let index_expr = spec.index;
+        });
 
-        match (index_expr, target_expr) {
-            (Some(index), Some(target)) => {
-                quote!(#helper_fn_ident()?.set_indexed_target(#index, #target, self.#field_ident)?;)
-            }
-            (Some(index), None) => {
-                quote!(#helper_fn_ident()?.set_indexed(#index, self.#field_ident)?;)
-            }
-            (None, Some(target)) => {
-                quote!(#helper_fn_ident()?.set_target(#target, self.#field_ident)?;)
-            }
-            (None, None) => quote!(#helper_fn_ident()?.set(self.#field_ident)?;),
-        }
-    });
+    // `None` uses the per-field attribute targets; `Some(target)` is the runtime
+    // target taken by `get_for`/`set_for`.
+    let rt = quote!(target);
+    let get_fields = get_inits(&struct_ident, &specs, None);
+    let get_for_fields = get_inits(&struct_ident, &specs, Some(&rt));
+    let flatten_get = flatten_inits(&flattened, None);
+    let flatten_get_for = flatten_inits(&flattened, Some(&rt));
+    let set_fields = set_stmts(&struct_ident, &specs, None);
+    let set_for_fields = set_stmts(&struct_ident, &specs, Some(&rt));
+    let flatten_set = flatten_sets(&flattened, None);
+    let flatten_set_for = flatten_sets(&flattened, Some(&rt));
+    let skipped_for = skipped.clone();
+    let get_into = get_into_stmts(&struct_ident, &specs, &flattened);
 
     let expanded = quote! {
         impl #struct_ident {
             #(#helpers)*
+            #(#caches)*
 
             #[inline]
             pub fn get() -> ::msfs::vars::VarResult<Self> {
-                Ok(Self { #(#get_inits,)* })
+                Ok(Self {
+                    #(#get_fields,)*
+                    #(#flatten_get,)*
+                    #(#skipped: ::core::default::Default::default(),)*
+                })
             }
 
             #[inline]
             pub fn set(&self) -> ::msfs::vars::VarResult<()> {
-                #(#set_stmts)*
+                #(#set_fields)*
+                #(#flatten_set)*
+                Ok(())
+            }
+
+            /// Refresh `self` in place. Fields whose read fails keep their previous
+            /// value; the first error is returned once every field has been tried.
+            #[inline]
+            pub fn get_into(&mut self) -> ::msfs::vars::VarResult<()> {
+                #[allow(unused_mut)]
+                let mut __first_err: ::core::option::Option<::msfs::vars::VarError> =
+                    ::core::option::Option::None;
+                #(#get_into)*
+                match __first_err {
+                    ::core::option::Option::Some(e) => Err(e),
+                    ::core::option::Option::None => Ok(()),
+                }
+            }
+
+            /// Like `get()`, but reads every field from `target`, overriding
+            /// any `target = ...` attributes.
+            #[inline]
+            pub fn get_for(target: ::msfs::sys::FsObjectId) -> ::msfs::vars::VarResult<Self> {
+                Ok(Self {
+                    #(#get_for_fields,)*
+                    #(#flatten_get_for,)*
+                    #(#skipped_for: ::core::default::Default::default(),)*
+                })
+            }
+
+            /// Like `set()`, but writes every field to `target`.
+            #[inline]
+            pub fn set_for(&self, target: ::msfs::sys::FsObjectId) -> ::msfs::vars::VarResult<()> {
+                #(#set_for_fields)*
+                #(#flatten_set_for)*
                 Ok(())
             }
         }
@@ -287,26 +656,336 @@ fn derive_var_struct_impl(input: DeriveInput) -> syn::Result<TokenStream> {
     Ok(expanded.into())
 }
 
-fn is_f64_type(ty: &syn::Type) -> bool {
-    let syn::Type::Path(p) = ty else {
-        return false;
+fn get_inits(
+    struct_ident: &syn::Ident,
+    specs: &[FieldSpec],
+    rt: Option<&proc_macro2::TokenStream>,
+) -> Vec<proc_macro2::TokenStream> {
+    specs
+        .iter()
+        .map(|spec| {
+            let field_ident = &spec.ident;
+            if spec.access == VarAccess::WriteOnly {
+                return match &spec.default {
+                    Some(default) => quote!(#field_ident: #default),
+                    None => quote!(#field_ident: ::core::default::Default::default()),
+                };
+            }
+            let read = converted_read(struct_ident, spec, rt);
+            match (&spec.default, spec.optional) {
+                (Some(default), _) if matches!(spec.ty, FieldTy::String) => {
+                    quote!(#field_ident: #read.unwrap_or_else(|_| ::core::convert::Into::into(#default)))
+                }
+                (Some(default), _) => quote!(#field_ident: #read.unwrap_or(#default)),
+                (None, true) => quote!(#field_ident: #read.ok()),
+                (None, false) => quote!(#field_ident: #read?),
+            }
+        })
+        .collect()
+}
+
+/// Statements for `get_into`: fields whose read fails keep their previous value
+/// and the first error is returned after every field has been tried.
+fn get_into_stmts(
+    struct_ident: &syn::Ident,
+    specs: &[FieldSpec],
+    flattened: &[FlattenSpec],
+) -> Vec<proc_macro2::TokenStream> {
+    let vars = specs
+        .iter()
+        .filter(|spec| spec.access != VarAccess::WriteOnly)
+        .map(|spec| {
+            let field_ident = &spec.ident;
+            let read = converted_read(struct_ident, spec, None);
+            match (&spec.default, spec.optional) {
+                (Some(default), _) if matches!(spec.ty, FieldTy::String) => quote! {
+                    self.#field_ident = #read.unwrap_or_else(|_| ::core::convert::Into::into(#default));
+                },
+                (Some(default), _) => quote!(self.#field_ident = #read.unwrap_or(#default);),
+                (None, true) => quote!(self.#field_ident = #read.ok();),
+                (None, false) => quote! {
+                    match #read {
+                        Ok(value) => self.#field_ident = value,
+                        Err(e) => {
+                            __first_err.get_or_insert(e);
+                        }
+                    }
+                },
+            }
+        });
+
+    let nested = flattened
+        .iter()
+        .filter(|f| f.access != VarAccess::WriteOnly)
+        .map(|f| {
+            let ident = &f.ident;
+            quote! {
+                if let Err(e) = self.#ident.get_into() {
+                    __first_err.get_or_insert(e);
+                }
+            }
+        });
+
+    vars.chain(nested).collect()
+}
+
+fn set_stmts(
+    struct_ident: &syn::Ident,
+    specs: &[FieldSpec],
+    rt: Option<&proc_macro2::TokenStream>,
+) -> Vec<proc_macro2::TokenStream> {
+    specs
+        .iter()
+        .filter(|spec| {
+            spec.access != VarAccess::ReadOnly
+                && spec.kind != VarKindSel::E
+                && !matches!(spec.ty, FieldTy::String)
+        })
+        .map(|spec| {
+            let field_ident = &spec.ident;
+            let to_raw = |value: proc_macro2::TokenStream| {
+                if matches!(spec.ty, FieldTy::Enum(_)) {
+                    return quote!(((#value) as i32 as f64));
+                }
+                if matches!(spec.ty, FieldTy::Bool) {
+                    return quote!((if #value { 1.0 } else { 0.0 }));
+                }
+                let value = match &spec.offset {
+                    Some(offset) => quote!((#value - (#offset))),
+                    None => value,
+                };
+                match &spec.scale {
+                    Some(scale) => quote!(#value / (#scale)),
+                    None => value,
+                }
+            };
+            let invalidate = spec.max_age_ms.map(|_| {
+                let cache = cache_ident(struct_ident, field_ident);
+                quote!(Self::#cache().invalidate();)
+            });
+            if spec.optional {
+                let write = write_expr(struct_ident, spec, to_raw(quote!(value)), rt);
+                quote! {
+                    if let Some(value) = self.#field_ident {
+                        #write?;
+                        #invalidate
+                    }
+                }
+            } else {
+                let write = write_expr(struct_ident, spec, to_raw(quote!(self.#field_ident)), rt);
+                quote! {
+                    #write?;
+                    #invalidate
+                }
+            }
+        })
+        .collect()
+}
+
+fn flatten_inits(
+    flattened: &[FlattenSpec],
+    rt: Option<&proc_macro2::TokenStream>,
+) -> Vec<proc_macro2::TokenStream> {
+    flattened
+        .iter()
+        .map(|f| {
+            let (ident, ty) = (&f.ident, &f.ty);
+            match (f.access, rt) {
+                (VarAccess::WriteOnly, _) => quote!(#ident: ::core::default::Default::default()),
+                (_, Some(rt)) => quote!(#ident: <#ty>::get_for(#rt)?),
+                (_, None) => quote!(#ident: <#ty>::get()?),
+            }
+        })
+        .collect()
+}
+
+fn flatten_sets(
+    flattened: &[FlattenSpec],
+    rt: Option<&proc_macro2::TokenStream>,
+) -> Vec<proc_macro2::TokenStream> {
+    flattened
+        .iter()
+        .filter(|f| f.access != VarAccess::ReadOnly)
+        .map(|f| {
+            let ident = &f.ident;
+            match rt {
+                Some(rt) => quote!(self.#ident.set_for(#rt)?;),
+                None => quote!(self.#ident.set()?;),
+            }
+        })
+        .collect()
+}
+
+fn helper_ident(struct_ident: &syn::Ident, field_ident: &syn::Ident) -> syn::Ident {
+    format_ident!("__msfs_varstruct_get_var_{}_{}", struct_ident, field_ident)
+}
+
+fn cache_ident(struct_ident: &syn::Ident, field_ident: &syn::Ident) -> syn::Ident {
+    format_ident!("__msfs_varstruct_cache_{}_{}", struct_ident, field_ident)
+}
+
+/// `read_expr` with the field's scale/offset, bool or enum conversion applied.
+fn converted_read(
+    struct_ident: &syn::Ident,
+    spec: &FieldSpec,
+    rt: Option<&proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let read = read_expr(struct_ident, spec, rt);
+    if let FieldTy::Enum(ty) = &spec.ty {
+        let unmapped = match &spec.fallback {
+            Some(fallback) => quote!(#fallback),
+            None => quote!(return Err(::msfs::vars::VarError::Unmapped(raw))),
+        };
+        return quote! {
+            #read.and_then(|raw| {
+                let raw = raw.round() as i32;
+                Ok(match <#ty as ::core::convert::TryFrom<i32>>::try_from(raw) {
+                    Ok(value) => value,
+                    Err(_) => #unmapped,
+                })
+            })
+        };
+    }
+    if matches!(spec.ty, FieldTy::Bool) {
+        return quote!(#read.map(|raw| raw.round() != 0.0));
+    }
+    match (&spec.scale, &spec.offset) {
+        (None, None) => read,
+        (scale, offset) => {
+            let scale = scale.as_ref().map(|e| quote!(* (#e)));
+            let offset = offset.as_ref().map(|e| quote!(+ (#e)));
+            quote!(#read.map(|raw| raw #scale #offset))
+        }
+    }
+}
+
+/// `uncached_read_expr`, served from the field's cache when it has `max_age_ms`.
+fn read_expr(
+    struct_ident: &syn::Ident,
+    spec: &FieldSpec,
+    rt: Option<&proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let read = uncached_read_expr(struct_ident, spec, rt);
+    let Some(max_age_ms) = spec.max_age_ms else {
+        return read;
+    };
+
+    let cache = cache_ident(struct_ident, &spec.ident);
+    let key = match rt.cloned().or_else(|| spec.target.map(target_to_tokens)) {
+        Some(target) => quote!(::core::option::Option::Some(#target)),
+        None => quote!(::core::option::Option::None),
     };
-    if p.qself.is_some() {
-        return false;
+    quote! {
+        Self::#cache().get_or_read(
+            #key,
+            ::core::time::Duration::from_millis(#max_age_ms),
+            || #read,
+        )
     }
-    let Some(seg) = p.path.segments.last() else {
-        return false;
+}
+
+/// Expression of type `VarResult<f64>` (`VarResult<String>` for String fields)
+/// reading the field's var. `rt` overrides the attribute target.
+fn uncached_read_expr(
+    struct_ident: &syn::Ident,
+    spec: &FieldSpec,
+    rt: Option<&proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let helper = helper_ident(struct_ident, &spec.ident);
+    let target = rt.cloned().or_else(|| spec.target.map(target_to_tokens));
+
+    if matches!(spec.ty, FieldTy::String) {
+        let name = LitStr::new(&spec.name, spec.ident.span());
+        let index = match spec.index {
+            Some(index) => quote!(::core::option::Option::Some(#index)),
+            None => quote!(::core::option::Option::None),
+        };
+        let target = target.unwrap_or_else(|| quote!(::msfs::sys::FS_OBJECT_ID_USER_AIRCRAFT));
+        return quote!(::msfs::vars::a_var::get_string(#name, #index, #target));
+    }
+
+    match (spec.index, target) {
+        (Some(index), Some(target)) => {
+            quote!(Self::#helper().and_then(|v| v.get_indexed_target(#index, #target)))
+        }
+        (Some(index), None) => quote!(Self::#helper().and_then(|v| v.get_indexed(#index))),
+        (None, Some(target)) => quote!(Self::#helper().and_then(|v| v.get_target(#target))),
+        (None, None) => quote!(Self::#helper().and_then(|v| v.get())),
+    }
+}
+
+/// Expression of type `VarResult<()>` writing `value` to the field's var. `rt`
+/// overrides the attribute target.
+fn write_expr(
+    struct_ident: &syn::Ident,
+    spec: &FieldSpec,
+    value: proc_macro2::TokenStream,
+    rt: Option<&proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let helper = helper_ident(struct_ident, &spec.ident);
+    let target = rt.cloned().or_else(|| spec.target.map(target_to_tokens));
+
+    match (spec.index, target) {
+        (Some(index), Some(target)) => {
+            quote!(Self::#helper().and_then(|v| v.set_indexed_target(#index, #target, #value)))
+        }
+        (Some(index), None) => quote!(Self::#helper().and_then(|v| v.set_indexed(#index, #value))),
+        (None, Some(target)) => quote!(Self::#helper().and_then(|v| v.set_target(#target, #value))),
+        (None, None) => quote!(Self::#helper().and_then(|v| v.set(#value))),
+    }
+}
+
+/// The field's value type and whether it is wrapped in `Option`.
+fn field_ty(ty: &syn::Type) -> syn::Result<(FieldTy, bool)> {
+    if let syn::Type::Path(p) = ty
+        && p.qself.is_none()
+        && let Some(seg) = p.path.segments.last()
+        && seg.ident == "Option"
+        && let syn::PathArguments::AngleBracketed(args) = &seg.arguments
+        && let Some(syn::GenericArgument::Type(inner)) = args.args.first()
+    {
+        return Ok((plain_field_ty(inner)?, true));
+    }
+    Ok((plain_field_ty(ty)?, false))
+}
+
+fn plain_field_ty(ty: &syn::Type) -> syn::Result<FieldTy> {
+    let syn::Type::Path(p) = ty else {
+        return Err(syn::Error::new(
+            ty.span(),
+            "unsupported #[var] field type (expected f64, bool, String, an integer or a Copy enum)",
+        ));
     };
-    seg.ident == "f64"
+    if p.qself.is_none()
+        && let Some(seg) = p.path.segments.last()
+    {
+        if seg.ident == "f64" {
+            return Ok(FieldTy::F64);
+        }
+        if seg.ident == "String" {
+            return Ok(FieldTy::String);
+        }
+        if seg.ident == "bool" {
+            return Ok(FieldTy::Bool);
+        }
+        if seg.ident == "f32" {
+            return Err(syn::Error::new(
+                ty.span(),
+                "f32 #[var] fields aren't supported; use f64",
+            ));
+        }
+    }
+    Ok(FieldTy::Enum(Box::new(ty.clone())))
 }
 
 fn parse_kind_str(s: &str, span: proc_macro2::Span) -> syn::Result<VarKindSel> {
     match s.trim() {
         "A" | "AVar" | "a" | "avar" => Ok(VarKindSel::A),
         "L" | "LVar" | "l" | "lvar" => Ok(VarKindSel::L),
+        "E" | "EVar" | "e" | "evar" => Ok(VarKindSel::E),
         other => Err(syn::Error::new(
             span,
-            format!("unknown var kind: {other} (expected A/AVar or L/LVar)"),
+            format!("unknown var kind: {other} (expected A/AVar, L/LVar or E/EVar)"),
         )),
     }
 }
@@ -317,6 +996,8 @@ fn infer_kind_from_name(name: &str) -> Option<VarKindSel> {
         Some(VarKindSel::A)
     } else if upper.starts_with("L:") {
         Some(VarKindSel::L)
+    } else if upper.starts_with("E:") {
+        Some(VarKindSel::E)
     } else {
         None
     }
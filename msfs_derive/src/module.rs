@@ -0,0 +1,113 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Expr, ExprLit, Lit, LitStr, spanned::Spanned};
+
+struct ModuleSpec {
+    name: syn::Ident,
+    ctor: proc_macro2::TokenStream,
+    abi: Option<syn::Type>,
+}
+
+/// Parses `#[<attr>(name = "...", ctor = expr, abi = Type)]`. `name` defaults to the
+/// struct name in snake_case, `ctor` to `Default::default()`, `abi` to `Fs2024`.
+fn parse_module_attr(input: &DeriveInput, attr_name: &str) -> syn::Result<ModuleSpec> {
+    let ident = &input.ident;
+    let mut name: Option<syn::Ident> = None;
+    let mut ctor: Option<Expr> = None;
+    let mut abi: Option<syn::Type> = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident(attr_name)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let lit: LitStr = meta.value()?.parse()?;
+                name = Some(syn::Ident::new(&lit.value(), lit.span()));
+                return Ok(());
+            }
+            if meta.path.is_ident("ctor") {
+                // Accept `ctor = MyGauge::new()` or `ctor = "MyGauge::new()"`.
+                let expr: Expr = meta.value()?.parse()?;
+                ctor = Some(match expr {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }) => s.parse()?,
+                    other => other,
+                });
+                return Ok(());
+            }
+            if meta.path.is_ident("abi") {
+                abi = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            Err(meta.error(format!("unsupported #[{attr_name}(...)] key")))
+        })?;
+    }
+
+    let name = name.unwrap_or_else(|| format_ident!("{}", snake_case(&input.ident.to_string())));
+    let ctor = match ctor {
+        Some(e) => quote!(#e),
+        None => quote!(<#ident as ::core::default::Default>::default()),
+    };
+
+    Ok(ModuleSpec { name, ctor, abi })
+}
+
+fn ensure_plain(input: &DeriveInput, derive: &str) -> syn::Result<()> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            format!("{derive} cannot be derived for generic types"),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn derive_gauge_module_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    ensure_plain(&input, "GaugeModule")?;
+    let ident = &input.ident;
+    let ModuleSpec { name, ctor, abi } = parse_module_attr(&input, "gauge")?;
+
+    let expanded = match abi {
+        Some(abi) => quote! {
+            ::msfs::export_gauge_abi!(abi = #abi, name = #name, state = #ident, ctor = #ctor);
+        },
+        None => quote! {
+            ::msfs::export_gauge!(name = #name, state = #ident, ctor = #ctor);
+        },
+    };
+
+    Ok(expanded.into())
+}
+
+pub(crate) fn derive_system_module_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    ensure_plain(&input, "SystemModule")?;
+    let ident = &input.ident;
+    let ModuleSpec { name, ctor, abi } = parse_module_attr(&input, "system")?;
+
+    let expanded = match abi {
+        Some(abi) => quote! {
+            ::msfs::export_system_abi!(abi = #abi, name = #name, state = #ident, ctor = #ctor);
+        },
+        None => quote! {
+            ::msfs::export_system!(name = #name, state = #ident, ctor = #ctor);
+        },
+    };
+
+    Ok(expanded.into())
+}
+
+fn snake_case(name: &str) -> String {
+    crate::comm_bus::screaming_snake(name).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snake_case_words() {
+        assert_eq!(snake_case("FuelSystem"), "fuel_system");
+        assert_eq!(snake_case("HTTPGauge"), "http_gauge");
+        assert_eq!(snake_case("Fms2Display"), "fms2_display");
+        assert_eq!(snake_case("Pfd"), "pfd");
+    }
+}
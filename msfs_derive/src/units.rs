@@ -0,0 +1,83 @@
+//! Unit names accepted by `#[var(unit = "...")]`.
+//!
+//! The table is `msfs::vars::units`'s own, included from its source since the
+//! proc-macro crate can't depend on `msfs` itself.
+
+macro_rules! units {
+    ($($family:ident: $name:literal $([$($alias:literal),* $(,)?])?;)*) => {
+        &[$($name, $($($alias,)*)?)*]
+    };
+}
+
+static KNOWN_UNITS: &[&str] = include!("../../msfs/src/vars/unit_table.rs");
+
+fn normalize(unit: &str) -> String {
+    unit.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_lowercase()
+}
+
+pub(crate) fn is_known(unit: &str) -> bool {
+    let unit = normalize(unit);
+    KNOWN_UNITS.iter().any(|u| *u == unit)
+}
+
+/// Closest known unit name, for "did you mean" hints.
+pub(crate) fn suggest(unit: &str) -> Option<&'static str> {
+    let unit = normalize(unit);
+    // Typos like "knot s" usually differ only in spacing.
+    let squashed = unit.replace(' ', "");
+    if let Some(u) = KNOWN_UNITS.iter().find(|u| u.replace(' ', "") == squashed) {
+        return Some(u);
+    }
+
+    KNOWN_UNITS
+        .iter()
+        .map(|u| (levenshtein(&unit, u), *u))
+        .filter(|(d, u)| *d <= 2.max(u.len() / 4))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, u)| u)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_names_and_aliases() {
+        for unit in [
+            "knots",
+            "Feet Per Minute",
+            "degrees  latitude",
+            "degrees longitude",
+            "radians per second squared",
+            "pounds per gallon",
+            "slug feet squared",
+        ] {
+            assert!(is_known(unit), "{unit}");
+        }
+        assert!(!is_known("furlongs"));
+    }
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(suggest("knot s"), Some("knots"));
+        assert_eq!(suggest("feets"), Some("feet"));
+        assert_eq!(suggest("furlongs per fortnight"), None);
+    }
+}